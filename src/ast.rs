@@ -0,0 +1,547 @@
+use crate::source_map::Span;
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExprKind {
+    Literal(Literal),
+    Ident(String),
+    Unary {
+        op: UnaryOp,
+        operand: Box<Expr>,
+    },
+    BinaryOp {
+        left: Box<Expr>,
+        op: BinaryOp,
+        right: Box<Expr>,
+    },
+    Call {
+        callee: String,
+        args: Vec<Expr>,
+    },
+    If {
+        condition: Box<Expr>,
+        then_branch: Vec<Stmt>,
+        else_branch: Option<Vec<Stmt>>,
+    },
+    // "x = {x}": a szöveg- és kifejezésrészek sorrendben
+    Interpolated(Vec<InterpPart>),
+    StructLit {
+        name: String,
+        fields: Vec<FieldInit>,
+    },
+    Field {
+        object: Box<Expr>,
+        field: String,
+    },
+    // `p.hossz(...)`
+    MethodCall {
+        receiver: Box<Expr>,
+        method: String,
+        args: Vec<Expr>,
+    },
+    // `Pont::uj(...)`, `Alak::Kor(1.0)`
+    AssocCall {
+        type_name: String,
+        name: String,
+        args: Vec<Expr>,
+    },
+    // `Szin::Piros`: adat nélküli enum-változat
+    Path {
+        type_name: String,
+        name: String,
+    },
+    Tuple(Vec<Expr>),
+    Match {
+        scrutinee: Box<Expr>,
+        arms: Vec<MatchArm>,
+    },
+    // Utasításblokk (`eset` ágában), értéke `()`
+    Block(Vec<Stmt>),
+    // `a..b`, `a..=b`; lépésköz: `(a..b).lepes(2)`
+    Range {
+        start: Box<Expr>,
+        end: Box<Expr>,
+        inclusive: bool,
+    },
+    // `|x, y: i32| x + y`, `mozgat || { ... }`; a paraméterek típusa elhagyható
+    Closure {
+        params: Vec<Param>,
+        return_type: Option<TypeAnnot>,
+        body: Box<Expr>,
+        is_move: bool,
+    },
+    // `akkor letolt(url)`: a `Jovo<T>` bevárása `aszink` függvényben
+    Await(Box<Expr>),
+    // `[1, 2, 3]`: rögzített méretű tömb, de `Tomb<T>` vagy `[T]` helyén is állhat
+    Array(Vec<Expr>),
+    // `t[i]`; tartománnyal (`t[1..3]`) szeletet ad
+    Index {
+        object: Box<Expr>,
+        index: Box<Expr>,
+    },
+    // `x as u8`: ellenőrzött átalakítás primitív típusok között
+    Cast {
+        expr: Box<Expr>,
+        target: TypeAnnot,
+    },
+    // `f()?`: `Opcio::Nincs` vagy `Eredmeny::Hiba` esetén azonnal visszatér vele a függvényből
+    Try(Box<Expr>),
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Expr,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    pub kind: PatternKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum PatternKind {
+    Wildcard,
+    Binding(String),
+    // Literál minta (a negatív szám `Unary` kifejezésként)
+    Literal(Expr),
+    Range {
+        start: Expr,
+        end: Expr,
+        inclusive: bool,
+    },
+    Tuple(Vec<Pattern>),
+    // `Pont { x: 0.0, y }`, a `..` a többi mezőt elhagyja
+    Struct {
+        name: String,
+        fields: Vec<FieldPattern>,
+        rest: bool,
+    },
+    // `Alak::Kor(r)`, `Szin::Piros`
+    Variant {
+        enum_name: String,
+        variant: String,
+        fields: Vec<Pattern>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldPattern {
+    pub name: String,
+    pub pattern: Pattern,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldInit {
+    pub name: String,
+    pub value: Expr,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum InterpPart {
+    Text(String),
+    Expr(Expr),
+}
+
+#[derive(Debug, Clone)]
+pub enum Literal {
+    // Az utótag nélküli literál típusát a típusellenőrző határozza meg
+    Int(u128, Option<IntTy>),
+    Float(f64, Option<FloatTy>),
+    String(String),
+    ByteString(Vec<u8>),
+    Char(char),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntTy {
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+}
+
+impl IntTy {
+    pub fn from_name(name: &str) -> Option<IntTy> {
+        Some(match name {
+            "i8" => IntTy::I8,
+            "i16" => IntTy::I16,
+            "i32" => IntTy::I32,
+            "i64" => IntTy::I64,
+            "i128" => IntTy::I128,
+            "isize" => IntTy::Isize,
+            "u8" => IntTy::U8,
+            "u16" => IntTy::U16,
+            "u32" => IntTy::U32,
+            "u64" => IntTy::U64,
+            "u128" => IntTy::U128,
+            "usize" => IntTy::Usize,
+            _ => return None,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            IntTy::I8 => "i8",
+            IntTy::I16 => "i16",
+            IntTy::I32 => "i32",
+            IntTy::I64 => "i64",
+            IntTy::I128 => "i128",
+            IntTy::Isize => "isize",
+            IntTy::U8 => "u8",
+            IntTy::U16 => "u16",
+            IntTy::U32 => "u32",
+            IntTy::U64 => "u64",
+            IntTy::U128 => "u128",
+            IntTy::Usize => "usize",
+        }
+    }
+
+    // A célplatform 64 bites, így az isize/usize 64 bites
+    pub fn bits(&self) -> u32 {
+        match self {
+            IntTy::I8 | IntTy::U8 => 8,
+            IntTy::I16 | IntTy::U16 => 16,
+            IntTy::I32 | IntTy::U32 => 32,
+            IntTy::I64 | IntTy::U64 | IntTy::Isize | IntTy::Usize => 64,
+            IntTy::I128 | IntTy::U128 => 128,
+        }
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(self, IntTy::I8 | IntTy::I16 | IntTy::I32 | IntTy::I64 | IntTy::I128 | IntTy::Isize)
+    }
+
+    // A legnagyobb ábrázolható érték
+    pub fn max_value(&self) -> u128 {
+        if self.is_signed() {
+            (1u128 << (self.bits() - 1)) - 1
+        } else if self.bits() == 128 {
+            u128::MAX
+        } else {
+            (1u128 << self.bits()) - 1
+        }
+    }
+
+    // A legkisebb ábrázolható érték abszolút értéke
+    pub fn min_magnitude(&self) -> u128 {
+        if self.is_signed() {
+            1u128 << (self.bits() - 1)
+        } else {
+            0
+        }
+    }
+
+    // Az `other` minden értéke ábrázolható-e ebben a típusban (veszteségmentes átalakítás)
+    pub fn contains(&self, other: IntTy) -> bool {
+        self.max_value() >= other.max_value() && self.min_magnitude() >= other.min_magnitude()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloatTy {
+    F32,
+    F64,
+}
+
+impl FloatTy {
+    pub fn from_name(name: &str) -> Option<FloatTy> {
+        match name {
+            "f32" => Some(FloatTy::F32),
+            "f64" => Some(FloatTy::F64),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FloatTy::F32 => "f32",
+            FloatTy::F64 => "f64",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+    Neq,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone)]
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum StmtKind {
+    Expr(Expr),
+    // `var x = ...` (módosítható) vagy `legyen x = ...`
+    Let {
+        name: String,
+        mutable: bool,
+        type_annot: Option<TypeAnnot>,
+        value: Expr,
+    },
+    // `x = 1`, `p.x += 2`: összetett értékadásnál az `op` a művelet
+    Assign {
+        target: Expr,
+        op: Option<BinaryOp>,
+        value: Expr,
+    },
+    Return(Option<Expr>),
+    Const(Const),
+    While {
+        condition: Expr,
+        body: Vec<Stmt>,
+    },
+    // szor minta in kifejezés { ... }
+    For {
+        pattern: Box<Pattern>,
+        iterable: Expr,
+        body: Vec<Stmt>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: String,
+    pub type_annot: Option<TypeAnnot>,
+    pub span: Span,
+}
+
+// Függvénytípus jelölésének neve (kulcsszó, így típusnévvel nem ütközhet)
+pub const FN_TYPE: &str = "fv";
+// Tömb- és szelettípus jelölésének neve (`[T; N]`, `[T]`); az elemtípus az egyetlen argumentum
+pub const ARRAY_TYPE: &str = "[]";
+// A soha vissza nem térő függvény visszatérési típusa (`-> !`)
+pub const NEVER_TYPE: &str = "!";
+// Az érték nélküli `()` típus jelölése (pl. `Eredmeny<(), str>`)
+pub const UNIT_TYPE: &str = "()";
+
+// Típusjelölés: `i32`, `Lista<T>`, `dyn Kiirhato`, `fv(i32) -> bool`, `[u8; 4]`
+#[derive(Debug, Clone)]
+pub struct TypeAnnot {
+    pub name: String,
+    // Függvénytípusnál (`name == FN_TYPE`) a paraméterek típusai
+    pub args: Vec<TypeAnnot>,
+    // `dyn Trait`: a `name` a trait neve
+    pub is_dyn: bool,
+    // Függvénytípus visszatérési típusa; `None` esetén `()`
+    pub return_type: Option<Box<TypeAnnot>>,
+    // Rögzített méretű tömb hossza; `None` esetén (`name == ARRAY_TYPE` mellett) szelet
+    pub array_len: Option<u64>,
+    pub span: Span,
+}
+
+impl TypeAnnot {
+    pub fn named(name: &str, span: Span) -> Self {
+        Self {
+            name: name.to_string(),
+            args: Vec::new(),
+            is_dyn: false,
+            return_type: None,
+            array_len: None,
+            span,
+        }
+    }
+
+    pub fn is_fn(&self) -> bool {
+        self.name == FN_TYPE
+    }
+
+    pub fn is_array(&self) -> bool {
+        self.name == ARRAY_TYPE
+    }
+}
+
+impl fmt::Display for TypeAnnot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_fn() {
+            let params: Vec<String> = self.args.iter().map(|arg| arg.to_string()).collect();
+            write!(f, "fv({})", params.join(", "))?;
+            if let Some(ret) = &self.return_type {
+                write!(f, " -> {}", ret)?;
+            }
+            return Ok(());
+        }
+        if self.is_array() {
+            return match self.array_len {
+                Some(len) => write!(f, "[{}; {}]", self.args[0], len),
+                None => write!(f, "[{}]", self.args[0])
+            };
+        }
+        if self.is_dyn {
+            write!(f, "dyn ")?;
+        }
+        write!(f, "{}", self.name)?;
+        if !self.args.is_empty() {
+            let args: Vec<String> = self.args.iter().map(|arg| arg.to_string()).collect();
+            write!(f, "<{}>", args.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+// `T: Osszehasonlithato + Kiirhato`
+#[derive(Debug, Clone)]
+pub struct GenericParam {
+    pub name: String,
+    pub bounds: Vec<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: String,
+    pub is_pub: bool,
+    // `aszink fv`: a hívás `Jovo<T>`-t ad, a törzs állapotgépként fut
+    pub is_async: bool,
+    pub generics: Vec<GenericParam>,
+    pub params: Vec<Param>,
+    pub return_type: Option<TypeAnnot>,
+    pub body: Vec<Stmt>,
+    pub span: Span,
+}
+
+impl Function {
+    // Metódus-e (az impl blokk a `self` paraméter típusát a blokk típusára állítja)
+    pub fn takes_self(&self) -> bool {
+        self.params.first().is_some_and(|param| param.name == "self")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Impl {
+    pub generics: Vec<GenericParam>,
+    // `impl Kiirhato for Pont`
+    pub trait_name: Option<String>,
+    pub type_name: String,
+    // `impl<T> Lista<T>`: a típus argumentumai (a blokk generikus paraméterei)
+    pub type_args: Vec<TypeAnnot>,
+    pub methods: Vec<Function>,
+    pub span: Span,
+}
+
+// A trait metódusa; alapértelmezett törzs nélkül a `function.body` üres
+#[derive(Debug, Clone)]
+pub struct TraitMethod {
+    pub function: Function,
+    pub has_default: bool,
+}
+
+// `vonas Kiirhato { fv kiir(self) -> str; }`
+#[derive(Debug, Clone)]
+pub struct Trait {
+    pub name: String,
+    pub is_pub: bool,
+    pub methods: Vec<TraitMethod>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub name: String,
+    pub type_annot: TypeAnnot,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct Struct {
+    pub name: String,
+    pub is_pub: bool,
+    pub generics: Vec<GenericParam>,
+    pub fields: Vec<Field>,
+    pub span: Span,
+}
+
+// `Kor(f64)`; adat nélküli változatnál a mezőlista üres
+#[derive(Debug, Clone)]
+pub struct Variant {
+    pub name: String,
+    pub fields: Vec<TypeAnnot>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct Enum {
+    pub name: String,
+    pub is_pub: bool,
+    pub generics: Vec<GenericParam>,
+    pub variants: Vec<Variant>,
+    pub span: Span,
+}
+
+// `konst MERET: usize = 4 * 1024;` modulszinten vagy blokkban; az értéket a típusellenőrző
+// fordítási időben kiszámolja, így csak konstans kifejezés állhat itt
+#[derive(Debug, Clone)]
+pub struct Const {
+    pub name: String,
+    pub is_pub: bool,
+    pub type_annot: Option<TypeAnnot>,
+    pub value: Expr,
+    pub span: Span,
+}
+
+// `import std.memory;` (modul), `use std.memory.Lap as L;` (a modul egy eleme)
+#[derive(Debug, Clone)]
+pub struct Import {
+    pub path: Vec<String>,
+    pub alias: Option<String>,
+    pub is_item: bool,
+    pub span: Span,
+}
+
+impl Import {
+    // A név, amelyen az importált modul vagy elem a fájlban elérhető
+    pub fn local_name(&self) -> &str {
+        self.alias.as_deref().or(self.path.last().map(String::as_str)).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Program {
+    pub imports: Vec<Import>,
+    pub structs: Vec<Struct>,
+    pub enums: Vec<Enum>,
+    pub traits: Vec<Trait>,
+    pub impls: Vec<Impl>,
+    pub consts: Vec<Const>,
+    pub functions: Vec<Function>,
+}
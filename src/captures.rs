@@ -0,0 +1,177 @@
+use crate::ast::{ Const, Expr, ExprKind, InterpPart, Param, Pattern, PatternKind, Stmt, StmtKind };
+
+// A lezárás szabad nevei az első előfordulásuk sorrendjében: a törzsben hivatkozott, de
+// nem a lezárásban (paraméterként, `var`-ral vagy mintában) kötött nevek. Hogy ezek közül
+// melyik a környező függvény helyi változója (és így elkapandó), azt a típusellenőrző dönti el.
+pub fn free_variables(params: &[Param], body: &Expr) -> Vec<String> {
+    let mut collector = Collector {
+        scopes: vec![params.iter().map(|param| param.name.clone()).collect()],
+        free: Vec::new()
+    };
+    collector.expr(body);
+    collector.free
+}
+
+struct Collector {
+    // Hatókörönként a kötött nevek; a belső lezárások paraméterei is külön hatókört kapnak
+    scopes: Vec<Vec<String>>,
+    free: Vec<String>
+}
+
+impl Collector {
+    fn use_name(&mut self, name: &str) {
+        let bound = self.scopes.iter().any(|scope| scope.iter().any(|bound| bound == name));
+        if !bound && !self.free.iter().any(|free| free == name) {
+            self.free.push(name.to_string());
+        }
+    }
+
+    fn bind(&mut self, name: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(name.to_string());
+        }
+    }
+
+    fn with_scope(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.scopes.push(Vec::new());
+        visit(self);
+        self.scopes.pop();
+    }
+
+    fn block(&mut self, stmts: &[Stmt]) {
+        self.with_scope(|collector| {
+            for stmt in stmts {
+                collector.stmt(stmt);
+            }
+        });
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match &stmt.kind {
+            StmtKind::Expr(expr) => self.expr(expr),
+            // Az érték még a kötés előtt értékelődik ki: `var x = x + 1` a külső `x`-re hivatkozik
+            StmtKind::Let { name, value, .. } | StmtKind::Const(Const { name, value, .. }) => {
+                self.expr(value);
+                self.bind(name);
+            },
+            // A módosított külső változót is el kell kapni
+            StmtKind::Assign { target, value, .. } => {
+                self.expr(target);
+                self.expr(value);
+            },
+            StmtKind::Return(expr) => {
+                if let Some(expr) = expr {
+                    self.expr(expr);
+                }
+            },
+            StmtKind::While { condition, body } => {
+                self.expr(condition);
+                self.block(body);
+            },
+            StmtKind::For { pattern, iterable, body } => {
+                self.expr(iterable);
+                self.with_scope(|collector| {
+                    collector.pattern(pattern);
+                    for stmt in body {
+                        collector.stmt(stmt);
+                    }
+                });
+            }
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) {
+        match &pattern.kind {
+            PatternKind::Binding(name) => self.bind(name),
+            PatternKind::Tuple(elements) | PatternKind::Variant { fields: elements, .. } => {
+                for element in elements {
+                    self.pattern(element);
+                }
+            },
+            PatternKind::Struct { fields, .. } => {
+                for field in fields {
+                    self.pattern(&field.pattern);
+                }
+            },
+            PatternKind::Wildcard | PatternKind::Literal(_) | PatternKind::Range { .. } => {}
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Literal(_) | ExprKind::Path { .. } => {},
+            ExprKind::Ident(name) => self.use_name(name),
+            ExprKind::Unary { operand, .. } | ExprKind::Await(operand) | ExprKind::Try(operand) | ExprKind::Cast { expr: operand, .. } => self.expr(operand),
+            ExprKind::BinaryOp { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            },
+            // A hívott név is lehet elkapott lezárás
+            ExprKind::Call { callee, args } => {
+                self.use_name(callee);
+                self.exprs(args);
+            },
+            ExprKind::If { condition, then_branch, else_branch } => {
+                self.expr(condition);
+                self.block(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.block(else_branch);
+                }
+            },
+            ExprKind::Interpolated(parts) => {
+                for part in parts {
+                    if let InterpPart::Expr(expr) = part {
+                        self.expr(expr);
+                    }
+                }
+            },
+            ExprKind::StructLit { fields, .. } => {
+                for field in fields {
+                    self.expr(&field.value);
+                }
+            },
+            ExprKind::Field { object, .. } => self.expr(object),
+            ExprKind::MethodCall { receiver, args, .. } => {
+                self.expr(receiver);
+                self.exprs(args);
+            },
+            ExprKind::AssocCall { args, .. } | ExprKind::Tuple(args) | ExprKind::Array(args) => self.exprs(args),
+            ExprKind::Index { object, index } => {
+                self.expr(object);
+                self.expr(index);
+            },
+            ExprKind::Match { scrutinee, arms } => {
+                self.expr(scrutinee);
+                for arm in arms {
+                    self.with_scope(|collector| {
+                        collector.pattern(&arm.pattern);
+                        if let Some(guard) = &arm.guard {
+                            collector.expr(guard);
+                        }
+                        collector.expr(&arm.body);
+                    });
+                }
+            },
+            ExprKind::Block(stmts) => self.block(stmts),
+            ExprKind::Range { start, end, .. } => {
+                self.expr(start);
+                self.expr(end);
+            },
+            // A belső lezárás szabad nevei a külsőnek is szabad nevei (neki is el kell kapnia őket)
+            ExprKind::Closure { params, body, .. } => {
+                self.with_scope(|collector| {
+                    for param in params {
+                        collector.bind(&param.name);
+                    }
+                    collector.expr(body);
+                });
+            }
+        }
+    }
+
+    fn exprs(&mut self, exprs: &[Expr]) {
+        for expr in exprs {
+            self.expr(expr);
+        }
+    }
+}
@@ -1,30 +1,277 @@
-use inkwell::{context::Context, module::Module, builder::Builder};
-use crate::ast::{Program, Stmt, Expr, Literal, BinaryOp};
+use inkwell::{context::Context, module::Module, builder::Builder, AddressSpace, FloatPredicate, IntPredicate};
+use inkwell::basic_block::BasicBlock;
+use inkwell::targets::TargetData;
+use inkwell::types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FloatType, FunctionType, IntType, PointerType, StructType};
+use inkwell::values::{BasicMetadataValueEnum, BasicValue, BasicValueEnum, CallableValue, FloatValue, FunctionValue, IntValue, PointerValue};
+use crate::ast::{
+    Program, Const, Function, Impl, Struct, Enum, Trait, Stmt, StmtKind, Expr, ExprKind, InterpPart, Literal, MatchArm, Pattern, PatternKind,
+    BinaryOp, UnaryOp, IntTy, FloatTy, GenericParam, Param, TypeAnnot, NEVER_TYPE
+};
+use crate::consteval::ConstValue;
+use crate::diagnostic::{codes, Diagnostic};
+use crate::source_map::Span;
+use crate::typechecker::{
+    array_element, future_type, primitive_type, Captures, Coercions, ConstValues, Instantiations, Type, TypeTable, ARRAY_LEN, ARRAY_PUSH,
+    BUILTIN_BLOCK_ON, BUILTIN_EXIT, BUILTIN_JOIN, BUILTIN_LOAD_FILE, BUILTIN_PANIC, BUILTIN_READ_FILE, BUILTIN_READ_LINE, BUILTIN_SLEEP, BUILTIN_SPAWN,
+    BUILTIN_WRITE_FILE, DISPLAY_METHOD, ITER_COUNT, ITER_GET, OPTION_NONE, OPTION_SOME, OPTION_TYPE, ORD_METHOD, RANGE_STEP, RESULT_ERR, RESULT_OK, SELF_TYPE,
+    THREAD_TYPE
+};
+use std::collections::HashMap;
+
+fn codegen_error(message: impl Into<String>) -> Diagnostic {
+    Diagnostic::error(message).with_code(codes::CODEGEN)
+}
+
+// Metódusnév Itanium-szerű kódolása: `Pont::hossz` -> `_ZN4Pont5hosszE`
+fn mangle_method(type_name: &str, method: &str) -> String {
+    format!("_ZN{}{}{}{}E", type_name.len(), type_name, method.len(), method)
+}
+
+// Generikus példány neve: `max<i32>`, `Lista<str>` (a típus nevével azonos alakban)
+fn instance_name(name: &str, args: &[Type]) -> String {
+    if args.is_empty() {
+        return name.to_string();
+    }
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    format!("{}<{}>", name, args.join(", "))
+}
+
+// Az impl blokk típusa a blokk generikus paramétereivel (`Lista<T>`)
+fn impl_self_type(block: &Impl) -> TypeAnnot {
+    TypeAnnot { args: block.type_args.clone(), ..TypeAnnot::named(&block.type_name, block.span) }
+}
+
+fn type_subst(generics: &[GenericParam], args: &[Type]) -> HashMap<String, Type> {
+    generics.iter().map(|generic| generic.name.clone()).zip(args.iter().cloned()).collect()
+}
+
+// A niche-ként kódolt változat sorszáma az adat nélküli változatok között
+fn niche_rank(index: usize, dataful: usize) -> u64 {
+    if index < dataful { index as u64 } else { index as u64 - 1 }
+}
+
+// Áll-e `akkor` a kifejezésben (a lezárások törzse sosem aszinkron, azt nem nézzük)
+fn contains_await(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Await(_) => true,
+        ExprKind::Literal(_) | ExprKind::Ident(_) | ExprKind::Path { .. } | ExprKind::Closure { .. } => false,
+        ExprKind::Unary { operand, .. } | ExprKind::Cast { expr: operand, .. } | ExprKind::Try(operand) => contains_await(operand),
+        ExprKind::Field { object, .. } => contains_await(object),
+        ExprKind::BinaryOp { left, right, .. } | ExprKind::Range { start: left, end: right, .. } | ExprKind::Index { object: left, index: right } => {
+            contains_await(left) || contains_await(right)
+        },
+        ExprKind::Call { args, .. } | ExprKind::AssocCall { args, .. } | ExprKind::Tuple(args) | ExprKind::Array(args) => args.iter().any(contains_await),
+        ExprKind::MethodCall { receiver, args, .. } => contains_await(receiver) || args.iter().any(contains_await),
+        ExprKind::StructLit { fields, .. } => fields.iter().any(|init| contains_await(&init.value)),
+        ExprKind::Interpolated(parts) => parts.iter().any(|part| matches!(part, InterpPart::Expr(expr) if contains_await(expr))),
+        ExprKind::If { condition, then_branch, else_branch } => {
+            contains_await(condition) || block_contains_await(then_branch) || else_branch.as_deref().is_some_and(block_contains_await)
+        },
+        ExprKind::Match { scrutinee, arms } => {
+            contains_await(scrutinee) || arms.iter().any(|arm| arm.guard.as_ref().is_some_and(contains_await) || contains_await(&arm.body))
+        },
+        ExprKind::Block(stmts) => block_contains_await(stmts)
+    }
+}
+
+fn block_contains_await(stmts: &[Stmt]) -> bool {
+    stmts.iter().any(|stmt| match &stmt.kind {
+        StmtKind::Expr(value) | StmtKind::Let { value, .. } | StmtKind::Return(Some(value)) => contains_await(value),
+        StmtKind::Assign { target, value, .. } => contains_await(target) || contains_await(value),
+        StmtKind::While { condition, body } => contains_await(condition) || block_contains_await(body),
+        StmtKind::For { iterable, body, .. } => contains_await(iterable) || block_contains_await(body),
+        StmtKind::Return(None) | StmtKind::Const(_) => false
+    })
+}
+
+// A sorok `column` oszlopának mintája helyére a részmintái kerülnek: a `_` `count` darab `_`-ra
+// bomlik, a `subpatterns` által elutasított (másik változatú) sor kiesik
+fn specialize_rows<'p, 'ctx>(
+    rows: &[MatchRow<'p, 'ctx>],
+    column: usize,
+    count: usize,
+    wildcard: &'p Pattern,
+    mut subpatterns: impl FnMut(&'p Pattern) -> Option<Vec<&'p Pattern>>
+) -> Vec<MatchRow<'p, 'ctx>> {
+    rows.iter()
+        .filter_map(|row| {
+            let replacement = match row.patterns[column].kind {
+                PatternKind::Wildcard => vec![wildcard; count],
+                _ => subpatterns(row.patterns[column])?
+            };
+            let mut row = row.clone();
+            row.patterns.splice(column..=column, replacement);
+            Some(row)
+        })
+        .collect()
+}
+
+// Az oszlop értéke helyére a részértékei kerülnek
+fn replace_column<'ctx>(occurrences: &[BasicValueEnum<'ctx>], column: usize, fields: Vec<BasicValueEnum<'ctx>>) -> Vec<BasicValueEnum<'ctx>> {
+    let mut occurrences = occurrences.to_vec();
+    occurrences.splice(column..=column, fields);
+    occurrences
+}
+
+// Ennél mélyebb példánylánc (`f<T>` törzse `f<[T; 1]>`-et kéri, az `f<[[T; 1]; 1]>`-et, ...) nem ér véget
+const INSTANCE_DEPTH_LIMIT: usize = 64;
+
+// x86-64 (a célplatform 64 bites, lásd `IntTy::bits`)
+const DATA_LAYOUT: &str = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128";
+
+// Az enum ábrázolása; maga az enum mindig névvel ellátott LLVM struct
+#[derive(Clone, Copy)]
+enum EnumLayout<'ctx> {
+    // Legfeljebb egy változat: csak az adatmezők, tag nélkül
+    Single,
+    // Csak adat nélküli változatok: `{ tag }`
+    Tag(IntType<'ctx>),
+    // Egyetlen adatos, egymezős változat; a többi változatot a mező érvénytelen értékei
+    // (niche) kódolják `start`-tól kezdve: `str`-nél a null pointer, `char`-nál a 0x10FFFF fölöttiek
+    Niche { dataful: usize, start: u64 },
+    // Általános eset: `{ tag, [n x iN] }`, az adatterület a legnagyobb változat méretű
+    Tagged(IntType<'ctx>)
+}
+
+struct EnumInfo<'ctx> {
+    layout: EnumLayout<'ctx>,
+    // változatonként a név és az adatmezők structja
+    variants: Vec<(String, StructType<'ctx>)>
+}
+
+// Az állapotgép keretének fejléce, a runtime `DlangFuture` struktúrájával azonos elrendezésben:
+// a `poll` függvény, az eredmény címe, a keretet felszabadító függvény, majd az állapot
+const FRAME_POLL: u64 = 0;
+const FRAME_RESULT: u64 = 8;
+const FRAME_DROP: u64 = 16;
+const FRAME_STATE: u64 = 24;
+const FRAME_HEADER_SIZE: u64 = 28;
+// A befejeződött állapotgép állapota; a 0 a kezdőállapot, az `n` az `n`-edik `akkor`
+const STATE_DONE: u64 = u32::MAX as u64;
+
+// `aszink` függvény állapotgépe fordítás közben. A függvény helyi változói a heapen lévő
+// keretben kapnak helyet (lásd `build_local`), így túlélik a felfüggesztést.
+struct AsyncFrame<'ctx> {
+    // A `poll` függvény paramétere (`i8*`)
+    frame: PointerValue<'ctx>,
+    // A keretmezők címszámítása ide kerül, így minden folytatási pontot dominál
+    slots_block: BasicBlock<'ctx>,
+    size: u64,
+    result: PointerValue<'ctx>,
+    // A folytatási pontok: az `n`-edik állapotban a bevárást újra megkísérlő blokk
+    resume_points: Vec<BasicBlock<'ctx>>,
+}
+
+// A döntési fa egy sora: a vizsgálandó oszlopok mintái, a már kötött nevek és az `eset` ága
+#[derive(Clone)]
+struct MatchRow<'p, 'ctx> {
+    patterns: Vec<&'p Pattern>,
+    bindings: Vec<(String, BasicValueEnum<'ctx>)>,
+    arm: usize
+}
+
+// Az `eset` ágai a döntési fa építése közben: a törzsek blokkjai, a kötések helyei és hogy
+// melyik ágat érte el a fa
+struct MatchArms<'p, 'ctx> {
+    arms: &'p [MatchArm],
+    blocks: Vec<BasicBlock<'ctx>>,
+    slots: Vec<Vec<(String, PointerValue<'ctx>)>>,
+    reached: Vec<bool>,
+    wildcard: &'p Pattern
+}
+
+// Generikus függvény vagy metódus; a példányai típusargumentumonként külön fordulnak
+struct Template {
+    // előbb az impl blokk, majd a függvény saját paraméterei
+    generics: Vec<String>,
+    impl_generics: usize,
+    // Metódusnál az impl blokk típusa, a `Self` értéke
+    self_type: Option<TypeAnnot>,
+    function: Function
+}
 
 pub struct CodeGen<'ctx> {
     context: &'ctx Context,
     module: Module<'ctx>,
-    builder: Builder<'ctx>
+    builder: Builder<'ctx>,
+    expr_types: TypeTable,
+    instantiations: Instantiations,
+    coercions: Coercions,
+    captures: Captures,
+    const_values: ConstValues,
+    // Modulszintű konstansok: skalár és string azonnali értékként, tuple az LLVM globális címeként
+    constants: HashMap<String, (BasicValueEnum<'ctx>, bool)>,
+    // A lezárásfüggvények (`lezaras.N`) sorszámozásához
+    closure_count: usize,
+    // A fordítás alatt álló példány típusparamétereinek értéke
+    type_args: HashMap<String, Type>,
+    struct_defs: HashMap<String, Struct>,
+    enum_defs: HashMap<String, Enum>,
+    trait_defs: HashMap<String, Trait>,
+    // struct neve -> mezőnevek az LLVM struct mezőinek sorrendjében
+    struct_fields: HashMap<String, Vec<String>>,
+    // az enum LLVM típusneve (`Talan<i32>`) -> elrendezés
+    enums: HashMap<String, EnumInfo<'ctx>>,
+    target_data: TargetData,
+    templates: HashMap<String, Template>,
+    // Deklarált, de még nem fordított példányok, a kérő példányok láncának hosszával
+    pending: Vec<(String, Function, HashMap<String, Type>, usize)>,
+    // A fordítás alatt álló példány mélysége (a nem generikus függvényeké 0)
+    instance_depth: usize,
+    // Az aktuális függvény változóinak (és paramétereinek) stack-helye
+    variables: HashMap<String, PointerValue<'ctx>>,
+    // `aszink` függvény fordítása közben az állapotgép kerete
+    async_frame: Option<AsyncFrame<'ctx>>,
+    // Az aktuális függvény (aszinkronnál a jövő kimenetének) típusa; a `?` ennek a hibaváltozatával tér vissza
+    return_type: Option<Type>
 }
 
 impl<'ctx> CodeGen<'ctx> {
-    fn new(context: &'ctx Context) -> Self {
+    pub fn new(
+        context: &'ctx Context,
+        expr_types: TypeTable,
+        instantiations: Instantiations,
+        coercions: Coercions,
+        captures: Captures,
+        const_values: ConstValues
+    ) -> Self {
         let module = context.create_module("dlang");
         let builder = context.create_builder();
-        let codegen = Self { context, module, builder };
+        let target_data = TargetData::create(DATA_LAYOUT);
+        module.set_data_layout(&target_data.get_data_layout());
+        let codegen = Self {
+            context,
+            module,
+            builder,
+            expr_types,
+            instantiations,
+            coercions,
+            captures,
+            const_values,
+            constants: HashMap::new(),
+            closure_count: 0,
+            type_args: HashMap::new(),
+            struct_defs: HashMap::new(),
+            enum_defs: HashMap::new(),
+            trait_defs: HashMap::new(),
+            struct_fields: HashMap::new(),
+            enums: HashMap::new(),
+            target_data,
+            templates: HashMap::new(),
+            pending: Vec::new(),
+            instance_depth: 0,
+            variables: HashMap::new(),
+            async_frame: None,
+            return_type: None
+        };
 
         let fn_type = context.void_type().fn_type(&[context.i8_type().ptr_type(..).into()], false);
         codegen.module.add_function("print", fn_type, None);
+        codegen
     }
     
-    pub fn compile(&mut self, program: &Program) -> Result<(), String> {
-        for fv in &program.functions {
-            self.declare_functions(fv)?;
-        }
-        
-        for fv in &program.functions {
-            self.define_function(fv)?;
-        }
+    pub fn compile(&mut self, program: &Program) -> Result<(), Diagnostic> {
+        self.generate(program)?;
 
         let pass_manager_builder = inkwell::passes::PassManagerBuilder::create();
         pass_manager_builder.set_optimalization_level(inkwell::OptimizationLevel::Aggressive);
@@ -32,61 +279,799 @@ impl<'ctx> CodeGen<'ctx> {
         pass_manager_builder.populate_module_pass_manager(&module_pass_manager);
         module_pass_manager.run_on(&self.module);
         
-        self.module.print_to_file("output.ll").map_err(|e| e.to_string())?;
+        self.module.print_to_file("output.ll").map_err(|e| codegen_error(e.to_string()))?;
         Ok(())
     }
-    
-    fn declare_function(&self, fv: &Function) -> Result<(), String> {
-        let return_type = match fv.return_type.as_deref() {
-            Some("i32") => self.context.i32_type(),
-            Some("f64") => self.context.f64_type(),
-            Some("bool") => self.context.bool_type(),
-            Some("str") => self.context.i8_type().ptr_type(inkwell::AddressSpace::Generic),
-            None => self.context.void_type(),
-            _ => return Err(format!("Unknown return type: {:?}", fv.return_type))
-        };
+
+    // A program optimalizálatlan IR-je; a hibás modul kódgenerálási hiba, nem az LLVM összeomlása
+    fn generate(&mut self, program: &Program) -> Result<(), Diagnostic> {
+        for def in &program.structs {
+            let names = def.fields.iter().map(|field| field.name.clone()).collect();
+            self.struct_fields.insert(def.name.clone(), names);
+            self.struct_defs.insert(def.name.clone(), def.clone());
+        }
+        for def in &program.enums {
+            self.enum_defs.insert(def.name.clone(), def.clone());
+        }
+        for def in &program.traits {
+            self.trait_defs.insert(def.name.clone(), def.clone());
+        }
+        // A nem generikus típusok azonnal, a generikusak példányonként, az első használatkor jönnek létre
+        for def in program.structs.iter().filter(|def| def.generics.is_empty()) {
+            self.llvm_type(&Type::Struct(def.name.clone(), Vec::new()))?;
+        }
+        for def in program.enums.iter().filter(|def| def.generics.is_empty()) {
+            self.llvm_type(&Type::Enum(def.name.clone(), Vec::new()))?;
+        }
+
+        for def in &program.consts {
+            self.declare_constant(def)?;
+        }
+
+        for block in &program.impls {
+            let impl_generics: Vec<String> = block.generics.iter().map(|generic| generic.name.clone()).collect();
+            let self_type = impl_self_type(block);
+            for method in self.impl_methods(block) {
+                let name = mangle_method(&block.type_name, &method.name);
+                if impl_generics.is_empty() && method.generics.is_empty() {
+                    self.type_args = HashMap::from([(SELF_TYPE.to_string(), self.annot_type(&self_type, &HashMap::new())?)]);
+                    self.declare_function(&name, &method)?;
+                    self.type_args.clear();
+                } else {
+                    self.add_template(name, &impl_generics, Some(self_type.clone()), &method);
+                }
+            }
+        }
+        for fv in &program.functions {
+            if fv.generics.is_empty() {
+                self.declare_function(&fv.name, fv)?;
+            } else {
+                self.add_template(fv.name.clone(), &[], None, fv);
+            }
+        }
         
-        let param_type: Vec<_> = fv.params.iter()
-            .map(|(_, typ)| self.parse_type(typ)).collect::<Result<Vec<_>, _>>()?;
+        for block in &program.impls {
+            self.define_methods(block)?;
+        }
+        for fv in program.functions.iter().filter(|fv| fv.generics.is_empty()) {
+            self.define_function(&fv.name, fv)?;
+        }
+        // A példányok törzse újabb példányokat kérhet, ezért addig fordítunk, amíg van függő
+        while let Some((name, fv, type_args, depth)) = self.pending.pop() {
+            self.type_args = type_args;
+            self.instance_depth = depth;
+            self.define_function(&name, &fv)?;
+        }
+        self.type_args.clear();
+        self.instance_depth = 0;
+        self.module.verify().map_err(|e| codegen_error(format!("invalid LLVM IR generated: {}", e)))
+    }
+
+    // A skalár konstans a használat helyén azonnali értékként jelenik meg; a tuple egyetlen
+    // (nem írható) globálisba kerül, amelyet a használat betölt
+    fn declare_constant(&mut self, def: &Const) -> Result<(), Diagnostic> {
+        let value = self.constant_value(def)?;
+        let constant = if value.is_struct_value() || value.is_array_value() {
+            let global = self.module.add_global(value.get_type(), None, &format!("konst.{}", def.name));
+            global.set_initializer(&value);
+            global.set_constant(true);
+            (global.as_pointer_value().into(), true)
+        } else {
+            (value, false)
+        };
+        self.constants.insert(def.name.clone(), constant);
+        Ok(())
+    }
+
+    // A típusellenőrző által kiszámolt érték LLVM konstansként
+    fn constant_value(&mut self, def: &Const) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let value = self.const_values.get(&def.span).cloned()
+            .ok_or_else(|| codegen_error(format!("constant `{}` was not evaluated", def.name)))?;
+        let ty = self.expr_type(def.value.span)
+            .ok_or_else(|| codegen_error(format!("constant `{}` has no type", def.name)))?;
+        self.build_constant(&value, &ty)
+    }
+
+    fn build_constant(&mut self, value: &ConstValue, ty: &Type) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        match (value, ty) {
+            (ConstValue::Int(_) | ConstValue::UInt(_), Type::Int(int_ty)) => {
+                let bits = value.bits().unwrap_or_default();
+                let words = [bits as u64, (bits >> 64) as u64];
+                Ok(self.int_type(*int_ty).const_int_arbitrary_precision(&words).into())
+            },
+            (ConstValue::Float(n), Type::Float(FloatTy::F32)) => Ok(self.context.f32_type().const_float(*n).into()),
+            (ConstValue::Float(n), _) => Ok(self.context.f64_type().const_float(*n).into()),
+            (ConstValue::Bool(b), _) => Ok(self.context.bool_type().const_int(*b as u64, false).into()),
+            (ConstValue::Char(c), _) => Ok(self.context.i32_type().const_int(*c as u64, false).into()),
+            // Null-terminált globális, mint a string literáloknál
+            (ConstValue::String(s), _) => {
+                let bytes = self.context.const_string(s.as_bytes(), true);
+                let global = self.module.add_global(bytes.get_type(), None, "konst.str");
+                global.set_initializer(&bytes);
+                global.set_constant(true);
+                Ok(global.as_pointer_value().const_cast(self.ptr_type()).into())
+            },
+            (ConstValue::Tuple(elements), Type::Tuple(types)) => {
+                let fields = elements.iter().zip(types)
+                    .map(|(element, ty)| self.build_constant(element, ty))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(self.llvm_type(ty)?.into_struct_type().const_named_struct(&fields).into())
+            },
+            (ConstValue::Array(elements), Type::Array(element, _)) => {
+                let values = elements.iter()
+                    .map(|value| self.build_constant(value, element))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(match self.llvm_type(element)? {
+                    BasicTypeEnum::IntType(ty) => ty.const_array(&values.iter().map(|v| v.into_int_value()).collect::<Vec<_>>()),
+                    BasicTypeEnum::FloatType(ty) => ty.const_array(&values.iter().map(|v| v.into_float_value()).collect::<Vec<_>>()),
+                    BasicTypeEnum::PointerType(ty) => ty.const_array(&values.iter().map(|v| v.into_pointer_value()).collect::<Vec<_>>()),
+                    BasicTypeEnum::StructType(ty) => ty.const_array(&values.iter().map(|v| v.into_struct_value()).collect::<Vec<_>>()),
+                    BasicTypeEnum::ArrayType(ty) => ty.const_array(&values.iter().map(|v| v.into_array_value()).collect::<Vec<_>>()),
+                    BasicTypeEnum::VectorType(_) => return Err(codegen_error(format!("constant of type `{}` cannot be emitted", ty)))
+                }.into())
+            },
+            _ => Err(codegen_error(format!("constant of type `{}` cannot be emitted", ty)))
+        }
+    }
+
+    // Struct vagy enum (példány) névvel ellátott LLVM típusa, az első kéréskor létrehozva
+    fn named_type(&mut self, ty: &Type) -> Result<StructType<'ctx>, Diagnostic> {
+        let key = ty.to_string();
+        if let Some(existing) = self.context.get_struct_type(&key) {
+            return Ok(existing);
+        }
+        let named = self.context.opaque_struct_type(&key);
+        match ty {
+            Type::Struct(name, args) => {
+                let field_types = {
+                    let def = &self.struct_defs[name];
+                    let subst = type_subst(&def.generics, args);
+                    def.fields.iter()
+                        .map(|field| self.annot_type(&field.type_annot, &subst))
+                        .collect::<Result<Vec<_>, _>>()?
+                };
+                let fields = self.sized_types(&key, &field_types)?;
+                named.set_body(&fields, false);
+            },
+            Type::Enum(name, args) => {
+                let (variant_names, variant_types) = {
+                    let def = &self.enum_defs[name];
+                    let subst = type_subst(&def.generics, args);
+                    let types = def.variants.iter()
+                        .map(|variant| variant.fields.iter().map(|field| self.annot_type(field, &subst)).collect())
+                        .collect::<Result<Vec<Vec<Type>>, _>>()?;
+                    (def.variants.iter().map(|variant| variant.name.clone()).collect::<Vec<_>>(), types)
+                };
+                let field_types = variant_types.iter()
+                    .map(|types| self.sized_types(&key, types))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.define_enum(&key, named, variant_names, &variant_types, field_types);
+            },
+            _ => unreachable!()
+        }
+        Ok(named)
+    }
+
+    // Mezőtípusok; a még törzs nélküli (önmagát tartalmazó) típus mérete végtelen lenne
+    fn sized_types(&mut self, owner: &str, types: &[Type]) -> Result<Vec<BasicTypeEnum<'ctx>>, Diagnostic> {
+        let fields = types.iter().map(|ty| self.llvm_type(ty)).collect::<Result<Vec<_>, _>>()?;
+        if fields.iter().any(|field| !field.is_sized()) {
+            return Err(codegen_error(format!("recursive type `{}` has infinite size", owner)));
+        }
+        Ok(fields)
+    }
+
+    fn define_enum(
+        &mut self,
+        key: &str,
+        enum_type: StructType<'ctx>,
+        names: Vec<String>,
+        variant_types: &[Vec<Type>],
+        field_types: Vec<Vec<BasicTypeEnum<'ctx>>>
+    ) {
+        let variants: Vec<(String, StructType<'ctx>)> = names.into_iter()
+            .zip(&field_types)
+            .map(|(name, fields)| (name, self.context.struct_type(fields, false)))
+            .collect();
+        let layout = self.enum_layout(variant_types);
+
+        let body: Vec<BasicTypeEnum<'ctx>> = match layout {
+            EnumLayout::Single => field_types.first().cloned().unwrap_or_default(),
+            EnumLayout::Tag(tag) => vec![tag.into()],
+            EnumLayout::Niche { dataful, .. } => vec![field_types[dataful][0]],
+            EnumLayout::Tagged(tag) => {
+                let size = variants.iter().map(|(_, payload)| self.target_data.get_abi_size(payload)).max().unwrap_or(0);
+                let align = variants.iter().map(|(_, payload)| self.target_data.get_abi_alignment(payload)).max().unwrap_or(1);
+                // Az adatterület szavai a legszigorúbb igazítású változathoz igazodnak
+                let word = self.context.custom_width_int_type(align * 8);
+                let words = size.div_ceil(align as u64) as u32;
+                vec![tag.into(), word.array_type(words).into()]
+            }
+        };
+        enum_type.set_body(&body, false);
+        self.enums.insert(key.to_string(), EnumInfo { layout, variants });
+    }
+
+    // A változatok (már helyettesített) mezőtípusai alapján
+    fn enum_layout(&self, variants: &[Vec<Type>]) -> EnumLayout<'ctx> {
+        if variants.len() <= 1 {
+            return EnumLayout::Single;
+        }
+        let tag = match variants.len() {
+            0..=0x100 => self.context.i8_type(),
+            0x101..=0x10000 => self.context.i16_type(),
+            _ => self.context.i32_type()
+        };
+
+        let dataful: Vec<usize> = variants.iter()
+            .enumerate()
+            .filter(|(_, fields)| !fields.is_empty())
+            .map(|(i, _)| i)
+            .collect();
+        let niches = variants.len() as u64 - 1;
+        match dataful.as_slice() {
+            [] => EnumLayout::Tag(tag),
+            [i] => match variants[*i].as_slice() {
+                [Type::String] if niches == 1 => EnumLayout::Niche { dataful: *i, start: 0 },
+                [Type::Char] if niches <= u32::MAX as u64 - 0x10FFFF => EnumLayout::Niche { dataful: *i, start: 0x110000 },
+                _ => EnumLayout::Tagged(tag)
+            },
+            _ => EnumLayout::Tagged(tag)
+        }
+    }
+
+    // A `Tipus::nev` kifejezés (vagy minta) enum-változat-e: az enum LLVM típusneve és a változat indexe
+    fn variant_at(&mut self, span: Span, type_name: &str, variant: &str) -> Result<Option<(String, usize)>, Diagnostic> {
+        match self.expr_type(span) {
+            Some(ty) if matches!(&ty, Type::Enum(name, _) if name == type_name) => {
+                self.llvm_type(&ty)?;
+                let key = ty.to_string();
+                Ok(self.variant_index(&key, variant).map(|index| (key, index)))
+            },
+            _ => Ok(None)
+        }
+    }
+
+    fn variant_index(&self, enum_name: &str, variant: &str) -> Option<usize> {
+        self.enums.get(enum_name).and_then(|info| info.variants.iter().position(|(name, _)| name == variant))
+    }
+
+    fn build_variant(&mut self, enum_name: &str, index: usize, fields: &[BasicValueEnum<'ctx>]) -> BasicValueEnum<'ctx> {
+        let info = &self.enums[enum_name];
+        let layout = info.layout;
+        let payload_type = info.variants[index].1;
+        let enum_type = self.context.get_struct_type(enum_name).unwrap();
+
+        let mut value = enum_type.get_undef();
+        match layout {
+            EnumLayout::Single => {
+                for (i, field) in fields.iter().enumerate() {
+                    value = self.builder.build_insert_value(value, *field, i as u32, "variant").unwrap().into_struct_value();
+                }
+            },
+            EnumLayout::Tag(tag) => {
+                let tag_value = tag.const_int(index as u64, false);
+                value = self.builder.build_insert_value(value, tag_value, 0, "variant").unwrap().into_struct_value();
+            },
+            EnumLayout::Niche { dataful, start } => {
+                let field = if index == dataful {
+                    fields[0]
+                } else {
+                    match enum_type.get_field_type_at_index(0).unwrap() {
+                        BasicTypeEnum::PointerType(ptr) => ptr.const_null().into(),
+                        other => other.into_int_type().const_int(start + niche_rank(index, dataful), false).into()
+                    }
+                };
+                value = self.builder.build_insert_value(value, field, 0, "variant").unwrap().into_struct_value();
+            },
+            EnumLayout::Tagged(tag) => {
+                let slot = self.build_entry_alloca(enum_type.into(), "variant");
+                let tag_ptr = self.builder.build_struct_gep(slot, 0, "variant.tag").unwrap();
+                self.builder.build_store(tag_ptr, tag.const_int(index as u64, false));
+                if !fields.is_empty() {
+                    let mut payload = payload_type.get_undef();
+                    for (i, field) in fields.iter().enumerate() {
+                        payload = self.builder.build_insert_value(payload, *field, i as u32, "payload").unwrap().into_struct_value();
+                    }
+                    let data_ptr = self.builder.build_struct_gep(slot, 1, "variant.data").unwrap();
+                    let payload_ptr = self.builder.build_pointer_cast(data_ptr, payload_type.ptr_type(AddressSpace::Generic), "variant.payload");
+                    self.builder.build_store(payload_ptr, payload);
+                }
+                return self.builder.build_load(slot, "variant");
+            }
+        }
+        value.into()
+    }
+
+    // i1: az enum-érték az `index`-edik változat-e
+    fn build_variant_test(&mut self, enum_name: &str, value: BasicValueEnum<'ctx>, index: usize) -> IntValue<'ctx> {
+        let info = &self.enums[enum_name];
+        let layout = info.layout;
+        let niches = info.variants.len() as u64 - 1;
+        let value = value.into_struct_value();
+
+        match layout {
+            EnumLayout::Single => self.context.bool_type().const_int(1, false),
+            EnumLayout::Tag(tag) | EnumLayout::Tagged(tag) => {
+                let tag_value = self.builder.build_extract_value(value, 0, "tag").unwrap().into_int_value();
+                self.builder.build_int_compare(IntPredicate::EQ, tag_value, tag.const_int(index as u64, false), "is_variant")
+            },
+            EnumLayout::Niche { dataful, start } => {
+                let field = match self.builder.build_extract_value(value, 0, "niche").unwrap() {
+                    BasicValueEnum::PointerValue(ptr) => self.builder.build_ptr_to_int(ptr, self.context.i64_type(), "niche"),
+                    other => other.into_int_value()
+                };
+                let int_type = field.get_type();
+                if index == dataful {
+                    // Az adatos változat minden érték, ami nem niche: `field - start >= niches` (előjel nélkül)
+                    let offset = self.builder.build_int_sub(field, int_type.const_int(start, false), "niche.offset");
+                    self.builder.build_int_compare(IntPredicate::UGE, offset, int_type.const_int(niches, false), "is_variant")
+                } else {
+                    let niche = int_type.const_int(start + niche_rank(index, dataful), false);
+                    self.builder.build_int_compare(IntPredicate::EQ, field, niche, "is_variant")
+                }
+            }
+        }
+    }
+
+    // Az `index`-edik változat adatmezői; csak akkor olvasható, ha az érték valóban ez a változat
+    fn build_variant_fields(&mut self, enum_name: &str, value: BasicValueEnum<'ctx>, index: usize) -> Vec<BasicValueEnum<'ctx>> {
+        let info = &self.enums[enum_name];
+        let layout = info.layout;
+        let payload_type = info.variants[index].1;
+        let count = payload_type.count_fields();
+        let struct_value = value.into_struct_value();
+
+        let payload = match layout {
+            EnumLayout::Single => struct_value,
+            EnumLayout::Niche { dataful, .. } if index == dataful => struct_value,
+            EnumLayout::Tag(_) | EnumLayout::Niche { .. } => return Vec::new(),
+            EnumLayout::Tagged(_) => {
+                let slot = self.build_entry_alloca(struct_value.get_type().into(), "variant");
+                self.builder.build_store(slot, struct_value);
+                let data_ptr = self.builder.build_struct_gep(slot, 1, "variant.data").unwrap();
+                let payload_ptr = self.builder.build_pointer_cast(data_ptr, payload_type.ptr_type(AddressSpace::Generic), "variant.payload");
+                self.builder.build_load(payload_ptr, "payload").into_struct_value()
+            }
+        };
+        (0..count)
+            .map(|i| self.builder.build_extract_value(payload, i, "field").unwrap())
+            .collect()
+    }
+
+    fn field_index(&self, struct_name: &str, field: &str) -> Result<u32, Diagnostic> {
+        self.struct_fields.get(struct_name)
+            .and_then(|fields| fields.iter().position(|f| f == field))
+            .map(|i| i as u32)
+            .ok_or_else(|| codegen_error(format!("unknown field `{}` of `{}`", field, struct_name)))
+    }
+
+    // Az impl blokk metódusai; trait implementációnál a felül nem írt alapértelmezett metódusokkal
+    fn impl_methods(&self, block: &Impl) -> Vec<Function> {
+        let mut methods = block.methods.clone();
+        if let Some(def) = block.trait_name.as_ref().and_then(|name| self.trait_defs.get(name)) {
+            let defaults = def.methods.iter()
+                .filter(|item| item.has_default && !block.methods.iter().any(|fv| fv.name == item.function.name))
+                .map(|item| item.function.clone());
+            methods.extend(defaults);
+        }
+        methods
+    }
+
+    fn define_methods(&mut self, block: &Impl) -> Result<(), Diagnostic> {
+        for method in self.impl_methods(block) {
+            let name = mangle_method(&block.type_name, &method.name);
+            if !self.templates.contains_key(&name) {
+                let self_type = self.annot_type(&impl_self_type(block), &HashMap::new())?;
+                self.type_args = HashMap::from([(SELF_TYPE.to_string(), self_type)]);
+                self.define_function(&name, &method)?;
+                self.type_args.clear();
+            }
+        }
+        Ok(())
+    }
+
+    fn add_template(&mut self, name: String, impl_generics: &[String], self_type: Option<TypeAnnot>, fv: &Function) {
+        let mut generics = impl_generics.to_vec();
+        generics.extend(fv.generics.iter().map(|generic| generic.name.clone()));
+        let template = Template { generics, impl_generics: impl_generics.len(), self_type, function: fv.clone() };
+        self.templates.insert(name, template);
+    }
+
+    // A hívott függvény neve; generikus függvénynél a típusargumentumok szerinti példányé, amelyet
+    // az első kéréskor deklarálunk, a törzsét pedig a `compile()` végén fordítjuk
+    fn instance(&mut self, type_name: Option<&str>, name: &str, args: &[Type]) -> Result<String, Diagnostic> {
+        let key = match type_name {
+            Some(type_name) => mangle_method(type_name, name),
+            None => name.to_string()
+        };
+        let Some(template) = self.templates.get(&key) else { return Ok(key) };
+        let (impl_args, fn_args) = args.split_at(template.impl_generics.min(args.len()));
+        let instance = match type_name {
+            Some(type_name) => mangle_method(&instance_name(type_name, impl_args), &instance_name(name, fn_args)),
+            None => instance_name(name, fn_args)
+        };
+        if self.module.get_function(&instance).is_none() {
+            if self.instance_depth >= INSTANCE_DEPTH_LIMIT {
+                return Err(codegen_error(format!("reached the recursion limit while instantiating `{}`", key))
+                    .with_note(format!("`{}` is (directly or indirectly) called from its own body with ever larger type arguments, so it would have infinitely many instances", key))
+                    .with_help("make the recursive call with the same type arguments, or use a non-generic helper"));
+            }
+            let mut type_args: HashMap<String, Type> = template.generics.iter().cloned().zip(args.iter().cloned()).collect();
+            if let Some(self_type) = &template.self_type {
+                type_args.insert(SELF_TYPE.to_string(), self.annot_type(self_type, &type_args)?);
+            }
+            let function = template.function.clone();
+            let outer = std::mem::replace(&mut self.type_args, type_args.clone());
+            let declared = self.declare_function(&instance, &function);
+            self.type_args = outer;
+            declared?;
+            self.pending.push((instance.clone(), function, type_args, self.instance_depth + 1));
+        }
+        Ok(instance)
+    }
+
+    // A kifejezés típusa az aktuális példány típusargumentumaival
+    fn expr_type(&self, span: Span) -> Option<Type> {
+        self.expr_types.get(&span).map(|ty| ty.substitute(&self.type_args))
+    }
+
+    fn instantiation(&self, span: Span) -> Option<Vec<Type>> {
+        self.instantiations.get(&span).map(|args| args.iter().map(|arg| arg.substitute(&self.type_args)).collect())
+    }
+
+    fn declare_function(&mut self, name: &str, fv: &Function) -> Result<(), Diagnostic> {
+        let param_types: Vec<_> = fv.params.iter()
+            .map(|param| self.param_type(param).map(|t| t.into())).collect::<Result<Vec<_>, _>>()?;
         
-        let fv_type = return_type.fv_type(&param_types, false);
-        self.module.add_function(&fv.name, fv_type, None);
+        let fv_type = match &fv.return_type {
+            _ if fv.is_async => {
+                let output = self.output_type(fv)?;
+                self.llvm_type(&future_type(output))?.fn_type(&param_types, false)
+            },
+            Some(annot) if annot.name == NEVER_TYPE => self.context.void_type().fn_type(&param_types, false),
+            Some(_) => self.parse_type(&fv.return_type)?.fn_type(&param_types, false),
+            None => self.context.void_type().fn_type(&param_types, false)
+        };
+        self.module.add_function(name, fv_type, None);
         
         Ok(())
     }
     
-    fn define_function(&mut self, fv: &Function) -> Result<(), String> {
-        let fuggveny = self.module.get_function(&fv.name)
-            .ok_or_else(|| format!("Function {} is not found", fv.name))?;
+    fn define_function(&mut self, name: &str, fv: &Function) -> Result<(), Diagnostic> {
+        if fv.is_async {
+            return self.define_async_function(name, fv);
+        }
+        let fuggveny = self.module.get_function(name)
+            .ok_or_else(|| codegen_error(format!("function `{}` is not declared", fv.name)))?;
             
         let entry = self.context.append_basic_block(fuggveny, "entry");
         self.builder.position_at_end(entry);
+        self.variables.clear();
+        self.return_type = fv.return_type.as_ref().map(|annot| self.annot_type(annot, &self.type_args)).transpose()?;
             
-        for (i, (name, _)) in fv.params.iter().enumerate() {
-            let param = fuggveny.get_nth_param(i as u32).unwrap();
-            param.set_name(name);
+        // A paraméterek stack-re kerülnek, így a változókkal azonos módon olvashatók
+        for (i, param) in fv.params.iter().enumerate() {
+            let value = fuggveny.get_nth_param(i as u32).unwrap();
+            value.set_name(&param.name);
+            let alloca = self.builder.build_alloca(value.get_type(), &param.name);
+            self.builder.build_store(alloca, value);
+            self.variables.insert(param.name.clone(), alloca);
         }
         
         for stmt in &fv.body {
             self.compile_stmt(stmt, fuggveny)?;
         }
         
-        if fuggveny.get_type().get_return_type().is_void() {
-            self.builder.build_return(None);
+        // A típusellenőrző szerint a nem `()` típusú törzs vége elérhetetlen
+        if self.builder.get_insert_block().and_then(|block| block.get_terminator()).is_none() {
+            if fuggveny.get_type().get_return_type().is_none() {
+                self.builder.build_return(None);
+            } else {
+                self.builder.build_unreachable();
+            }
         }
         
         Ok(())
     }
     
-    fn compile_stmt(&mut self, stmt: &Stmt, fuggveny: inkwell::values::FunctionValue<'ctx>) -> Result<(), String> {
-        match stmt {
-            Stmt::Let { name, value, .. } => {
+    // `aszink fv f(a) -> T`: a `poll` függvény (`f.folytat(keret) -> bool`) a törzs állapotgépe,
+    // a `true` a befejeződést jelzi. Maga `f` csak lefoglalja és kitölti a keretet, majd
+    // visszaadja a `Jovo<T>`-t; a törzs az első `poll` hívásig nem fut.
+    fn define_async_function(&mut self, name: &str, fv: &Function) -> Result<(), Diagnostic> {
+        let ramp = self.module.get_function(name)
+            .ok_or_else(|| codegen_error(format!("function `{}` is not declared", fv.name)))?;
+        let ptr_type = self.ptr_type();
+        let poll_type = self.context.bool_type().fn_type(&[ptr_type.into()], false);
+        let poll = self.module.add_function(&format!("{}.folytat", name), poll_type, None);
+
+        let entry = self.context.append_basic_block(poll, "entry");
+        let dispatch = self.context.append_basic_block(poll, "dispatch");
+        let start = self.context.append_basic_block(poll, "start");
+        let frame = poll.get_nth_param(0).unwrap().into_pointer_value();
+        self.builder.position_at_end(entry);
+        let output = self.output_type(fv)?;
+        let output_type = self.llvm_type(&output)?;
+        self.return_type = Some(output);
+        self.async_frame = Some(AsyncFrame {
+            frame,
+            slots_block: entry,
+            size: FRAME_HEADER_SIZE,
+            result: ptr_type.const_null(),
+            resume_points: Vec::new()
+        });
+        let (result, result_offset) = self.build_frame_slot(output_type, "eredmeny").unwrap();
+        if let Some(async_frame) = &mut self.async_frame {
+            async_frame.result = result;
+        }
+
+        self.builder.position_at_end(start);
+        self.variables.clear();
+        let mut param_offsets = Vec::new();
+        for (i, param) in fv.params.iter().enumerate() {
+            let param_type = ramp.get_nth_param(i as u32).unwrap().get_type();
+            let (slot, offset) = self.build_frame_slot(param_type, &param.name).unwrap();
+            param_offsets.push(offset);
+            self.variables.insert(param.name.clone(), slot);
+        }
+        for stmt in &fv.body {
+            self.compile_stmt(stmt, poll)?;
+        }
+        if self.builder.get_insert_block().and_then(|block| block.get_terminator()).is_none() {
+            self.finish_async();
+        }
+
+        // Az állapot szerinti elágazás a keretmezők címszámítása után
+        let async_frame = self.async_frame.take().unwrap();
+        self.builder.position_at_end(entry);
+        self.builder.build_unconditional_branch(dispatch);
+        self.builder.position_at_end(dispatch);
+        let state_type = self.context.i32_type();
+        let state_ptr = self.frame_field(frame, FRAME_STATE, state_type.into(), "allapot");
+        let state = self.builder.build_load(state_ptr, "allapot").into_int_value();
+        let done = self.context.append_basic_block(poll, "kesz");
+        let mut cases = vec![(state_type.const_zero(), start)];
+        for (i, block) in async_frame.resume_points.iter().enumerate() {
+            cases.push((state_type.const_int(i as u64 + 1, false), *block));
+        }
+        self.builder.build_switch(state, done, &cases);
+        self.builder.position_at_end(done);
+        self.builder.build_return(Some(&self.context.bool_type().const_int(1, false)));
+
+        // A belépési függvény: keret lefoglalása, fejléc és paraméterek
+        let entry = self.context.append_basic_block(ramp, "entry");
+        self.builder.position_at_end(entry);
+        let i64_type = self.context.i64_type();
+        let size = i64_type.const_int(async_frame.size, false);
+        let frame = self.call_runtime("malloc", ptr_type.fn_type(&[i64_type.into()], false), &[size.into()]);
+        let free = self.runtime_fn("free", self.context.void_type().fn_type(&[ptr_type.into()], false));
+        let header: [(u64, BasicValueEnum<'ctx>); 3] = [
+            (FRAME_POLL, poll.as_global_value().as_pointer_value().const_cast(ptr_type).into()),
+            (FRAME_RESULT, unsafe { self.builder.build_in_bounds_gep(frame, &[i64_type.const_int(result_offset, false)], "eredmeny") }.into()),
+            (FRAME_DROP, free.as_global_value().as_pointer_value().const_cast(ptr_type).into())
+        ];
+        for (offset, value) in header {
+            let field = self.frame_field(frame, offset, ptr_type.into(), "fejlec");
+            self.builder.build_store(field, value);
+        }
+        let state_ptr = self.frame_field(frame, FRAME_STATE, state_type.into(), "allapot");
+        self.builder.build_store(state_ptr, state_type.const_zero());
+        for (i, offset) in param_offsets.into_iter().enumerate() {
+            let value = ramp.get_nth_param(i as u32).unwrap();
+            value.set_name(&fv.params[i].name);
+            let field = self.frame_field(frame, offset, value.get_type(), &fv.params[i].name);
+            self.builder.build_store(field, value);
+        }
+        let future = self.build_future(frame, &output)?;
+        self.builder.build_return(Some(&future));
+        Ok(())
+    }
+
+    // Az `aszink` függvény eredménytípusa (a `Jovo` paramétere)
+    fn output_type(&self, fv: &Function) -> Result<Type, Diagnostic> {
+        match &fv.return_type {
+            Some(annot) => self.annot_type(annot, &self.type_args),
+            None => Ok(Type::Void)
+        }
+    }
+
+    // Helyi változó helye: `aszink` függvényben a keret egy mezője, egyébként stack
+    fn build_local(&mut self, ty: BasicTypeEnum<'ctx>, name: &str) -> PointerValue<'ctx> {
+        match self.build_frame_slot(ty, name) {
+            Some((slot, _)) => slot,
+            None => self.build_entry_alloca(ty, name)
+        }
+    }
+
+    // A stack-helyek a függvény belépési blokkjának elejére kerülnek, így ciklusban sem nő a stack
+    fn build_entry_alloca(&self, ty: BasicTypeEnum<'ctx>, name: &str) -> PointerValue<'ctx> {
+        let current = self.builder.get_insert_block().unwrap();
+        let entry = current.get_parent().unwrap().get_first_basic_block().unwrap();
+        match entry.get_first_instruction() {
+            Some(first) => self.builder.position_before(&first),
+            None => self.builder.position_at_end(entry)
+        }
+        let slot = self.builder.build_alloca(ty, name);
+        self.builder.position_at_end(current);
+        slot
+    }
+
+    // Új keretmező és az eltolása a keret elejétől
+    fn build_frame_slot(&mut self, ty: BasicTypeEnum<'ctx>, name: &str) -> Option<(PointerValue<'ctx>, u64)> {
+        let align = self.target_data.get_abi_alignment(&ty) as u64;
+        let size = self.target_data.get_abi_size(&ty);
+        let async_frame = self.async_frame.as_mut()?;
+        let offset = async_frame.size.next_multiple_of(align.max(1));
+        async_frame.size = offset + size;
+        let (frame, slots_block) = (async_frame.frame, async_frame.slots_block);
+
+        let current = self.builder.get_insert_block();
+        self.builder.position_at_end(slots_block);
+        let slot = self.frame_field(frame, offset, ty, name);
+        if let Some(block) = current {
+            self.builder.position_at_end(block);
+        }
+        Some((slot, offset))
+    }
+
+    fn frame_field(&self, frame: PointerValue<'ctx>, offset: u64, ty: BasicTypeEnum<'ctx>, name: &str) -> PointerValue<'ctx> {
+        let offset = self.context.i64_type().const_int(offset, false);
+        let byte = unsafe { self.builder.build_in_bounds_gep(frame, &[offset], name) };
+        self.builder.build_pointer_cast(byte, ty.ptr_type(AddressSpace::Generic), name)
+    }
+
+    // `vissza` (vagy a törzs vége) az állapotgépben: befejezett állapot, `poll` -> `true`
+    fn finish_async(&mut self) {
+        let Some(async_frame) = &self.async_frame else { return };
+        let state_type = self.context.i32_type();
+        let state_ptr = self.frame_field(async_frame.frame, FRAME_STATE, state_type.into(), "allapot");
+        self.builder.build_store(state_ptr, state_type.const_int(STATE_DONE, false));
+        self.builder.build_return(Some(&self.context.bool_type().const_int(1, false)));
+    }
+
+    // `akkor jovo`: amíg a jövő nem kész, az állapotgép a folytatási pont sorszámával tér vissza,
+    // és a következő `poll` ugyanitt próbálkozik újra
+    fn compile_await(&mut self, expr: &Expr, future: &Expr) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let function = self.current_function();
+        let future = self.compile_expr(future)?;
+        let handle = self.future_handle(future);
+        let slot = self.build_local(self.ptr_type().into(), "akkor.jovo");
+        self.builder.build_store(slot, handle);
+
+        let poll_block = self.context.append_basic_block(function, "akkor.poll");
+        let pending_block = self.context.append_basic_block(function, "akkor.var");
+        let ready_block = self.context.append_basic_block(function, "akkor.kesz");
+        self.builder.build_unconditional_branch(poll_block);
+
+        self.builder.position_at_end(poll_block);
+        let handle = self.builder.build_load(slot, "akkor.jovo").into_pointer_value();
+        let poll_type = self.context.bool_type().fn_type(&[self.ptr_type().into()], false);
+        let poll = self.runtime_fn("dlang_future_poll", poll_type);
+        let ready = self.builder.build_call(poll, &[handle.into()], "akkor.kesz")
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+        self.builder.build_conditional_branch(ready, ready_block, pending_block);
+
+        let async_frame = self.async_frame.as_mut().ok_or_else(|| codegen_error("`akkor` outside of an `aszink` function"))?;
+        async_frame.resume_points.push(poll_block);
+        let state = async_frame.resume_points.len() as u64;
+        let frame = async_frame.frame;
+        self.builder.position_at_end(pending_block);
+        let state_type = self.context.i32_type();
+        let state_ptr = self.frame_field(frame, FRAME_STATE, state_type.into(), "allapot");
+        self.builder.build_store(state_ptr, state_type.const_int(state, false));
+        self.builder.build_return(Some(&self.context.bool_type().const_zero()));
+
+        self.builder.position_at_end(ready_block);
+        let output = self.expr_type(expr.span).unwrap_or(Type::Void);
+        self.take_future_output(handle, &output)
+    }
+
+    // `Jovo<T>` -> a keret (vagy a runtime jövőjének) címe
+    fn future_handle(&mut self, future: BasicValueEnum<'ctx>) -> PointerValue<'ctx> {
+        let address = self.builder.build_extract_value(future.into_struct_value(), 0, "jovo.keret").unwrap().into_int_value();
+        self.builder.build_int_to_ptr(address, self.ptr_type(), "jovo")
+    }
+
+    fn build_future(&mut self, handle: PointerValue<'ctx>, output: &Type) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let future_type = self.llvm_type(&future_type(output.clone()))?.into_struct_type();
+        let address = self.builder.build_ptr_to_int(handle, self.int_type(IntTy::Usize), "jovo.keret");
+        Ok(self.builder.build_insert_value(future_type.get_undef(), address, 0, "jovo").unwrap().into_struct_value().into())
+    }
+
+    // A kész jövő eredményét kimásolja, majd felszabadítja a jövőt
+    fn take_future_output(&mut self, handle: PointerValue<'ctx>, output: &Type) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let ptr_type = self.ptr_type();
+        let result = self.call_runtime("dlang_future_result", ptr_type.fn_type(&[ptr_type.into()], false), &[handle.into()]);
+        let output_type = self.llvm_type(output)?;
+        let typed = self.builder.build_pointer_cast(result, output_type.ptr_type(AddressSpace::Generic), "jovo.eredmeny");
+        let value = self.builder.build_load(typed, "jovo.eredmeny");
+        let free = self.runtime_fn("dlang_future_free", self.context.void_type().fn_type(&[ptr_type.into()], false));
+        self.builder.build_call(free, &[handle.into()], "");
+        Ok(value)
+    }
+
+    // A `later` kiértékelése úgy, hogy a már kiszámolt `live` értékek túléljék a benne álló
+    // `akkor` felfüggesztését: a keretbe kerülnek, és utána onnan töltődnek vissza
+    fn across_await<T>(
+        &mut self,
+        live: &mut [BasicValueEnum<'ctx>],
+        later: &Expr,
+        compile: impl FnOnce(&mut Self) -> Result<T, Diagnostic>
+    ) -> Result<T, Diagnostic> {
+        if live.is_empty() || !contains_await(later) {
+            return compile(self);
+        }
+        let slots: Vec<_> = live.iter().map(|value| {
+            let slot = self.build_local(value.get_type(), "akkor.ertek");
+            self.builder.build_store(slot, *value);
+            slot
+        }).collect();
+        let result = compile(self)?;
+        for (value, slot) in live.iter_mut().zip(slots) {
+            *value = self.builder.build_load(slot, "akkor.ertek");
+        }
+        Ok(result)
+    }
+
+    // Operandusok balról jobbra; a későbbi operandus `akkor`-ja előtt a korábbiak a keretbe kerülnek
+    fn compile_operands<'e>(&mut self, operands: impl IntoIterator<Item = &'e Expr>) -> Result<Vec<BasicValueEnum<'ctx>>, Diagnostic> {
+        let mut values = Vec::new();
+        for operand in operands {
+            let value = self.across_await(&mut values, operand, |this| this.compile_expr(operand))?;
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    fn compile_stmt(&mut self, stmt: &Stmt, fuggveny: inkwell::values::FunctionValue<'ctx>) -> Result<(), Diagnostic> {
+        // A `vissza` utáni (elérhetetlen) utasítások külön blokkba kerülnek
+        if self.builder.get_insert_block().and_then(|block| block.get_terminator()).is_some() {
+            let dead = self.context.append_basic_block(fuggveny, "halott");
+            self.builder.position_at_end(dead);
+        }
+        match &stmt.kind {
+            StmtKind::Let { name, value, .. } => {
                 let val = self.compile_expr(value)?;
-                let alloca = self.builder.build_alloca(val.get_type(), name);
+                let alloca = self.build_local(val.get_type(), name);
+                self.builder.build_store(alloca, val);
+                self.variables.insert(name.clone(), alloca);
+                Ok(())
+            },
+            // A helyi konstans értéke már ismert; a változókhoz hasonlóan a blokk végéig látható
+            StmtKind::Const(def) => {
+                let val = self.constant_value(def)?;
+                let alloca = self.build_local(val.get_type(), &def.name);
                 self.builder.build_store(alloca, val);
+                self.variables.insert(def.name.clone(), alloca);
+                Ok(())
+            },
+            // Az érték a hely előtt értékelődik ki: egy `akkor` felfüggesztése után a hely címe
+            // már nem lenne érvényes
+            StmtKind::Assign { target, op, value } => {
+                let mut val = self.compile_expr(value)?;
+                let place = self.compile_place(target)?;
+                if let Some(op) = op {
+                    let ty = self.expr_type(target.span).ok_or_else(|| codegen_error("assignment to an untyped place"))?;
+                    let current = self.builder.build_load(place, "jelenlegi");
+                    val = self.compile_binary(*op, current, val, &ty)?;
+                }
+                self.builder.build_store(place, val);
+                Ok(())
+            },
+            StmtKind::Return(expr) if self.async_frame.is_some() => {
+                if let Some(expr) = expr {
+                    let val = self.compile_expr(expr)?;
+                    if let Some(async_frame) = &self.async_frame {
+                        self.builder.build_store(async_frame.result, val);
+                    }
+                }
+                self.finish_async();
                 Ok(())
             },
-            Stmt::Return(expr) => {
+            StmtKind::Return(expr) => {
                 if let Some(expr) = expr {
                     let val = self.compile_expr(expr)?;
                     self.builder.build_return(Some(&val));
@@ -95,42 +1080,2382 @@ impl<'ctx> CodeGen<'ctx> {
                 }
                 Ok(())
             },
-            _ => unimplemented!()
+            StmtKind::For { pattern, iterable, body } => self.with_scope(|this| match this.expr_type(iterable.span) {
+                Some(ty) if array_element(&ty).is_some() => this.compile_array_loop(pattern, iterable, body, fuggveny),
+                Some(Type::Range(element)) => this.compile_range_loop(pattern, iterable, body, &element, fuggveny),
+                Some(Type::Struct(name, args)) => this.compile_iter_loop(pattern, iterable, body, &name, &args, fuggveny),
+                _ => Err(codegen_error("`szor` loop over a non-iterable value"))
+            }),
+            // A kifejezés-utasítás értéke eldobódik
+            StmtKind::Expr(expr) => self.compile_expr(expr).map(|_| ()),
+            StmtKind::While { condition, body } => {
+                let condition_block = self.context.append_basic_block(fuggveny, "mialatt.feltetel");
+                let body_block = self.context.append_basic_block(fuggveny, "mialatt.torzs");
+                let end_block = self.context.append_basic_block(fuggveny, "mialatt.vege");
+                self.builder.build_unconditional_branch(condition_block);
+
+                self.builder.position_at_end(condition_block);
+                let condition = self.compile_expr(condition)?.into_int_value();
+                self.builder.build_conditional_branch(condition, body_block, end_block);
+
+                self.builder.position_at_end(body_block);
+                self.compile_branch(body, fuggveny, condition_block)?;
+                self.builder.position_at_end(end_block);
+                Ok(())
+            }
         }
     }
-    
-    fn compile_expr(&mut self, expr: &Expr) -> Result<inkwell::values::BasicValueEnum<'ctx>, String> {
-        match expr {
-            Expr::Literal(lit) => match lit {
-                Literal::Int(n) => Ok(self.context.i32_type().const_int(*n as u64, false).into()),
-                Literal::Float(n) => Ok(self.context.f64_type().const_float(*n).into()),
-                Literal::Bool(b) => Ok(self.context.bool_type().const_int(*b as u64, false).into()),
-                Literal::String(s) => {
-                    let string = self.context.const_string(s.as_bytes(), false);
-                    Ok(string.as_basic_value_enum())
-                }
-            },
-            Expr::BinaryOp { left, op, right } => {
-                let lhs = self.compile_expr(left)?;
-                let rhs = self.compile_expr(right)?;
-                
-                match op {
-                    BinaryOp::Add => Ok(self.builder.build_int_add(lhs.into_int_value(), rhs.into_int_value(), "addtmp").into()),
-                    BinaryOp::Sub => Ok(self.builder.build_int_sub(lhs.inot_int_value(), rhs.into_int_value(), "subtmp").into()),
-                    _ => unimplemented!()
-                }
-            },
-            _ => unimplemented!()
+
+    // Egy ág utasításai saját hatókörben; ha az ág nem ugrott el, a `next` blokkban folytatódik
+    fn compile_branch(&mut self, stmts: &[Stmt], function: FunctionValue<'ctx>, next: BasicBlock<'ctx>) -> Result<(), Diagnostic> {
+        self.with_scope(|this| {
+            for stmt in stmts {
+                this.compile_stmt(stmt, function)?;
+            }
+            Ok::<(), Diagnostic>(())
+        })?;
+        if self.builder.get_insert_block().unwrap().get_terminator().is_none() {
+            self.builder.build_unconditional_branch(next);
+        }
+        Ok(())
+    }
+
+    // A `visit` alatt kötött nevek csak a hatókör végéig láthatók; utána újra a külső,
+    // elfedett változókra hivatkoznak (a típusellenőrző hatóköreinek megfelelően)
+    fn with_scope<R>(&mut self, visit: impl FnOnce(&mut Self) -> R) -> R {
+        let outer = self.variables.clone();
+        let result = visit(self);
+        self.variables = outer;
+        result
+    }
+
+    // Számlálós ciklus. A léptetés előtt a hátralévő távolságot vizsgáljuk, így a típus
+    // határáig tartó tartomány (`0u8..=255`) sem csordul túl.
+    fn compile_range_loop(&mut self, pattern: &Pattern, range: &Expr, body: &[Stmt], element: &Type, function: FunctionValue<'ctx>) -> Result<(), Diagnostic> {
+        let (le, lt) = match element {
+            Type::Int(int_ty) if int_ty.is_signed() => (IntPredicate::SLE, IntPredicate::SLT),
+            _ => (IntPredicate::ULE, IntPredicate::ULT)
+        };
+        // A tartomány helyi változóban, hogy egy `akkor` felfüggesztése után is elérhető legyen
+        let range = self.compile_expr(range)?.into_struct_value();
+        let range_slot = self.build_local(range.get_type().into(), "szor.range");
+        self.builder.build_store(range_slot, range);
+        let start = self.builder.build_extract_value(range, 0, "szor.start").unwrap().into_int_value();
+        let end = self.builder.build_extract_value(range, 1, "szor.end").unwrap().into_int_value();
+        let inclusive = self.builder.build_extract_value(range, 3, "szor.inclusive").unwrap().into_int_value();
+
+        let counter = self.build_local(start.get_type().into(), "szor.i");
+        self.builder.build_store(counter, start);
+
+        let body_block = self.context.append_basic_block(function, "szor.body");
+        let latch_block = self.context.append_basic_block(function, "szor.latch");
+        let next_block = self.context.append_basic_block(function, "szor.next");
+        let end_block = self.context.append_basic_block(function, "szor.end");
+
+        let closed = self.builder.build_int_compare(le, start, end, "szor.closed");
+        let open = self.builder.build_int_compare(lt, start, end, "szor.open");
+        let enter = self.builder.build_select(inclusive, closed, open, "szor.enter").into_int_value();
+        self.builder.build_conditional_branch(enter, body_block, end_block);
+
+        self.builder.position_at_end(body_block);
+        let current = self.builder.build_load(counter, "szor.i");
+        self.bind_pattern(pattern, current);
+        for stmt in body {
+            self.compile_stmt(stmt, function)?;
+        }
+        if self.builder.get_insert_block().unwrap().get_terminator().is_none() {
+            self.builder.build_unconditional_branch(latch_block);
+        }
+
+        // Zárt tartománynál `end - i < step`, nyitottnál `end - i <= step` esetén ez volt az utolsó elem
+        self.builder.position_at_end(latch_block);
+        let range = self.builder.build_load(range_slot, "szor.range").into_struct_value();
+        let end = self.builder.build_extract_value(range, 1, "szor.end").unwrap().into_int_value();
+        let step = self.builder.build_extract_value(range, 2, "szor.step").unwrap().into_int_value();
+        let inclusive = self.builder.build_extract_value(range, 3, "szor.inclusive").unwrap().into_int_value();
+        let current = self.builder.build_load(counter, "szor.i").into_int_value();
+        let remaining = self.builder.build_int_sub(end, current, "szor.remaining");
+        let last_closed = self.builder.build_int_compare(IntPredicate::ULT, remaining, step, "szor.last");
+        let last_open = self.builder.build_int_compare(IntPredicate::ULE, remaining, step, "szor.last");
+        let last = self.builder.build_select(inclusive, last_closed, last_open, "szor.last").into_int_value();
+        self.builder.build_conditional_branch(last, end_block, next_block);
+
+        self.builder.position_at_end(next_block);
+        let next = self.builder.build_int_add(current, step, "szor.next");
+        self.builder.build_store(counter, next);
+        self.builder.build_unconditional_branch(body_block);
+
+        self.builder.position_at_end(end_block);
+        Ok(())
+    }
+
+    // Index alapú bejárás: a `darab` egyszer hívódik, majd `elem(ertek, i)` minden `i < darab` indexre
+    fn compile_iter_loop(&mut self, pattern: &Pattern, iterable: &Expr, body: &[Stmt], type_name: &str, type_args: &[Type], function: FunctionValue<'ctx>) -> Result<(), Diagnostic> {
+        let count_fn = self.instance(Some(type_name), ITER_COUNT, type_args)?;
+        let get_fn = self.instance(Some(type_name), ITER_GET, type_args)?;
+        let value = self.compile_expr(iterable)?;
+        let count = self.compile_call(&count_fn, &[value.into()])?;
+        let usize_type = self.int_type(IntTy::Usize);
+        let value_slot = self.build_local(value.get_type(), "szor.ertek");
+        self.builder.build_store(value_slot, value);
+        let count_slot = self.build_local(count.get_type(), "szor.darab");
+        self.builder.build_store(count_slot, count);
+        let index = self.build_local(usize_type.into(), "szor.index");
+        self.builder.build_store(index, usize_type.const_zero());
+
+        let cond_block = self.context.append_basic_block(function, "szor.cond");
+        let body_block = self.context.append_basic_block(function, "szor.body");
+        let end_block = self.context.append_basic_block(function, "szor.end");
+        self.builder.build_unconditional_branch(cond_block);
+
+        self.builder.position_at_end(cond_block);
+        let current = self.builder.build_load(index, "szor.index").into_int_value();
+        let count = self.builder.build_load(count_slot, "szor.darab").into_int_value();
+        let more = self.builder.build_int_compare(IntPredicate::ULT, current, count, "szor.more");
+        self.builder.build_conditional_branch(more, body_block, end_block);
+
+        self.builder.position_at_end(body_block);
+        let value = self.builder.build_load(value_slot, "szor.ertek");
+        let element = self.compile_call(&get_fn, &[value.into(), current.into()])?;
+        self.bind_pattern(pattern, element);
+        for stmt in body {
+            self.compile_stmt(stmt, function)?;
+        }
+        if self.builder.get_insert_block().unwrap().get_terminator().is_none() {
+            let current = self.builder.build_load(index, "szor.index").into_int_value();
+            let next = self.builder.build_int_add(current, usize_type.const_int(1, false), "szor.next");
+            self.builder.build_store(index, next);
+            self.builder.build_unconditional_branch(cond_block);
         }
+
+        self.builder.position_at_end(end_block);
+        Ok(())
     }
     
-    fn parse_type(&self, type_str: &Option<String>) -> Result<inkwell::types::BasicTypeEnum<'ctx>, String> {
-        match type_str.as_deref() {
-            Some("i32") => Ok(self.context.i32_type().into()),
-            Some("f64") => Ok(self.context.f64_type().into()),
-            Some("bool") => Ok(self.context.bool_type().into()),
-            Some("str") => Ok(self.context.i8_type().ptr_type(inkwell::AddressSpace::Generic).into()),
-            _ => Err(format!("Unknown type: {:?}", type_str))
+    // Tömb, szelet vagy `Tomb<T>` bejárása: a kezdőcím és a hossz a ciklus előtt rögzül
+    fn compile_array_loop(&mut self, pattern: &Pattern, iterable: &Expr, body: &[Stmt], function: FunctionValue<'ctx>) -> Result<(), Diagnostic> {
+        let (base, len) = self.array_parts(iterable)?;
+        let usize_type = self.int_type(IntTy::Usize);
+        let base_slot = self.build_local(base.get_type().into(), "szor.adat");
+        self.builder.build_store(base_slot, base);
+        let len_slot = self.build_local(usize_type.into(), "szor.hossz");
+        self.builder.build_store(len_slot, len);
+        let index = self.build_local(usize_type.into(), "szor.index");
+        self.builder.build_store(index, usize_type.const_zero());
+
+        let cond_block = self.context.append_basic_block(function, "szor.cond");
+        let body_block = self.context.append_basic_block(function, "szor.body");
+        let end_block = self.context.append_basic_block(function, "szor.end");
+        self.builder.build_unconditional_branch(cond_block);
+
+        self.builder.position_at_end(cond_block);
+        let current = self.builder.build_load(index, "szor.index").into_int_value();
+        let len = self.builder.build_load(len_slot, "szor.hossz").into_int_value();
+        let more = self.builder.build_int_compare(IntPredicate::ULT, current, len, "szor.more");
+        self.builder.build_conditional_branch(more, body_block, end_block);
+
+        self.builder.position_at_end(body_block);
+        let base = self.builder.build_load(base_slot, "szor.adat").into_pointer_value();
+        let element_ptr = unsafe { self.builder.build_gep(base, &[current], "szor.elem") };
+        let element = self.builder.build_load(element_ptr, "szor.elem");
+        self.bind_pattern(pattern, element);
+        for stmt in body {
+            self.compile_stmt(stmt, function)?;
+        }
+        if self.builder.get_insert_block().unwrap().get_terminator().is_none() {
+            let current = self.builder.build_load(index, "szor.index").into_int_value();
+            let next = self.builder.build_int_add(current, usize_type.const_int(1, false), "szor.next");
+            self.builder.build_store(index, next);
+            self.builder.build_unconditional_branch(cond_block);
         }
+
+        self.builder.position_at_end(end_block);
+        Ok(())
     }
-}
\ No newline at end of file
+
+    // A `dyn Trait` helyén álló értékek a típusellenőrző jelölése szerint trait objektummá alakulnak
+    fn compile_expr(&mut self, expr: &Expr) -> Result<inkwell::values::BasicValueEnum<'ctx>, Diagnostic> {
+        let value = self.compile_expr_kind(expr)?;
+        match (self.coercions.get(&expr.span).cloned(), self.expr_type(expr.span)) {
+            (Some(Type::Dyn(trait_name)), Some(ty)) => self.build_trait_object(value, &ty, &trait_name),
+            (Some(Type::Slice(_)), Some(ty)) => {
+                let (base, len) = self.value_parts(value, &ty)?;
+                Ok(self.build_slice(base, len))
+            },
+            (Some(target), Some(Type::Array(..))) if target.is_dyn_array() => self.build_dyn_array(value.into_array_value(), &target),
+            _ => Ok(value)
+        }
+    }
+
+    fn compile_expr_kind(&mut self, expr: &Expr) -> Result<inkwell::values::BasicValueEnum<'ctx>, Diagnostic> {
+        match &expr.kind {
+            ExprKind::Literal(lit) => match lit {
+                // Az utótag nélküli literál típusát a típusellenőrző a környezetből határozta meg
+                Literal::Int(n, suffix) => {
+                    let int_type = match self.expr_type(expr.span) {
+                        Some(Type::Int(int_ty)) => self.int_type(int_ty),
+                        _ => self.int_type(suffix.unwrap_or(IntTy::I32))
+                    };
+                    // 128 bites értékeknél két 64 bites szóként adjuk át
+                    let words = [*n as u64, (*n >> 64) as u64];
+                    Ok(int_type.const_int_arbitrary_precision(&words).into())
+                },
+                Literal::Float(n, suffix) => {
+                    let float_type = match self.expr_type(expr.span) {
+                        Some(Type::Float(float_ty)) => self.float_type(float_ty),
+                        _ => self.float_type(suffix.unwrap_or(FloatTy::F64))
+                    };
+                    Ok(float_type.const_float(*n).into())
+                },
+                Literal::Bool(b) => Ok(self.context.bool_type().const_int(*b as u64, false).into()),
+                Literal::String(s) => {
+                    // Null-terminált globális konstans, a runtime C stringként kapja meg
+                    let global = self.builder.build_global_string_ptr(s, "str");
+                    Ok(global.as_pointer_value().into())
+                },
+                Literal::ByteString(bytes) => {
+                    let array = self.context.const_string(bytes, false);
+                    let global = self.module.add_global(array.get_type(), None, "bytes");
+                    global.set_initializer(&array);
+                    global.set_constant(true);
+                    Ok(global.as_pointer_value().into())
+                },
+                // A karakter Unicode skalárértékként, 32 biten
+                Literal::Char(c) => Ok(self.context.i32_type().const_int(*c as u64, false).into())
+            },
+            ExprKind::Unary { op, operand } => {
+                let value = self.compile_expr(operand)?;
+                match (op, value) {
+                    (UnaryOp::Neg, BasicValueEnum::IntValue(v)) => Ok(self.builder.build_int_neg(v, "negtmp").into()),
+                    (UnaryOp::Neg, BasicValueEnum::FloatValue(v)) => Ok(self.builder.build_float_neg(v, "fnegtmp").into()),
+                    (UnaryOp::Not, BasicValueEnum::IntValue(v)) => Ok(self.builder.build_not(v, "nottmp").into()),
+                    _ => Err(codegen_error("invalid operand for unary operator"))
+                }
+            },
+            ExprKind::Await(future) => self.compile_await(expr, future),
+            ExprKind::Try(operand) => self.compile_try(operand),
+            ExprKind::Cast { expr: operand, .. } => {
+                let target = self.expr_type(expr.span).ok_or_else(|| codegen_error("cast to an untyped value"))?;
+                self.compile_cast(operand, &target)
+            },
+            ExprKind::BinaryOp { left, op: op @ (BinaryOp::And | BinaryOp::Or), right } => self.compile_logical(*op, left, right),
+            ExprKind::BinaryOp { left, op, right } => {
+                let values = self.compile_operands([left.as_ref(), right.as_ref()])?;
+                let (lhs, rhs) = (values[0], values[1]);
+                let ty = self.expr_type(left.span).ok_or_else(|| codegen_error("operation on an untyped value"))?;
+                self.compile_binary(*op, lhs, rhs, &ty)
+            },
+            ExprKind::Ident(name) => {
+                if let Some(ptr) = self.variables.get(name) {
+                    return Ok(self.builder.build_load(*ptr, name));
+                }
+                match self.constants.get(name) {
+                    Some(&(global, true)) => return Ok(self.builder.build_load(global.into_pointer_value(), name)),
+                    Some(&(value, false)) => return Ok(value),
+                    None => {}
+                }
+                // Függvény értékként: környezet nélküli lezárás
+                let function = self.module.get_function(name)
+                    .ok_or_else(|| codegen_error(format!("unknown variable `{}`", name)))?;
+                let thunk = self.function_thunk(function)?;
+                Ok(self.build_closure_value(thunk, self.ptr_type().const_null()))
+            },
+            ExprKind::Call { callee, args } if self.variables.contains_key(callee) => {
+                let values = self.compile_operands(args)?;
+                let closure = self.builder.build_load(self.variables[callee], callee);
+                let return_type = self.expr_type(expr.span).unwrap_or(Type::Void);
+                self.call_closure(closure, &values, &return_type)
+            },
+            ExprKind::Call { callee, args } if callee == BUILTIN_SPAWN && self.module.get_function(callee).is_none() => {
+                let closure = self.compile_expr(&args[0])?.into_struct_value();
+                let function = self.builder.build_extract_value(closure, 0, "spawn.fn").unwrap();
+                let env = self.builder.build_extract_value(closure, 1, "spawn.env").unwrap();
+                let ptr_type = self.ptr_type();
+                let fn_type = ptr_type.fn_type(&[ptr_type.into(), ptr_type.into()], false);
+                let handle = self.call_runtime("dlang_thread_spawn", fn_type, &[function.into(), env.into()]);
+                let handle = self.builder.build_ptr_to_int(handle, self.int_type(IntTy::Usize), "szal.kezelo");
+                let thread_type = self.llvm_type(&Type::Struct(THREAD_TYPE.to_string(), Vec::new()))?.into_struct_type();
+                Ok(self.builder.build_insert_value(thread_type.get_undef(), handle, 0, "szal").unwrap().into_struct_value().into())
+            },
+            // `futtat(jovo)`: a runtime executora a jövő befejeződéséig futtatja a `poll`-t
+            ExprKind::Call { callee, args } if callee == BUILTIN_BLOCK_ON && self.module.get_function(callee).is_none() => {
+                let future = self.compile_expr(&args[0])?;
+                let handle = self.future_handle(future);
+                let fn_type = self.context.void_type().fn_type(&[self.ptr_type().into()], false);
+                let function = self.runtime_fn("dlang_executor_block_on", fn_type);
+                self.builder.build_call(function, &[handle.into()], "");
+                let output = self.expr_type(expr.span).unwrap_or(Type::Void);
+                self.take_future_output(handle, &output)
+            },
+            ExprKind::Call { callee, args } if (callee == BUILTIN_SLEEP || callee == BUILTIN_READ_FILE) && self.module.get_function(callee).is_none() => {
+                let arg = self.compile_expr(&args[0])?;
+                let (runtime_name, output) = match callee.as_str() {
+                    BUILTIN_SLEEP => ("dlang_future_sleep", Type::Void),
+                    _ => ("dlang_future_read_file", Type::String)
+                };
+                let fn_type = self.ptr_type().fn_type(&[arg.get_type().into()], false);
+                let handle = self.call_runtime(runtime_name, fn_type, &[arg.into()]);
+                self.build_future(handle, &output)
+            },
+            // `panik(uzenet)`, `kilep(kod)`: a runtime leállítja a programot, a hívás után nincs folytatás
+            ExprKind::Call { callee, args } if (callee == BUILTIN_PANIC || callee == BUILTIN_EXIT) && self.module.get_function(callee).is_none() => {
+                let arg = self.compile_expr(&args[0])?;
+                let runtime_name = if callee == BUILTIN_PANIC { "dlang_panic" } else { "dlang_exit" };
+                let function = self.runtime_fn(runtime_name, self.context.void_type().fn_type(&[arg.get_type().into()], false));
+                self.builder.build_call(function, &[arg.into()], "");
+                Ok(self.build_diverge())
+            },
+            // `sor_olvas()`: a runtime null mutatója `Opcio::Nincs`
+            ExprKind::Call { callee, .. } if callee == BUILTIN_READ_LINE && self.module.get_function(callee).is_none() => {
+                let line = self.call_runtime("dlang_readline", self.ptr_type().fn_type(&[], false), &[]);
+                let option_type = self.expr_type(expr.span).ok_or_else(|| codegen_error("untyped call"))?;
+                self.llvm_type(&option_type)?;
+                let key = option_type.to_string();
+                let some = self.build_variant(&key, self.variant_index(&key, OPTION_SOME).unwrap(), &[line.into()]);
+                let none = self.build_variant(&key, self.variant_index(&key, OPTION_NONE).unwrap(), &[]);
+                let is_null = self.builder.build_is_null(line, "sor.nincs");
+                Ok(self.builder.build_select(is_null, none, some, "sor"))
+            },
+            // `fajl_beolvas(ut)`, `fajl_ir(ut, tartalom)`: a null mutató, illetve a `false` `Eredmeny::Hiba`
+            ExprKind::Call { callee, args } if (callee == BUILTIN_LOAD_FILE || callee == BUILTIN_WRITE_FILE) && self.module.get_function(callee).is_none() => {
+                let args = self.compile_args(args)?;
+                let param_types: Vec<BasicMetadataTypeEnum> = args.iter().map(|_| self.ptr_type().into()).collect();
+                let (value, ok) = if callee == BUILTIN_LOAD_FILE {
+                    let content = self.call_runtime("dlang_readfile", self.ptr_type().fn_type(&param_types, false), &args);
+                    (content.into(), self.builder.build_is_not_null(content, "fajl.ok"))
+                } else {
+                    let function = self.runtime_fn("dlang_writefile", self.context.bool_type().fn_type(&param_types, false));
+                    let written = self.builder.build_call(function, &args, "fajl.ok").try_as_basic_value().left().unwrap().into_int_value();
+                    (self.unit_value(), written)
+                };
+                let result_type = self.expr_type(expr.span).ok_or_else(|| codegen_error("untyped call"))?;
+                self.build_ffi_result(&result_type, ok, value)
+            },
+            ExprKind::Call { callee, args } if callee == BUILTIN_JOIN && self.module.get_function(callee).is_none() => {
+                let thread = self.compile_expr(&args[0])?.into_struct_value();
+                let handle = self.builder.build_extract_value(thread, 0, "szal.kezelo").unwrap().into_int_value();
+                let handle = self.builder.build_int_to_ptr(handle, self.ptr_type(), "szal.kezelo");
+                let fn_type = self.context.void_type().fn_type(&[self.ptr_type().into()], false);
+                let function = self.runtime_fn("dlang_thread_join", fn_type);
+                self.builder.build_call(function, &[handle.into()], "");
+                Ok(self.unit_value())
+            },
+            ExprKind::Call { callee, args } => {
+                let type_args = self.instantiation(expr.span).unwrap_or_default();
+                let name = self.instance(None, callee, &type_args)?;
+                let args = self.compile_args(args)?;
+                let value = self.compile_call(&name, &args)?;
+                if self.expr_type(expr.span) == Some(Type::Never) {
+                    return Ok(self.build_diverge());
+                }
+                Ok(value)
+            },
+            ExprKind::MethodCall { receiver, method, .. } if method == ARRAY_LEN
+                && self.expr_type(receiver.span).as_ref().and_then(array_element).is_some() => {
+                let (_, len) = self.array_parts(receiver)?;
+                Ok(len.into())
+            },
+            // Az elem egy ideiglenes helyről másolódik a tömb végére
+            ExprKind::MethodCall { receiver, method, args } if method == ARRAY_PUSH
+                && self.expr_type(receiver.span).is_some_and(|ty| ty.is_dyn_array()) => {
+                let values = self.compile_operands([receiver.as_ref(), &args[0]])?;
+                let (array, element) = (self.dyn_array_handle(values[0]), values[1]);
+                let slot = self.build_local(element.get_type(), "tomb.uj");
+                self.builder.build_store(slot, element);
+                let slot = self.builder.build_pointer_cast(slot, self.ptr_type(), "tomb.uj");
+                let ptr_type = self.ptr_type();
+                let push = self.runtime_fn("dlang_array_push", self.context.void_type().fn_type(&[ptr_type.into(), ptr_type.into()], false));
+                self.builder.build_call(push, &[array.into(), slot.into()], "");
+                Ok(self.unit_value())
+            },
+            ExprKind::MethodCall { receiver, method, args } if method == RANGE_STEP
+                && matches!(self.expr_type(receiver.span), Some(Type::Range(_))) => {
+                let values = self.compile_operands([receiver.as_ref(), &args[0]])?;
+                Ok(self.builder.build_insert_value(values[0].into_struct_value(), values[1], 2, "range.step").unwrap().into_struct_value().into())
+            },
+            ExprKind::MethodCall { receiver, method, args } => {
+                let receiver_type = self.expr_type(receiver.span)
+                    .ok_or_else(|| codegen_error(format!("method call `{}` on an untyped value", method)))?;
+                // Generikus metódusnál az impl blokk paraméterei (a fogadó típusargumentumai) után a sajátjai;
+                // trait metódusként (generikus fogadón) hívva a típusellenőrző csak a sajátjait rögzítette
+                let called_on_type = matches!(self.expr_types.get(&receiver.span), Some(Type::Struct(..) | Type::Enum(..)));
+                let type_args = match (&receiver_type, self.instantiation(expr.span)) {
+                    (_, Some(args)) if called_on_type => args,
+                    (Type::Struct(_, receiver_args) | Type::Enum(_, receiver_args), own) => {
+                        receiver_args.iter().cloned().chain(own.unwrap_or_default()).collect()
+                    },
+                    (_, own) => own.unwrap_or_default()
+                };
+                let values = self.compile_operands(std::iter::once(receiver.as_ref()).chain(args))?;
+                self.call_method(&receiver_type, method, &type_args, &values)
+            },
+            ExprKind::AssocCall { type_name, name, args } => {
+                if let Some((enum_type, index)) = self.variant_at(expr.span, type_name, name)? {
+                    let fields = self.compile_operands(args)?;
+                    return Ok(self.build_variant(&enum_type, index, &fields));
+                }
+                let type_args = self.instantiation(expr.span).unwrap_or_default();
+                let name = self.instance(Some(type_name), name, &type_args)?;
+                let args = self.compile_args(args)?;
+                self.compile_call(&name, &args)
+            },
+            ExprKind::Path { type_name, name } => {
+                let (enum_type, index) = self.variant_at(expr.span, type_name, name)?
+                    .ok_or_else(|| codegen_error(format!("unknown variant `{}::{}`", type_name, name)))?;
+                Ok(self.build_variant(&enum_type, index, &[]))
+            },
+            ExprKind::Interpolated(parts) => self.compile_interpolation(parts),
+            ExprKind::Array(elements) => {
+                let ty = self.expr_type(expr.span).ok_or_else(|| codegen_error("array literal without a type"))?;
+                let array_type = self.llvm_type(&ty)?.into_array_type();
+                let mut array = array_type.get_undef();
+                for (i, value) in self.compile_operands(elements)?.into_iter().enumerate() {
+                    array = self.builder.build_insert_value(array, value, i as u32, "tomb").unwrap().into_array_value();
+                }
+                Ok(array.into())
+            },
+            ExprKind::Index { object, index } if matches!(self.expr_type(index.span), Some(Type::Range(_))) => {
+                self.compile_slicing(object, index)
+            },
+            ExprKind::Index { object, index } => {
+                let element_ptr = self.element_ptr(object, index)?;
+                Ok(self.builder.build_load(element_ptr, "elem"))
+            },
+            ExprKind::Tuple(elements) => {
+                let values = self.compile_operands(elements)?;
+                let types: Vec<_> = values.iter().map(|value| value.get_type()).collect();
+                let mut tuple = self.context.struct_type(&types, false).get_undef();
+                for (i, value) in values.into_iter().enumerate() {
+                    tuple = self.builder.build_insert_value(tuple, value, i as u32, "tuple")
+                        .unwrap()
+                        .into_struct_value();
+                }
+                Ok(tuple.into())
+            },
+            ExprKind::Block(stmts) => self.with_scope(|this| {
+                let function = this.current_function();
+                for stmt in stmts {
+                    this.compile_stmt(stmt, function)?;
+                }
+                Ok(this.unit_value())
+            }),
+            // `ha`/`vagy`: az ágak értéke nem használt, a kifejezés egység értékű
+            ExprKind::If { condition, then_branch, else_branch } => {
+                let function = self.current_function();
+                let condition = self.compile_expr(condition)?.into_int_value();
+                let then_block = self.context.append_basic_block(function, "ha.akkor");
+                let else_block = self.context.append_basic_block(function, "ha.vagy");
+                let end_block = self.context.append_basic_block(function, "ha.vege");
+                self.builder.build_conditional_branch(condition, then_block, else_block);
+
+                self.builder.position_at_end(then_block);
+                self.compile_branch(then_branch, function, end_block)?;
+                self.builder.position_at_end(else_block);
+                self.compile_branch(else_branch.as_deref().unwrap_or_default(), function, end_block)?;
+                self.builder.position_at_end(end_block);
+                Ok(self.unit_value())
+            },
+            ExprKind::Match { scrutinee, arms } => self.compile_match(scrutinee, arms, expr.span),
+            // A tartomány `{ start, end, step, inclusive }` struct, alapértelmezett lépésköze 1
+            ExprKind::Range { start, end, inclusive } => {
+                let bounds = self.compile_operands([start.as_ref(), end.as_ref()])?;
+                let (start, end) = (bounds[0].into_int_value(), bounds[1].into_int_value());
+                let int_type = start.get_type();
+                let bool_type = self.context.bool_type();
+                let range_type = self.context.struct_type(&[int_type.into(), int_type.into(), int_type.into(), bool_type.into()], false);
+                let fields: [BasicValueEnum<'ctx>; 4] = [
+                    start.into(),
+                    end.into(),
+                    int_type.const_int(1, false).into(),
+                    bool_type.const_int(*inclusive as u64, false).into()
+                ];
+                let mut range = range_type.get_undef();
+                for (i, field) in fields.into_iter().enumerate() {
+                    range = self.builder.build_insert_value(range, field, i as u32, "range")
+                        .unwrap()
+                        .into_struct_value();
+                }
+                Ok(range.into())
+            },
+            ExprKind::StructLit { name, fields } => {
+                let struct_type = match self.expr_type(expr.span) {
+                    Some(ty @ Type::Struct(..)) => self.llvm_type(&ty)?.into_struct_type(),
+                    _ => return Err(codegen_error(format!("unknown struct `{}`", name)))
+                };
+                let mut value = struct_type.get_undef();
+                let field_values = self.compile_operands(fields.iter().map(|init| &init.value))?;
+                for (init, field_value) in fields.iter().zip(field_values) {
+                    let index = self.field_index(name, &init.name)?;
+                    value = self.builder.build_insert_value(value, field_value, index, &init.name)
+                        .unwrap()
+                        .into_struct_value();
+                }
+                Ok(value.into())
+            },
+            ExprKind::Field { object, field } => {
+                let Some(Type::Struct(name, _)) = self.expr_type(object.span) else {
+                    return Err(codegen_error(format!("field access `{}` on a non-struct value", field)));
+                };
+                let index = self.field_index(&name, field)?;
+                let value = self.compile_expr(object)?;
+                Ok(self.builder.build_extract_value(value.into_struct_value(), index, field).unwrap())
+            },
+            ExprKind::Closure { params, body, is_move, .. } => self.compile_closure(expr, params, body, *is_move),
+        }
+    }
+
+    fn compile_args(&mut self, args: &[Expr]) -> Result<Vec<BasicMetadataValueEnum<'ctx>>, Diagnostic> {
+        Ok(self.compile_operands(args)?.into_iter().map(|value| value.into()).collect())
+    }
+
+    // A `void` függvények hívása az üres struct értéket (a `()` ábrázolását) adja
+    fn compile_call(&mut self, name: &str, args: &[BasicMetadataValueEnum<'ctx>]) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let function = self.module.get_function(name)
+            .ok_or_else(|| codegen_error(format!("function `{}` is not declared", name)))?;
+        let call = self.builder.build_call(function, args, "calltmp");
+        Ok(call.try_as_basic_value()
+            .left()
+            .unwrap_or_else(|| self.unit_value()))
+    }
+
+    // Lezáráskonverzió: az elkapott változók környezet-structba kerülnek, a törzs pedig
+    // `lezaras.N(env, paraméterek...)` függvénnyé fordul. `mozgat` esetén a környezet a heapen
+    // az értékek másolatát tartalmazza, különben a stacken a változók címét.
+    fn compile_closure(&mut self, expr: &Expr, params: &[Param], body: &Expr, is_move: bool) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let Some(Type::Function(param_types, return_type)) = self.expr_type(expr.span) else {
+            return Err(codegen_error("closure without a function type"));
+        };
+        let captured = self.captures.get(&expr.span).cloned().unwrap_or_default();
+        let mut values: Vec<BasicValueEnum<'ctx>> = Vec::new();
+        for name in &captured {
+            let ptr = *self.variables.get(name)
+                .ok_or_else(|| codegen_error(format!("unknown variable `{}`", name)))?;
+            values.push(if is_move { self.builder.build_load(ptr, name) } else { ptr.into() });
+        }
+
+        let field_types: Vec<BasicTypeEnum<'ctx>> = values.iter().map(|value| value.get_type()).collect();
+        let env_type = self.context.struct_type(&field_types, false);
+        let env = if is_move {
+            let i64_type = self.context.i64_type();
+            let size = i64_type.const_int(self.target_data.get_abi_size(&env_type), false);
+            let data = self.call_runtime("malloc", self.ptr_type().fn_type(&[i64_type.into()], false), &[size.into()]);
+            self.builder.build_pointer_cast(data, env_type.ptr_type(AddressSpace::Generic), "env")
+        } else {
+            self.build_local(env_type.into(), "env")
+        };
+        for (i, (name, value)) in captured.iter().zip(values).enumerate() {
+            let field = self.builder.build_struct_gep(env, i as u32, name).unwrap();
+            self.builder.build_store(field, value);
+        }
+
+        let mut llvm_params: Vec<_> = vec![self.ptr_type().into()];
+        for ty in &param_types {
+            llvm_params.push(self.llvm_type(ty)?.into());
+        }
+        let fn_type = match *return_type {
+            Type::Void => self.context.void_type().fn_type(&llvm_params, false),
+            ref ty => self.llvm_type(ty)?.fn_type(&llvm_params, false)
+        };
+        let function = self.module.add_function(&format!("lezaras.{}", self.closure_count), fn_type, None);
+        self.closure_count += 1;
+
+        let saved_block = self.builder.get_insert_block();
+        let saved_variables = std::mem::take(&mut self.variables);
+        // A lezárás törzse nem része a környező `aszink` függvény állapotgépének
+        let saved_frame = self.async_frame.take();
+        let entry = self.context.append_basic_block(function, "entry");
+        self.builder.position_at_end(entry);
+
+        let env_param = function.get_nth_param(0).unwrap().into_pointer_value();
+        let env_ptr = self.builder.build_pointer_cast(env_param, env_type.ptr_type(AddressSpace::Generic), "env");
+        for (i, name) in captured.iter().enumerate() {
+            let field = self.builder.build_struct_gep(env_ptr, i as u32, name).unwrap();
+            let ptr = if is_move { field } else { self.builder.build_load(field, name).into_pointer_value() };
+            self.variables.insert(name.clone(), ptr);
+        }
+        for (i, param) in params.iter().enumerate() {
+            let value = function.get_nth_param(i as u32 + 1).unwrap();
+            value.set_name(&param.name);
+            let alloca = self.builder.build_alloca(value.get_type(), &param.name);
+            self.builder.build_store(alloca, value);
+            self.variables.insert(param.name.clone(), alloca);
+        }
+
+        let result = match &body.kind {
+            // Blokktörzsnél az érték a `vissza` utasításokból jön
+            ExprKind::Block(stmts) => {
+                for stmt in stmts {
+                    self.compile_stmt(stmt, function)?;
+                }
+                None
+            },
+            _ => Some(self.compile_expr(body)?)
+        };
+        let terminated = self.builder.get_insert_block().and_then(|block| block.get_terminator()).is_some();
+        if !terminated {
+            match (fn_type.get_return_type(), result) {
+                (None, _) => { self.builder.build_return(None); },
+                (Some(_), Some(value)) => { self.builder.build_return(Some(&value)); },
+                (Some(_), None) => { self.builder.build_unreachable(); }
+            }
+        }
+
+        self.variables = saved_variables;
+        self.async_frame = saved_frame;
+        if let Some(block) = saved_block {
+            self.builder.position_at_end(block);
+        }
+        let env = self.builder.build_pointer_cast(env, self.ptr_type(), "env");
+        Ok(self.build_closure_value(function, env))
+    }
+
+    // A lezárás értéke `{ függvénymutató, környezet }`, mindkettő `i8*`
+    fn build_closure_value(&mut self, function: FunctionValue<'ctx>, env: PointerValue<'ctx>) -> BasicValueEnum<'ctx> {
+        let ptr_type = self.ptr_type();
+        let closure_type = self.context.struct_type(&[ptr_type.into(), ptr_type.into()], false);
+        let function = function.as_global_value().as_pointer_value().const_cast(ptr_type);
+        let closure = self.builder.build_insert_value(closure_type.get_undef(), function, 0, "lezaras").unwrap().into_struct_value();
+        self.builder.build_insert_value(closure, env, 1, "lezaras").unwrap().into_struct_value().into()
+    }
+
+    // Globális függvény lezárásként: a környezetet eldobó, a függvényt továbbhívó burkoló
+    fn function_thunk(&mut self, function: FunctionValue<'ctx>) -> Result<FunctionValue<'ctx>, Diagnostic> {
+        let name = format!("{}.lezaras", function.get_name().to_string_lossy());
+        if let Some(thunk) = self.module.get_function(&name) {
+            return Ok(thunk);
+        }
+        let target_type = function.get_type();
+        let mut param_types: Vec<_> = vec![self.ptr_type().into()];
+        param_types.extend(target_type.get_param_types().into_iter().map(|ty| ty.into()));
+        let fn_type = match target_type.get_return_type() {
+            Some(ty) => ty.fn_type(&param_types, false),
+            None => self.context.void_type().fn_type(&param_types, false)
+        };
+        let thunk = self.module.add_function(&name, fn_type, None);
+
+        let saved = self.builder.get_insert_block();
+        let entry = self.context.append_basic_block(thunk, "entry");
+        self.builder.position_at_end(entry);
+        let args: Vec<BasicMetadataValueEnum<'ctx>> = thunk.get_param_iter().skip(1).map(|value| value.into()).collect();
+        let call = self.builder.build_call(function, &args, "calltmp");
+        match call.try_as_basic_value().left() {
+            Some(value) => self.builder.build_return(Some(&value)),
+            None => self.builder.build_return(None)
+        };
+        if let Some(block) = saved {
+            self.builder.position_at_end(block);
+        }
+        Ok(thunk)
+    }
+
+    // Lezárás hívása: a függvénymutató a környezetet kapja első paraméterként
+    fn call_closure(&mut self, closure: BasicValueEnum<'ctx>, values: &[BasicValueEnum<'ctx>], return_type: &Type) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let mut param_types: Vec<_> = vec![self.ptr_type().into()];
+        param_types.extend(values.iter().map(|value| BasicMetadataTypeEnum::from(value.get_type())));
+        let fn_type = match return_type {
+            Type::Void => self.context.void_type().fn_type(&param_types, false),
+            ty => self.llvm_type(ty)?.fn_type(&param_types, false)
+        };
+
+        let closure = closure.into_struct_value();
+        let function = self.builder.build_extract_value(closure, 0, "lezaras.fn").unwrap().into_pointer_value();
+        let env = self.builder.build_extract_value(closure, 1, "lezaras.env").unwrap();
+        let function = self.builder.build_pointer_cast(function, fn_type.ptr_type(AddressSpace::Generic), "lezaras.fn");
+        let callee = CallableValue::try_from(function).map_err(|_| codegen_error("invalid closure function pointer"))?;
+
+        let mut args: Vec<BasicMetadataValueEnum<'ctx>> = vec![env.into()];
+        args.extend(values.iter().map(|value| BasicMetadataValueEnum::from(*value)));
+        let call = self.builder.build_call(callee, &args, "lezarashivas");
+        Ok(call.try_as_basic_value()
+            .left()
+            .unwrap_or_else(|| self.unit_value()))
+    }
+
+    // Metódushívás a fogadó típusa szerint: structon és enumon a (példányosított) metódus,
+    // `dyn Trait` értéken a vtable bejegyzése, primitív típuson a beépített trait metódus
+    fn call_method(&mut self, receiver_type: &Type, method: &str, type_args: &[Type], values: &[BasicValueEnum<'ctx>]) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let args: Vec<BasicMetadataValueEnum<'ctx>> = values.iter().map(|value| (*value).into()).collect();
+        match receiver_type {
+            Type::Struct(type_name, _) | Type::Enum(type_name, _) => {
+                let name = self.instance(Some(type_name), method, type_args)?;
+                self.compile_call(&name, &args)
+            },
+            Type::Dyn(trait_name) => self.call_dyn_method(trait_name, method, values),
+            _ if method == DISPLAY_METHOD => {
+                let ptr_type = self.ptr_type();
+                let string = self.value_to_string(values[0], receiver_type)?;
+                Ok(self.call_runtime("dlang_string_into_cstr", ptr_type.fn_type(&[ptr_type.into()], false), &[string.into()]).into())
+            },
+            _ if method == ORD_METHOD => Ok(self.compare_values(values[0], values[1], receiver_type)?.into()),
+            other => Err(codegen_error(format!("no method `{}` on type `{}`", method, other)))
+        }
+    }
+
+    // A vtable a trait metódusait a deklaráció sorrendjében tartalmazza; a bejegyzések
+    // első paramétere a trait objektum adatmutatója
+    fn call_dyn_method(&mut self, trait_name: &str, method: &str, values: &[BasicValueEnum<'ctx>]) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let (index, function) = self.trait_defs.get(trait_name)
+            .and_then(|def| def.methods.iter().enumerate().find(|(_, item)| item.function.name == method))
+            .map(|(index, item)| (index, item.function.clone()))
+            .ok_or_else(|| codegen_error(format!("no method `{}` in trait `{}`", method, trait_name)))?;
+        let fn_type = self.shim_type(&function, &HashMap::new())?;
+
+        let object = values[0].into_struct_value();
+        let data = self.builder.build_extract_value(object, 0, "dyn.data").unwrap();
+        let vtable = self.builder.build_extract_value(object, 1, "dyn.vtable").unwrap().into_pointer_value();
+        let slots = self.builder.build_pointer_cast(vtable, self.ptr_type().ptr_type(AddressSpace::Generic), "dyn.slots");
+        let index = self.context.i64_type().const_int(index as u64, false);
+        let slot = unsafe { self.builder.build_in_bounds_gep(slots, &[index], "dyn.slot") };
+        let entry = self.builder.build_load(slot, "dyn.entry").into_pointer_value();
+        let entry = self.builder.build_pointer_cast(entry, fn_type.ptr_type(AddressSpace::Generic), "dyn.fn");
+        let callee = CallableValue::try_from(entry).map_err(|_| codegen_error("invalid vtable entry"))?;
+
+        let mut args: Vec<BasicMetadataValueEnum<'ctx>> = vec![data.into()];
+        args.extend(values[1..].iter().map(|value| BasicMetadataValueEnum::from(*value)));
+        let call = self.builder.build_call(callee, &args, "dyncall");
+        Ok(call.try_as_basic_value()
+            .left()
+            .unwrap_or_else(|| self.unit_value()))
+    }
+
+    // Az érték másolata a heapre kerül, mellé a típus vtable-je
+    fn build_trait_object(&mut self, value: BasicValueEnum<'ctx>, ty: &Type, trait_name: &str) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let ptr_type = self.ptr_type();
+        let i64_type = self.context.i64_type();
+        let size = i64_type.const_int(self.target_data.get_abi_size(&value.get_type()), false);
+        let data = self.call_runtime("malloc", ptr_type.fn_type(&[i64_type.into()], false), &[size.into()]);
+        let typed = self.builder.build_pointer_cast(data, value.get_type().ptr_type(AddressSpace::Generic), "dyn.value");
+        self.builder.build_store(typed, value);
+
+        let vtable = self.vtable(ty, trait_name)?;
+        let object_type = self.llvm_type(&Type::Dyn(trait_name.to_string()))?.into_struct_type();
+        let object = self.builder.build_insert_value(object_type.get_undef(), data, 0, "dyn").unwrap().into_struct_value();
+        Ok(self.builder.build_insert_value(object, vtable, 1, "dyn").unwrap().into_struct_value().into())
+    }
+
+    // Típusonként és traitenként egy konstans vtable, az első használatkor létrehozva
+    fn vtable(&mut self, ty: &Type, trait_name: &str) -> Result<PointerValue<'ctx>, Diagnostic> {
+        let ptr_type = self.ptr_type();
+        let name = format!("vtable.{}.{}", ty, trait_name);
+        if let Some(global) = self.module.get_global(&name) {
+            return Ok(global.as_pointer_value().const_cast(ptr_type));
+        }
+        let methods: Vec<Function> = self.trait_defs.get(trait_name)
+            .map(|def| def.methods.iter().map(|item| item.function.clone()).collect())
+            .unwrap_or_default();
+        let mut entries = Vec::new();
+        for method in &methods {
+            let shim = self.vtable_shim(ty, &name, method)?;
+            entries.push(shim.as_global_value().as_pointer_value().const_cast(ptr_type));
+        }
+        let table = ptr_type.const_array(&entries);
+        let global = self.module.add_global(table.get_type(), None, &name);
+        global.set_initializer(&table);
+        global.set_constant(true);
+        Ok(global.as_pointer_value().const_cast(ptr_type))
+    }
+
+    // A vtable bejegyzése: az adatmutatóról betölti az értéket, és a típus metódusát hívja
+    fn vtable_shim(&mut self, ty: &Type, vtable: &str, method: &Function) -> Result<FunctionValue<'ctx>, Diagnostic> {
+        let subst = HashMap::from([(SELF_TYPE.to_string(), ty.clone())]);
+        let fn_type = self.shim_type(method, &subst)?;
+        let shim = self.module.add_function(&format!("{}.{}", vtable, method.name), fn_type, None);
+
+        let saved = self.builder.get_insert_block();
+        let entry = self.context.append_basic_block(shim, "entry");
+        self.builder.position_at_end(entry);
+        let value_type = self.llvm_type(ty)?;
+        let data = shim.get_nth_param(0).unwrap().into_pointer_value();
+        let typed = self.builder.build_pointer_cast(data, value_type.ptr_type(AddressSpace::Generic), "self");
+        let mut values = vec![self.builder.build_load(typed, "self")];
+        values.extend(shim.get_param_iter().skip(1));
+        let type_args = match ty {
+            Type::Struct(_, args) | Type::Enum(_, args) => args.clone(),
+            _ => Vec::new()
+        };
+        let result = self.call_method(ty, &method.name, &type_args, &values)?;
+        if fn_type.get_return_type().is_some() {
+            self.builder.build_return(Some(&result));
+        } else {
+            self.builder.build_return(None);
+        }
+
+        if let Some(block) = saved {
+            self.builder.position_at_end(block);
+        }
+        Ok(shim)
+    }
+
+    // A trait metódusának vtable-beli típusa: a `self` helyén adatmutató
+    fn shim_type(&mut self, method: &Function, subst: &HashMap<String, Type>) -> Result<FunctionType<'ctx>, Diagnostic> {
+        let mut param_types: Vec<_> = vec![self.ptr_type().into()];
+        for param in &method.params[1..] {
+            let ty = match &param.type_annot {
+                Some(annot) => self.annot_type(annot, subst)?,
+                None => self.expr_types.get(&param.span).map(|ty| ty.substitute(subst))
+                    .ok_or_else(|| codegen_error("missing type annotation"))?
+            };
+            param_types.push(self.llvm_type(&ty)?.into());
+        }
+        Ok(match &method.return_type {
+            Some(annot) => {
+                let ty = self.annot_type(annot, subst)?;
+                self.llvm_type(&ty)?.fn_type(&param_types, false)
+            },
+            None => self.context.void_type().fn_type(&param_types, false)
+        })
+    }
+
+    // Kétoperandusú művelet az operandusok típusa szerint (az összetett értékadás is ezt használja)
+    fn compile_binary(&mut self, op: BinaryOp, lhs: BasicValueEnum<'ctx>, rhs: BasicValueEnum<'ctx>, ty: &Type) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        match (op, ty) {
+            (BinaryOp::Eq | BinaryOp::Neq | BinaryOp::Lt | BinaryOp::Gt | BinaryOp::Le | BinaryOp::Ge, _) => {
+                Ok(self.compile_comparison(op, lhs, rhs, ty)?.into())
+            },
+            (_, Type::Float(_)) => {
+                let (lhs, rhs) = (lhs.into_float_value(), rhs.into_float_value());
+                Ok(match op {
+                    BinaryOp::Add => self.builder.build_float_add(lhs, rhs, "faddtmp"),
+                    BinaryOp::Sub => self.builder.build_float_sub(lhs, rhs, "fsubtmp"),
+                    BinaryOp::Mul => self.builder.build_float_mul(lhs, rhs, "fmultmp"),
+                    _ => self.builder.build_float_div(lhs, rhs, "fdivtmp")
+                }.into())
+            },
+            (_, Type::Int(int_ty)) => {
+                let (lhs, rhs) = (lhs.into_int_value(), rhs.into_int_value());
+                Ok(match op {
+                    BinaryOp::Add => self.builder.build_int_add(lhs, rhs, "addtmp"),
+                    BinaryOp::Sub => self.builder.build_int_sub(lhs, rhs, "subtmp"),
+                    BinaryOp::Mul => self.builder.build_int_mul(lhs, rhs, "multmp"),
+                    _ => self.compile_int_division(lhs, rhs, *int_ty)
+                }.into())
+            },
+            _ => Err(codegen_error(format!("invalid operands for arithmetic on `{}`", ty)))
+        }
+    }
+
+    // `&&`, `||` rövidzárral: a jobb oldal csak akkor fut, ha a bal oldal nem dönti el az
+    // eredményt (mint a `konst` kiértékelésénél), így `i < t.len() && t[i] == 0` nem indexel túl
+    fn compile_logical(&mut self, op: BinaryOp, left: &Expr, right: &Expr) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let function = self.current_function();
+        let lhs = self.compile_expr(left)?.into_int_value();
+        let lhs_block = self.builder.get_insert_block().unwrap();
+        let rhs_block = self.context.append_basic_block(function, "logikai.jobb");
+        let end_block = self.context.append_basic_block(function, "logikai.vege");
+        // `&&`-nél a hamis, `||`-nél az igaz bal oldal az eredmény
+        let decided = match op {
+            BinaryOp::And => {
+                self.builder.build_conditional_branch(lhs, rhs_block, end_block);
+                self.context.bool_type().const_zero()
+            },
+            _ => {
+                self.builder.build_conditional_branch(lhs, end_block, rhs_block);
+                self.context.bool_type().const_all_ones()
+            }
+        };
+
+        self.builder.position_at_end(rhs_block);
+        let rhs = self.compile_expr(right)?;
+        let rhs_end = self.builder.get_insert_block().unwrap();
+        let rhs_reaches_end = rhs_end.get_terminator().is_none() && self.expr_type(right.span) != Some(Type::Never);
+        if rhs_reaches_end {
+            self.builder.build_unconditional_branch(end_block);
+        } else if rhs_end.get_terminator().is_none() {
+            self.builder.build_unreachable();
+        }
+
+        self.builder.position_at_end(end_block);
+        let phi = self.builder.build_phi(self.context.bool_type(), "logikai");
+        phi.add_incoming(&[(&decided, lhs_block)]);
+        if rhs_reaches_end {
+            phi.add_incoming(&[(&rhs.into_int_value(), rhs_end)]);
+        }
+        Ok(phi.as_basic_value())
+    }
+
+    // Egészosztás: a nullával osztás és az előjeles `MIN / -1` futásidejű hiba
+    fn compile_int_division(&mut self, lhs: IntValue<'ctx>, rhs: IntValue<'ctx>, int_ty: IntTy) -> IntValue<'ctx> {
+        let int_type = lhs.get_type();
+        let nonzero = self.builder.build_int_compare(IntPredicate::NE, rhs, int_type.const_zero(), "oszto.ok");
+        self.build_panic_check(nonzero, "attempt to divide by zero");
+        if !int_ty.is_signed() {
+            return self.builder.build_int_unsigned_div(lhs, rhs, "udivtmp");
+        }
+
+        let min = int_ty.min_magnitude();
+        let is_min = self.builder.build_int_compare(IntPredicate::EQ, lhs, int_type.const_int_arbitrary_precision(&[min as u64, (min >> 64) as u64]), "min");
+        let is_minus_one = self.builder.build_int_compare(IntPredicate::EQ, rhs, int_type.const_all_ones(), "minusz.egy");
+        let overflow = self.builder.build_and(is_min, is_minus_one, "tulcsordulas");
+        let ok = self.builder.build_not(overflow, "osztas.ok");
+        self.build_panic_check(ok, "attempt to divide with overflow");
+        self.builder.build_int_signed_div(lhs, rhs, "sdivtmp")
+    }
+
+    // `x as T`: a cél típusban el nem férő érték futásidejű hiba (konstansban fordítási hiba)
+    fn compile_cast(&mut self, operand: &Expr, target: &Type) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let value = self.compile_expr(operand)?;
+        let source = self.expr_type(operand.span).ok_or_else(|| codegen_error("cast of an untyped value"))?;
+        Ok(match (&source, target) {
+            (Type::Bool, Type::Int(to)) => self.builder.build_int_z_extend(value.into_int_value(), self.int_type(*to), "cast").into(),
+            // A karakter előjel nélküli 32 bites skalárérték
+            (Type::Char, Type::Int(to)) => self.int_cast(value.into_int_value(), IntTy::U32, *to).into(),
+            (Type::Int(IntTy::U8), Type::Char) => self.builder.build_int_z_extend(value.into_int_value(), self.context.i32_type(), "cast").into(),
+            (Type::Int(from), Type::Int(to)) => self.int_cast(value.into_int_value(), *from, *to).into(),
+            (Type::Int(from), Type::Float(to)) if from.is_signed() => {
+                self.builder.build_signed_int_to_float(value.into_int_value(), self.float_type(*to), "cast").into()
+            },
+            (Type::Int(_), Type::Float(to)) => self.builder.build_unsigned_int_to_float(value.into_int_value(), self.float_type(*to), "cast").into(),
+            (Type::Float(_), Type::Float(to)) => self.builder.build_float_cast(value.into_float_value(), self.float_type(*to), "cast").into(),
+            (Type::Float(_), Type::Int(to)) => self.float_to_int(value.into_float_value(), *to).into(),
+            _ => value
+        })
+    }
+
+    fn int_cast(&mut self, value: IntValue<'ctx>, from: IntTy, to: IntTy) -> IntValue<'ctx> {
+        let target_type = self.int_type(to);
+        let result = match from.bits().cmp(&to.bits()) {
+            std::cmp::Ordering::Less if from.is_signed() => self.builder.build_int_s_extend(value, target_type, "cast"),
+            std::cmp::Ordering::Less => self.builder.build_int_z_extend(value, target_type, "cast"),
+            std::cmp::Ordering::Greater => self.builder.build_int_truncate(value, target_type, "cast"),
+            std::cmp::Ordering::Equal => value
+        };
+        if to.contains(from) {
+            return result;
+        }
+
+        // Szűkítésnél visszaalakítva az eredeti értéket kell kapni, és az előjel sem változhat
+        let mut ok = self.context.bool_type().const_int(1, false);
+        if to.bits() < from.bits() {
+            let back = if to.is_signed() {
+                self.builder.build_int_s_extend(result, value.get_type(), "cast.vissza")
+            } else {
+                self.builder.build_int_z_extend(result, value.get_type(), "cast.vissza")
+            };
+            ok = self.builder.build_int_compare(IntPredicate::EQ, back, value, "cast.ok");
+        }
+        if from.is_signed() != to.is_signed() {
+            let signed_value = if from.is_signed() { value } else { result };
+            let non_negative = self.builder.build_int_compare(IntPredicate::SGE, signed_value, signed_value.get_type().const_zero(), "cast.elojel");
+            ok = self.builder.build_and(ok, non_negative, "cast.ok");
+        }
+        self.build_panic_check(ok, &format!("cast overflow: the value does not fit in `{}`", to.name()));
+        result
+    }
+
+    // A tört rész elvész; a NaN és a tartományon kívüli érték futásidejű hiba
+    fn float_to_int(&mut self, value: FloatValue<'ctx>, to: IntTy) -> IntValue<'ctx> {
+        let float_type = value.get_type();
+        let (lower, lower_predicate, upper) = if to.is_signed() {
+            (-(2f64.powi(to.bits() as i32 - 1)), FloatPredicate::OGE, 2f64.powi(to.bits() as i32 - 1))
+        } else {
+            (-1.0, FloatPredicate::OGT, 2f64.powi(to.bits() as i32))
+        };
+        let above = self.builder.build_float_compare(lower_predicate, value, float_type.const_float(lower), "cast.also");
+        let below = self.builder.build_float_compare(FloatPredicate::OLT, value, float_type.const_float(upper), "cast.felso");
+        let ok = self.builder.build_and(above, below, "cast.ok");
+        self.build_panic_check(ok, &format!("cast overflow: the value does not fit in `{}`", to.name()));
+        if to.is_signed() {
+            self.builder.build_float_to_signed_int(value, self.int_type(to), "cast")
+        } else {
+            self.builder.build_float_to_unsigned_int(value, self.int_type(to), "cast")
+        }
+    }
+
+    // Az értékadás bal oldalának címe: változó, vagy egy változó (akár beágyazott) mezője
+    fn compile_place(&mut self, expr: &Expr) -> Result<PointerValue<'ctx>, Diagnostic> {
+        match &expr.kind {
+            ExprKind::Ident(name) => self.variables.get(name).copied()
+                .ok_or_else(|| codegen_error(format!("unknown variable `{}`", name))),
+            ExprKind::Field { object, field } => {
+                let object_ptr = self.compile_place(object)?;
+                let Some(Type::Struct(name, _)) = self.expr_type(object.span) else {
+                    return Err(codegen_error("field assignment on a non-struct value"));
+                };
+                let index = self.field_index(&name, field)?;
+                Ok(self.builder.build_struct_gep(object_ptr, index, field).unwrap())
+            },
+            ExprKind::Index { object, index } => self.element_ptr(object, index),
+            _ => Err(codegen_error("invalid left-hand side of assignment"))
+        }
+    }
+
+    // Hely-e a kifejezés (változó, vagy annak mezője, eleme); a rögzített tömböt ilyenkor nem másoljuk
+    fn is_place(&self, expr: &Expr) -> bool {
+        match &expr.kind {
+            ExprKind::Ident(name) => self.variables.contains_key(name),
+            ExprKind::Field { object, .. } | ExprKind::Index { object, .. } => self.is_place(object),
+            _ => false
+        }
+    }
+
+    // Tömbszerű érték első elemének címe és a hossza (`usize`)
+    fn array_parts(&mut self, object: &Expr) -> Result<(PointerValue<'ctx>, IntValue<'ctx>), Diagnostic> {
+        let ty = self.expr_type(object.span).ok_or_else(|| codegen_error("indexing an untyped value"))?;
+        match &ty {
+            Type::Array(element, len) if self.is_place(object) => {
+                let array = self.compile_place(object)?;
+                let element_type = self.llvm_type(element)?.ptr_type(AddressSpace::Generic);
+                let base = self.builder.build_pointer_cast(array, element_type, "tomb.adat");
+                Ok((base, self.int_type(IntTy::Usize).const_int(*len, false)))
+            },
+            _ => {
+                let value = self.compile_expr(object)?;
+                self.value_parts(value, &ty)
+            }
+        }
+    }
+
+    // Mint az `array_parts`, de már kiértékelt értékre; a rögzített tömb ideiglenes helyre kerül
+    fn value_parts(&mut self, value: BasicValueEnum<'ctx>, ty: &Type) -> Result<(PointerValue<'ctx>, IntValue<'ctx>), Diagnostic> {
+        let usize_type = self.int_type(IntTy::Usize);
+        let element = array_element(ty).ok_or_else(|| codegen_error(format!("`{}` is not an array", ty)))?;
+        let element_type = self.llvm_type(&element)?.ptr_type(AddressSpace::Generic);
+        match ty {
+            Type::Array(_, len) => {
+                let slot = self.build_local(value.get_type(), "tomb");
+                self.builder.build_store(slot, value);
+                let base = self.builder.build_pointer_cast(slot, element_type, "tomb.adat");
+                Ok((base, usize_type.const_int(*len, false)))
+            },
+            Type::Slice(_) => {
+                let slice = value.into_struct_value();
+                let base = self.builder.build_extract_value(slice, 0, "szelet.adat").unwrap().into_pointer_value();
+                let len = self.builder.build_extract_value(slice, 1, "szelet.hossz").unwrap().into_int_value();
+                Ok((base, len))
+            },
+            _ => {
+                let array = self.dyn_array_handle(value);
+                let ptr_type = self.ptr_type();
+                let data = self.call_runtime("dlang_array_data", ptr_type.fn_type(&[ptr_type.into()], false), &[array.into()]);
+                let base = self.builder.build_pointer_cast(data, element_type, "tomb.adat");
+                let len_fn = self.runtime_fn("dlang_array_len", usize_type.fn_type(&[ptr_type.into()], false));
+                let len = self.builder.build_call(len_fn, &[array.into()], "tomb.hossz")
+                    .try_as_basic_value()
+                    .left()
+                    .unwrap()
+                    .into_int_value();
+                Ok((base, len))
+            }
+        }
+    }
+
+    // A `Tomb<T>` értékből a runtime `DlangArray` címe
+    fn dyn_array_handle(&self, value: BasicValueEnum<'ctx>) -> PointerValue<'ctx> {
+        let address = self.builder.build_extract_value(value.into_struct_value(), 0, "tomb.kezelo").unwrap().into_int_value();
+        self.builder.build_int_to_ptr(address, self.ptr_type(), "tomb")
+    }
+
+    fn build_slice(&mut self, base: PointerValue<'ctx>, len: IntValue<'ctx>) -> BasicValueEnum<'ctx> {
+        let slice_type = self.context.struct_type(&[base.get_type().into(), len.get_type().into()], false);
+        let slice = self.builder.build_insert_value(slice_type.get_undef(), base, 0, "szelet").unwrap();
+        self.builder.build_insert_value(slice, len, 1, "szelet").unwrap().into_struct_value().into()
+    }
+
+    // `[...]` literálból új `Tomb<T>`: a runtime tömb a literál elemeinek másolatával indul
+    fn build_dyn_array(&mut self, literal: inkwell::values::ArrayValue<'ctx>, ty: &Type) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let element = array_element(ty).ok_or_else(|| codegen_error(format!("`{}` is not an array", ty)))?;
+        let element_type = self.llvm_type(&element)?;
+        let usize_type = self.int_type(IntTy::Usize);
+        let ptr_type = self.ptr_type();
+        let len = literal.get_type().len() as u64;
+        let element_size = usize_type.const_int(self.target_data.get_abi_size(&element_type), false);
+        let new_fn = ptr_type.fn_type(&[usize_type.into(), usize_type.into()], false);
+        let array = self.call_runtime("dlang_array_new", new_fn, &[element_size.into(), usize_type.const_int(len, false).into()]);
+
+        let push = self.runtime_fn("dlang_array_push", self.context.void_type().fn_type(&[ptr_type.into(), ptr_type.into()], false));
+        let (base, _) = self.value_parts(literal.into(), &Type::Array(Box::new(element), len))?;
+        for i in 0..len {
+            let element_ptr = unsafe { self.builder.build_gep(base, &[usize_type.const_int(i, false)], "tomb.elem") };
+            let element_ptr = self.builder.build_pointer_cast(element_ptr, ptr_type, "tomb.elem");
+            self.builder.build_call(push, &[array.into(), element_ptr.into()], "");
+        }
+
+        let address = self.builder.build_ptr_to_int(array, usize_type, "tomb.kezelo");
+        let handle_type = self.llvm_type(ty)?.into_struct_type();
+        Ok(self.builder.build_insert_value(handle_type.get_undef(), address, 0, "tomb").unwrap().into_struct_value().into())
+    }
+
+    // Egész index `usize` szélességre hozva; a negatív érték így a hossznál nagyobb lesz
+    fn index_value(&mut self, index: &Expr) -> Result<IntValue<'ctx>, Diagnostic> {
+        let value = self.compile_expr(index)?.into_int_value();
+        let signed = matches!(self.expr_type(index.span), Some(Type::Int(int_ty)) if int_ty.is_signed());
+        Ok(self.usize_value(value, signed))
+    }
+
+    fn usize_value(&self, value: IntValue<'ctx>, signed: bool) -> IntValue<'ctx> {
+        let usize_type = self.int_type(IntTy::Usize);
+        match value.get_type().get_bit_width() {
+            64 => value,
+            bits if bits > 64 => self.builder.build_int_truncate(value, usize_type, "index"),
+            _ if signed => self.builder.build_int_s_extend(value, usize_type, "index"),
+            _ => self.builder.build_int_z_extend(value, usize_type, "index")
+        }
+    }
+
+    // `t[i]` címe a határellenőrzés után
+    fn element_ptr(&mut self, object: &Expr, index: &Expr) -> Result<PointerValue<'ctx>, Diagnostic> {
+        let (base, len) = self.array_parts(object)?;
+        let mut live: [BasicValueEnum<'ctx>; 2] = [base.into(), len.into()];
+        let index = self.across_await(&mut live, index, |this| this.index_value(index))?;
+        let (base, len) = (live[0].into_pointer_value(), live[1].into_int_value());
+        let in_bounds = self.builder.build_int_compare(IntPredicate::ULT, index, len, "index.ok");
+        self.build_bounds_check(in_bounds, "dlang_panic_bounds", &[index, len]);
+        Ok(unsafe { self.builder.build_gep(base, &[index], "elem") })
+    }
+
+    // `t[a..b]`: szelet a tömb egy szakaszára; `a <= b <= hossz` futásidőben ellenőrizve
+    fn compile_slicing(&mut self, object: &Expr, range: &Expr) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let (base, len) = self.array_parts(object)?;
+        let signed = matches!(self.expr_type(range.span), Some(Type::Range(bound)) if matches!(*bound, Type::Int(int_ty) if int_ty.is_signed()));
+        let mut live: [BasicValueEnum<'ctx>; 2] = [base.into(), len.into()];
+        let range = self.across_await(&mut live, range, |this| this.compile_expr(range))?.into_struct_value();
+        let (base, len) = (live[0].into_pointer_value(), live[1].into_int_value());
+        let start = self.builder.build_extract_value(range, 0, "szelet.eleje").unwrap().into_int_value();
+        let end = self.builder.build_extract_value(range, 1, "szelet.vege").unwrap().into_int_value();
+        let inclusive = self.builder.build_extract_value(range, 3, "szelet.zart").unwrap().into_int_value();
+        let start = self.usize_value(start, signed);
+        let end = self.usize_value(end, signed);
+        let usize_type = self.int_type(IntTy::Usize);
+        // Zárt tartománynál az `end + 1` csak `end < len` esetén nem csordul túl
+        let fits_closed = self.builder.build_int_compare(IntPredicate::ULT, end, len, "szelet.ok");
+        let fits_open = self.builder.build_int_compare(IntPredicate::ULE, end, len, "szelet.ok");
+        let fits = self.builder.build_select(inclusive, fits_closed, fits_open, "szelet.ok").into_int_value();
+        let end_closed = self.builder.build_int_add(end, usize_type.const_int(1, false), "szelet.vege");
+        let end_exclusive = self.builder.build_select(inclusive, end_closed, end, "szelet.vege").into_int_value();
+
+        let ordered = self.builder.build_int_compare(IntPredicate::ULE, start, end_exclusive, "szelet.ok");
+        let valid = self.builder.build_and(ordered, fits, "szelet.ok");
+        // Túlnyúló zárt tartománynál a megadott végindexet jelentjük
+        let reported_end = self.builder.build_select(fits, end_exclusive, end, "szelet.vege").into_int_value();
+        self.build_bounds_check(valid, "dlang_panic_slice", &[start, reported_end, len]);
+        let end = end_exclusive;
+
+        let slice_base = unsafe { self.builder.build_gep(base, &[start], "szelet.adat") };
+        let slice_len = self.builder.build_int_sub(end, start, "szelet.hossz");
+        Ok(self.build_slice(slice_base, slice_len))
+    }
+
+    // Hamis feltételnél a runtime hibakezelője leállítja a programot (`dlang_panic_*`)
+    fn build_bounds_check(&mut self, ok: IntValue<'ctx>, handler: &str, args: &[IntValue<'ctx>]) {
+        let function = self.current_function();
+        let fail_block = self.context.append_basic_block(function, "index.hiba");
+        let ok_block = self.context.append_basic_block(function, "index.ok");
+        self.builder.build_conditional_branch(ok, ok_block, fail_block);
+
+        self.builder.position_at_end(fail_block);
+        let usize_type = self.int_type(IntTy::Usize);
+        let param_types: Vec<BasicMetadataTypeEnum> = args.iter().map(|_| usize_type.into()).collect();
+        let panic = self.runtime_fn(handler, self.context.void_type().fn_type(&param_types, false));
+        let args: Vec<BasicMetadataValueEnum> = args.iter().map(|&arg| arg.into()).collect();
+        self.builder.build_call(panic, &args, "");
+        self.builder.build_unreachable();
+
+        self.builder.position_at_end(ok_block);
+    }
+
+    // Futásidejű ellenőrzés: hamis feltételnél `dlang_panic` az üzenettel
+    fn build_panic_check(&mut self, ok: IntValue<'ctx>, message: &str) {
+        let function = self.current_function();
+        let fail_block = self.context.append_basic_block(function, "ellenorzes.hiba");
+        let ok_block = self.context.append_basic_block(function, "ellenorzes.ok");
+        self.builder.build_conditional_branch(ok, ok_block, fail_block);
+
+        self.builder.position_at_end(fail_block);
+        let message = self.builder.build_global_string_ptr(message, "hiba.uzenet");
+        let panic = self.runtime_fn("dlang_panic", self.context.void_type().fn_type(&[self.ptr_type().into()], false));
+        self.builder.build_call(panic, &[message.as_pointer_value().into()], "");
+        self.builder.build_unreachable();
+
+        self.builder.position_at_end(ok_block);
+    }
+
+    // Összehasonlítás; a primitív típusok közvetlenül, a többiek az `osszehasonlit` eredményén keresztül
+    fn compile_comparison(&mut self, op: BinaryOp, left: BasicValueEnum<'ctx>, right: BasicValueEnum<'ctx>, ty: &Type) -> Result<IntValue<'ctx>, Diagnostic> {
+        let signed = !matches!(ty, Type::Int(int_ty) if !int_ty.is_signed()) && !matches!(ty, Type::Bool | Type::Char);
+        let (int_predicate, float_predicate) = match op {
+            BinaryOp::Eq => (IntPredicate::EQ, FloatPredicate::OEQ),
+            BinaryOp::Neq => (IntPredicate::NE, FloatPredicate::UNE),
+            BinaryOp::Lt => (if signed { IntPredicate::SLT } else { IntPredicate::ULT }, FloatPredicate::OLT),
+            BinaryOp::Gt => (if signed { IntPredicate::SGT } else { IntPredicate::UGT }, FloatPredicate::OGT),
+            BinaryOp::Le => (if signed { IntPredicate::SLE } else { IntPredicate::ULE }, FloatPredicate::OLE),
+            BinaryOp::Ge => (if signed { IntPredicate::SGE } else { IntPredicate::UGE }, FloatPredicate::OGE),
+            _ => return Err(codegen_error("not a comparison operator"))
+        };
+        Ok(match ty {
+            Type::Int(_) | Type::Bool | Type::Char => {
+                self.builder.build_int_compare(int_predicate, left.into_int_value(), right.into_int_value(), "cmptmp")
+            },
+            Type::Float(_) => {
+                self.builder.build_float_compare(float_predicate, left.into_float_value(), right.into_float_value(), "cmptmp")
+            },
+            _ => {
+                let order = self.compare_values(left, right, ty)?;
+                self.builder.build_int_compare(int_predicate, order, order.get_type().const_zero(), "cmptmp")
+            }
+        })
+    }
+
+    // A beépített és a felhasználói `osszehasonlit`: negatív, nulla vagy pozitív i32
+    fn compare_values(&mut self, left: BasicValueEnum<'ctx>, right: BasicValueEnum<'ctx>, ty: &Type) -> Result<IntValue<'ctx>, Diagnostic> {
+        let i32_type = self.context.i32_type();
+        let (less, greater) = match ty {
+            Type::Int(_) | Type::Bool | Type::Char => {
+                let signed = matches!(ty, Type::Int(int_ty) if int_ty.is_signed());
+                let (lt, gt) = if signed { (IntPredicate::SLT, IntPredicate::SGT) } else { (IntPredicate::ULT, IntPredicate::UGT) };
+                let (left, right) = (left.into_int_value(), right.into_int_value());
+                (self.builder.build_int_compare(lt, left, right, "lt"), self.builder.build_int_compare(gt, left, right, "gt"))
+            },
+            Type::Float(_) => {
+                let (left, right) = (left.into_float_value(), right.into_float_value());
+                (
+                    self.builder.build_float_compare(FloatPredicate::OLT, left, right, "lt"),
+                    self.builder.build_float_compare(FloatPredicate::OGT, left, right, "gt")
+                )
+            },
+            Type::String => {
+                let ptr_type = self.ptr_type();
+                let strcmp = self.runtime_fn("strcmp", i32_type.fn_type(&[ptr_type.into(), ptr_type.into()], false));
+                return Ok(self.builder.build_call(strcmp, &[left.into(), right.into()], "strcmp")
+                    .try_as_basic_value()
+                    .left()
+                    .unwrap()
+                    .into_int_value());
+            },
+            Type::Struct(_, args) | Type::Enum(_, args) => {
+                return Ok(self.call_method(ty, ORD_METHOD, args, &[left, right])?.into_int_value());
+            },
+            other => return Err(codegen_error(format!("cannot compare values of type `{}`", other)))
+        };
+        let less = self.builder.build_int_z_extend(less, i32_type, "lt");
+        let greater = self.builder.build_int_z_extend(greater, i32_type, "gt");
+        Ok(self.builder.build_int_sub(greater, less, "order"))
+    }
+
+    fn unit_value(&self) -> BasicValueEnum<'ctx> {
+        self.context.struct_type(&[], false).const_zero().into()
+    }
+
+    fn current_function(&self) -> FunctionValue<'ctx> {
+        self.builder.get_insert_block().and_then(|block| block.get_parent()).unwrap()
+    }
+
+    // Az `eset` döntési faként fordul: minden vizsgált értéket (oszlopot) egyszer bont szét,
+    // enum-változatra és egész literálra `switch`-csel ágazik el
+    fn compile_match(&mut self, scrutinee: &Expr, arms: &[MatchArm], span: Span) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let function = self.current_function();
+        let value = self.compile_expr(scrutinee)?;
+
+        let wildcard = Pattern { kind: PatternKind::Wildcard, span: scrutinee.span };
+        let mut state = MatchArms {
+            arms,
+            blocks: arms.iter().map(|_| self.context.append_basic_block(function, "match.arm")).collect(),
+            slots: vec![Vec::new(); arms.len()],
+            reached: vec![false; arms.len()],
+            wildcard: &wildcard
+        };
+        let rows = arms.iter()
+            .enumerate()
+            .map(|(arm, MatchArm { pattern, .. })| MatchRow { patterns: vec![pattern], bindings: Vec::new(), arm })
+            .collect();
+        self.compile_decision(vec![value], rows, &mut state)?;
+        let end_block = self.context.append_basic_block(function, "match.end");
+
+        let mut incoming: Vec<(BasicValueEnum<'ctx>, BasicBlock<'ctx>)> = Vec::new();
+        for (i, arm) in arms.iter().enumerate() {
+            self.builder.position_at_end(state.blocks[i]);
+            // A döntési fa által el nem ért (felesleges) ág törzse nem fordul le
+            if !state.reached[i] {
+                self.builder.build_unreachable();
+                continue;
+            }
+            let slots = std::mem::take(&mut state.slots[i]);
+            let arm_value = self.with_scope(|this| {
+                this.variables.extend(slots);
+                this.compile_expr(&arm.body)
+            })?;
+            // A `vissza`-val végződő ág nem folytatódik az illesztés utáni blokkban; a soha vissza
+            // nem térő (`!` típusú) ág értéke sem kerül a phi-be, a blokkja elérhetetlen
+            let current = self.builder.get_insert_block().unwrap();
+            if current.get_terminator().is_some() {
+                continue;
+            }
+            if self.expr_type(arm.body.span) == Some(Type::Never) {
+                self.builder.build_unreachable();
+            } else {
+                incoming.push((arm_value, current));
+                self.builder.build_unconditional_branch(end_block);
+            }
+        }
+
+        self.builder.position_at_end(end_block);
+        let result_type = self.expr_type(span).unwrap_or(Type::Void);
+        if result_type == Type::Void || incoming.is_empty() {
+            return Ok(self.unit_value());
+        }
+        let phi = self.builder.build_phi(incoming[0].0.get_type(), "match.value");
+        for (arm_value, block) in &incoming {
+            phi.add_incoming(&[(arm_value as &dyn BasicValue<'ctx>, *block)]);
+        }
+        Ok(phi.as_basic_value())
+    }
+
+    // A döntési fa egy csomópontja. A csupa `_` első sor levél; különben az első sor első
+    // vizsgálandó oszlopa dönt: a tuple és a struct mezőire bomlik, az enum a változatai, az egész
+    // literál az oszlop literáljai szerint ágazik el, a többi mintát egyenként vizsgáljuk
+    fn compile_decision<'p>(
+        &mut self,
+        occurrences: Vec<BasicValueEnum<'ctx>>,
+        mut rows: Vec<MatchRow<'p, 'ctx>>,
+        state: &mut MatchArms<'p, 'ctx>
+    ) -> Result<(), Diagnostic> {
+        // A kötés az oszlop értékét kapja, a helyén `_` marad
+        for row in &mut rows {
+            for (pattern, value) in row.patterns.iter_mut().zip(&occurrences) {
+                if let PatternKind::Binding(name) = &pattern.kind {
+                    row.bindings.push((name.clone(), *value));
+                    *pattern = state.wildcard;
+                }
+            }
+        }
+        // A típusellenőrző szerint az illesztés teljes, így ide nem juthat a vezérlés
+        let Some(first) = rows.first() else {
+            self.builder.build_unreachable();
+            return Ok(());
+        };
+        let Some(column) = first.patterns.iter().position(|pattern| !matches!(pattern.kind, PatternKind::Wildcard)) else {
+            return self.compile_match_leaf(occurrences, rows, state);
+        };
+        let pattern = first.patterns[column];
+        let wildcard = state.wildcard;
+        let function = self.current_function();
+
+        match &pattern.kind {
+            PatternKind::Tuple(elements) => {
+                let value = occurrences[column].into_struct_value();
+                let fields = (0..elements.len() as u32)
+                    .map(|i| self.builder.build_extract_value(value, i, "field").unwrap())
+                    .collect();
+                let rows = specialize_rows(&rows, column, elements.len(), wildcard, |pattern| match &pattern.kind {
+                    PatternKind::Tuple(elements) => Some(elements.iter().collect()),
+                    _ => None
+                });
+                self.compile_decision(replace_column(&occurrences, column, fields), rows, state)
+            },
+            PatternKind::Struct { name, .. } => {
+                let count = self.struct_fields.get(name).map_or(0, Vec::len);
+                let value = occurrences[column].into_struct_value();
+                let fields = (0..count as u32)
+                    .map(|i| self.builder.build_extract_value(value, i, "field").unwrap())
+                    .collect();
+                let rows = specialize_rows(&rows, column, count, wildcard, |pattern| {
+                    let PatternKind::Struct { name, fields, .. } = &pattern.kind else { return None };
+                    let mut subpatterns = vec![wildcard; count];
+                    for field in fields {
+                        subpatterns[self.field_index(name, &field.name).ok()? as usize] = &field.pattern;
+                    }
+                    Some(subpatterns)
+                });
+                self.compile_decision(replace_column(&occurrences, column, fields), rows, state)
+            },
+            PatternKind::Variant { enum_name, variant, .. } => {
+                let (key, _) = self.variant_at(pattern.span, enum_name, variant)?
+                    .ok_or_else(|| codegen_error(format!("unknown variant `{}::{}`", enum_name, variant)))?;
+                // Az oszlopban előforduló változatok, az első előfordulásuk sorrendjében
+                let mut cases: Vec<(usize, BasicBlock<'ctx>)> = Vec::new();
+                for row in &rows {
+                    let PatternKind::Variant { variant, .. } = &row.patterns[column].kind else { continue };
+                    let index = self.variant_index(&key, variant)
+                        .ok_or_else(|| codegen_error(format!("unknown variant `{}::{}`", enum_name, variant)))?;
+                    if !cases.iter().any(|(case, _)| *case == index) {
+                        cases.push((index, self.context.append_basic_block(function, "match.variant")));
+                    }
+                }
+                let complete = cases.len() == self.enums[&key].variants.len();
+                let default = self.context.append_basic_block(function, "match.default");
+                let value = occurrences[column];
+                self.build_variant_switch(&key, value, &cases, default);
+
+                for (index, block) in cases {
+                    self.builder.position_at_end(block);
+                    let fields = self.build_variant_fields(&key, value, index);
+                    let rows = specialize_rows(&rows, column, fields.len(), wildcard, |pattern| match &pattern.kind {
+                        PatternKind::Variant { variant, fields, .. } if self.variant_index(&key, variant) == Some(index) => {
+                            Some(fields.iter().collect())
+                        },
+                        _ => None
+                    });
+                    self.compile_decision(replace_column(&occurrences, column, fields), rows, state)?;
+                }
+                // Az oszlopban nem szereplő változatokra csak a `_` sorok illeszkednek
+                self.builder.position_at_end(default);
+                if complete {
+                    self.builder.build_unreachable();
+                    return Ok(());
+                }
+                let rows = specialize_rows(&rows, column, 0, wildcard, |_| None);
+                self.compile_decision(replace_column(&occurrences, column, Vec::new()), rows, state)
+            },
+            // A tartomány minta nem esetérték: megmarad minden ágban, és külön vizsgáljuk
+            PatternKind::Literal(_) if occurrences[column].is_int_value() => {
+                let mut cases: Vec<IntValue<'ctx>> = Vec::new();
+                let mut row_cases: Vec<Option<usize>> = Vec::new();
+                for row in &rows {
+                    let PatternKind::Literal(expr) = &row.patterns[column].kind else {
+                        row_cases.push(None);
+                        continue;
+                    };
+                    // Az azonos értékű konstansok az LLVM-ben egyetlen értékként léteznek
+                    let case = self.compile_expr(expr)?.into_int_value();
+                    let index = cases.iter().position(|other| *other == case).unwrap_or(cases.len());
+                    if index == cases.len() {
+                        cases.push(case);
+                    }
+                    row_cases.push(Some(index));
+                }
+                let blocks: Vec<BasicBlock<'ctx>> = cases.iter()
+                    .map(|_| self.context.append_basic_block(function, "match.case"))
+                    .collect();
+                let default = self.context.append_basic_block(function, "match.default");
+                let switch_cases: Vec<(IntValue<'ctx>, BasicBlock<'ctx>)> = cases.into_iter().zip(blocks.iter().copied()).collect();
+                self.builder.build_switch(occurrences[column].into_int_value(), default, &switch_cases);
+
+                for (case, block) in blocks.into_iter().enumerate() {
+                    self.builder.position_at_end(block);
+                    let rows = rows.iter()
+                        .zip(&row_cases)
+                        .filter(|(_, row_case)| row_case.is_none_or(|row_case| row_case == case))
+                        .map(|(row, row_case)| {
+                            let mut row = row.clone();
+                            if row_case.is_some() {
+                                row.patterns[column] = wildcard;
+                            }
+                            row
+                        })
+                        .collect();
+                    self.compile_decision(occurrences.clone(), rows, state)?;
+                }
+                self.builder.position_at_end(default);
+                let rows = rows.into_iter()
+                    .zip(row_cases)
+                    .filter_map(|(row, row_case)| row_case.is_none().then_some(row))
+                    .collect();
+                self.compile_decision(occurrences, rows, state)
+            },
+            _ => {
+                let matched = self.compile_literal_test(pattern, occurrences[column])?;
+                let then_block = self.context.append_basic_block(function, "match.test");
+                let else_block = self.context.append_basic_block(function, "match.test");
+                self.builder.build_conditional_branch(matched, then_block, else_block);
+
+                self.builder.position_at_end(then_block);
+                let mut matching = rows.clone();
+                matching[0].patterns[column] = wildcard;
+                self.compile_decision(occurrences.clone(), matching, state)?;
+                self.builder.position_at_end(else_block);
+                rows.remove(0);
+                self.compile_decision(occurrences, rows, state)
+            }
+        }
+    }
+
+    // Levél: az első sor illeszkedik. A kötései az ág helyeire kerülnek, majd az őr (ha van)
+    // a törzsbe, vagy a maradék sorok döntési fájába ugrik
+    fn compile_match_leaf<'p>(
+        &mut self,
+        occurrences: Vec<BasicValueEnum<'ctx>>,
+        mut rows: Vec<MatchRow<'p, 'ctx>>,
+        state: &mut MatchArms<'p, 'ctx>
+    ) -> Result<(), Diagnostic> {
+        let row = rows.remove(0);
+        for (name, value) in &row.bindings {
+            let slot = match state.slots[row.arm].iter().find(|(bound, _)| bound == name) {
+                Some(&(_, slot)) => slot,
+                None => {
+                    let slot = self.build_local(value.get_type(), name);
+                    state.slots[row.arm].push((name.clone(), slot));
+                    slot
+                }
+            };
+            self.builder.build_store(slot, *value);
+        }
+        state.reached[row.arm] = true;
+        let body = state.blocks[row.arm];
+        let arms = state.arms;
+        let Some(guard) = &arms[row.arm].guard else {
+            self.builder.build_unconditional_branch(body);
+            return Ok(());
+        };
+
+        // Az őrben álló `akkor` után a maradék sorok értékei a keretből töltődnek vissza
+        let mut live: Vec<BasicValueEnum<'ctx>> = occurrences.iter()
+            .copied()
+            .chain(rows.iter().flat_map(|row| row.bindings.iter().map(|(_, value)| *value)))
+            .collect();
+        let slots = state.slots[row.arm].clone();
+        let matched = self.across_await(&mut live, guard, |this| this.with_scope(|this| {
+            this.variables.extend(slots);
+            this.compile_expr(guard)
+        }))?.into_int_value();
+        let (occurrences, bound) = live.split_at(occurrences.len());
+        let mut bound = bound.iter();
+        for (_, value) in rows.iter_mut().flat_map(|row| row.bindings.iter_mut()) {
+            *value = *bound.next().unwrap();
+        }
+
+        let else_block = self.context.append_basic_block(self.current_function(), "match.guard");
+        self.builder.build_conditional_branch(matched, body, else_block);
+        self.builder.position_at_end(else_block);
+        self.compile_decision(occurrences.to_vec(), rows, state)
+    }
+
+    // Elágazás az enum-érték változata szerint: taggel `switch`, niche-ként kódolt (vagy egyetlen
+    // változatú) enumnál változatonkénti vizsgálat
+    fn build_variant_switch(&mut self, enum_name: &str, value: BasicValueEnum<'ctx>, cases: &[(usize, BasicBlock<'ctx>)], default: BasicBlock<'ctx>) {
+        match self.enums[enum_name].layout {
+            EnumLayout::Tag(tag) | EnumLayout::Tagged(tag) => {
+                let tag_value = self.builder.build_extract_value(value.into_struct_value(), 0, "tag").unwrap().into_int_value();
+                let cases: Vec<(IntValue<'ctx>, BasicBlock<'ctx>)> = cases.iter()
+                    .map(|&(index, block)| (tag.const_int(index as u64, false), block))
+                    .collect();
+                self.builder.build_switch(tag_value, default, &cases);
+            },
+            EnumLayout::Single | EnumLayout::Niche { .. } => {
+                let function = self.current_function();
+                for (i, &(index, block)) in cases.iter().enumerate() {
+                    let next = if i + 1 == cases.len() { default } else { self.context.append_basic_block(function, "match.variant") };
+                    let is_variant = self.build_variant_test(enum_name, value, index);
+                    self.builder.build_conditional_branch(is_variant, block, next);
+                    self.builder.position_at_end(next);
+                }
+            }
+        }
+    }
+
+    // A literál (nem egész értékű oszlopban) és a tartomány minta illeszkedését jelző i1 érték
+    fn compile_literal_test(&mut self, pattern: &Pattern, value: BasicValueEnum<'ctx>) -> Result<IntValue<'ctx>, Diagnostic> {
+        let ty = self.expr_type(pattern.span).unwrap_or(Type::Error);
+        match &pattern.kind {
+            PatternKind::Literal(expr) => {
+                let literal = self.compile_expr(expr)?;
+                self.compile_equals(value, literal, &ty)
+            },
+            PatternKind::Range { start, end, inclusive } => {
+                let lo = self.compile_expr(start)?.into_int_value();
+                let hi = self.compile_expr(end)?.into_int_value();
+                let signed = matches!(ty, Type::Int(int_ty) if int_ty.is_signed());
+                let (lower, upper) = match (signed, inclusive) {
+                    (true, true) => (IntPredicate::SGE, IntPredicate::SLE),
+                    (true, false) => (IntPredicate::SGE, IntPredicate::SLT),
+                    (false, true) => (IntPredicate::UGE, IntPredicate::ULE),
+                    (false, false) => (IntPredicate::UGE, IntPredicate::ULT)
+                };
+                let value = value.into_int_value();
+                let above = self.builder.build_int_compare(lower, value, lo, "range.lo");
+                let below = self.builder.build_int_compare(upper, value, hi, "range.hi");
+                Ok(self.builder.build_and(above, below, "range"))
+            },
+            _ => Err(codegen_error("only literal and range patterns are tested directly"))
+        }
+    }
+
+    fn compile_equals(&mut self, left: BasicValueEnum<'ctx>, right: BasicValueEnum<'ctx>, ty: &Type) -> Result<IntValue<'ctx>, Diagnostic> {
+        match ty {
+            Type::Int(_) | Type::Bool | Type::Char => {
+                Ok(self.builder.build_int_compare(IntPredicate::EQ, left.into_int_value(), right.into_int_value(), "eq"))
+            },
+            Type::Float(_) => {
+                Ok(self.builder.build_float_compare(FloatPredicate::OEQ, left.into_float_value(), right.into_float_value(), "eq"))
+            },
+            Type::String => {
+                let ptr_type = self.ptr_type();
+                let fn_type = self.context.i32_type().fn_type(&[ptr_type.into(), ptr_type.into()], false);
+                let strcmp = self.runtime_fn("strcmp", fn_type);
+                let order = self.builder.build_call(strcmp, &[left.into(), right.into()], "strcmp")
+                    .try_as_basic_value()
+                    .left()
+                    .unwrap()
+                    .into_int_value();
+                Ok(self.builder.build_int_compare(IntPredicate::EQ, order, self.context.i32_type().const_zero(), "eq"))
+            },
+            other => Err(codegen_error(format!("cannot compare values of type `{}`", other)))
+        }
+    }
+
+    // A mintában kötött nevek stack-re kerülnek, mint a `var` változók
+    fn bind_pattern(&mut self, pattern: &Pattern, value: BasicValueEnum<'ctx>) {
+        match &pattern.kind {
+            PatternKind::Binding(name) => {
+                let alloca = self.build_local(value.get_type(), name);
+                self.builder.build_store(alloca, value);
+                self.variables.insert(name.clone(), alloca);
+            },
+            PatternKind::Tuple(elements) => {
+                for (i, element) in elements.iter().enumerate() {
+                    let field = self.builder.build_extract_value(value.into_struct_value(), i as u32, "field").unwrap();
+                    self.bind_pattern(element, field);
+                }
+            },
+            PatternKind::Struct { name, fields, .. } => {
+                for field in fields {
+                    let Ok(index) = self.field_index(name, &field.name) else { continue };
+                    let field_value = self.builder.build_extract_value(value.into_struct_value(), index, &field.name).unwrap();
+                    self.bind_pattern(&field.pattern, field_value);
+                }
+            },
+            PatternKind::Variant { enum_name, variant, fields } => {
+                let Ok(Some((enum_type, index))) = self.variant_at(pattern.span, enum_name, variant) else { return };
+                for (field, field_value) in fields.iter().zip(self.build_variant_fields(&enum_type, value, index)) {
+                    self.bind_pattern(field, field_value);
+                }
+            },
+            PatternKind::Wildcard | PatternKind::Literal(_) | PatternKind::Range { .. } => {}
+        }
+    }
+
+    // A darabokat runtime DlangString-gé alakítjuk és sorban összefűzzük;
+    // az eredmény C string, mert a `str` így ábrázolódik
+    fn compile_interpolation(&mut self, parts: &[InterpPart]) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let ptr_type = self.ptr_type();
+        let mut result: Option<PointerValue<'ctx>> = None;
+
+        for part in parts {
+            let piece = match part {
+                InterpPart::Text(text) => {
+                    let global = self.builder.build_global_string_ptr(text, "str");
+                    self.call_runtime("dlang_string_new", ptr_type.fn_type(&[ptr_type.into()], false), &[global.as_pointer_value().into()])
+                },
+                InterpPart::Expr(expr) => {
+                    let mut live: Vec<BasicValueEnum<'ctx>> = result.into_iter().map(|prev| prev.into()).collect();
+                    let piece = self.across_await(&mut live, expr, |this| this.compile_to_string(expr))?;
+                    result = live.first().map(|prev| prev.into_pointer_value());
+                    piece
+                }
+            };
+
+            result = Some(match result {
+                None => piece,
+                Some(prev) => {
+                    let joined = self.call_runtime(
+                        "dlang_string_concat",
+                        ptr_type.fn_type(&[ptr_type.into(), ptr_type.into()], false),
+                        &[prev.into(), piece.into()]
+                    );
+                    self.free_string(prev);
+                    self.free_string(piece);
+                    joined
+                }
+            });
+        }
+
+        let result = result.ok_or_else(|| codegen_error("empty interpolated string"))?;
+        Ok(self.call_runtime("dlang_string_into_cstr", ptr_type.fn_type(&[ptr_type.into()], false), &[result.into()]).into())
+    }
+
+    fn compile_to_string(&mut self, expr: &Expr) -> Result<PointerValue<'ctx>, Diagnostic> {
+        let value = self.compile_expr(expr)?;
+        let ty = self.expr_type(expr.span).ok_or_else(|| codegen_error("cannot format an untyped value"))?;
+        self.value_to_string(value, &ty)
+    }
+
+    // Érték DlangString-gé alakítása a típusa szerinti runtime függvénnyel; a többi
+    // típus a `Kiirhato` implementációját hívja
+    fn value_to_string(&mut self, value: BasicValueEnum<'ctx>, ty: &Type) -> Result<PointerValue<'ctx>, Diagnostic> {
+        let ptr_type = self.ptr_type();
+        let i64_type = self.context.i64_type();
+        let f64_type = self.context.f64_type();
+
+        let (name, param_type, arg): (&str, _, BasicMetadataValueEnum) = match ty {
+            // A 128 bites érték két 64 bites félként adódik át
+            Type::Int(int_ty) if int_ty.bits() == 128 => {
+                let value = value.into_int_value();
+                let low = self.builder.build_int_truncate(value, i64_type, "also");
+                let shift = value.get_type().const_int(64, false);
+                let high = self.builder.build_right_shift(value, shift, false, "felso");
+                let high = self.builder.build_int_truncate(high, i64_type, "felso");
+                let name = if int_ty.is_signed() { "dlang_string_from_i128" } else { "dlang_string_from_u128" };
+                let fn_type = ptr_type.fn_type(&[i64_type.into(), i64_type.into()], false);
+                return Ok(self.call_runtime(name, fn_type, &[low.into(), high.into()]));
+            },
+            Type::Int(ty) => {
+                let value = self.builder.build_int_cast_sign_flag(value.into_int_value(), i64_type, ty.is_signed(), "intcast");
+                let name = if ty.is_signed() { "dlang_string_from_i64" } else { "dlang_string_from_u64" };
+                (name, i64_type.into(), value.into())
+            },
+            Type::Float(_) => {
+                let value = self.builder.build_float_cast(value.into_float_value(), f64_type, "floatcast");
+                ("dlang_string_from_f64", f64_type.into(), value.into())
+            },
+            Type::Bool => ("dlang_string_from_bool", self.context.bool_type().into(), value.into()),
+            Type::Char => ("dlang_string_from_char", self.context.i32_type().into(), value.into()),
+            Type::String => ("dlang_string_new", ptr_type.into(), value.into()),
+            Type::Struct(_, args) | Type::Enum(_, args) => {
+                let text = self.call_method(ty, DISPLAY_METHOD, args, &[value])?;
+                ("dlang_string_new", ptr_type.into(), text.into())
+            },
+            Type::Dyn(_) => {
+                let text = self.call_method(ty, DISPLAY_METHOD, &[], &[value])?;
+                ("dlang_string_new", ptr_type.into(), text.into())
+            },
+            other => return Err(codegen_error(format!("cannot format value of type `{}`", other)))
+        };
+
+        Ok(self.call_runtime(name, ptr_type.fn_type(&[param_type], false), &[arg]))
+    }
+
+    fn free_string(&self, string: PointerValue<'ctx>) {
+        let fn_type = self.context.void_type().fn_type(&[self.ptr_type().into()], false);
+        let function = self.runtime_fn("dlang_string_free", fn_type);
+        self.builder.build_call(function, &[string.into()], "");
+    }
+
+    // Mutatót visszaadó runtime hívás
+    fn call_runtime(&self, name: &str, fn_type: FunctionType<'ctx>, args: &[BasicMetadataValueEnum<'ctx>]) -> PointerValue<'ctx> {
+        let function = self.runtime_fn(name, fn_type);
+        self.builder.build_call(function, args, "calltmp")
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value()
+    }
+
+    // Sikernél `Eredmeny::Rendben(value)`, különben `Eredmeny::Hiba` a runtime utolsó hibaüzenetével (`dlang_last_error`)
+    fn build_ffi_result(&mut self, result_type: &Type, ok: IntValue<'ctx>, value: BasicValueEnum<'ctx>) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        self.llvm_type(result_type)?;
+        let key = result_type.to_string();
+        let function = self.current_function();
+        let ok_block = self.context.append_basic_block(function, "ffi.ok");
+        let err_block = self.context.append_basic_block(function, "ffi.hiba");
+        let end_block = self.context.append_basic_block(function, "ffi.vege");
+        self.builder.build_conditional_branch(ok, ok_block, err_block);
+
+        self.builder.position_at_end(ok_block);
+        let success = self.build_variant(&key, self.variant_index(&key, RESULT_OK).unwrap(), &[value]);
+        let ok_block = self.builder.get_insert_block().unwrap();
+        self.builder.build_unconditional_branch(end_block);
+
+        self.builder.position_at_end(err_block);
+        let message = self.call_runtime("dlang_last_error", self.ptr_type().fn_type(&[], false), &[]);
+        let failure = self.build_variant(&key, self.variant_index(&key, RESULT_ERR).unwrap(), &[message.into()]);
+        let err_block = self.builder.get_insert_block().unwrap();
+        self.builder.build_unconditional_branch(end_block);
+
+        self.builder.position_at_end(end_block);
+        let phi = self.builder.build_phi(success.get_type(), "ffi.eredmeny");
+        phi.add_incoming(&[(&success, ok_block), (&failure, err_block)]);
+        Ok(phi.as_basic_value())
+    }
+
+    // `x?`: a `Van`/`Rendben` adata, különben a `Nincs`/`Hiba` a függvény visszatérési típusában
+    // (a hibaérték változatlanul) azonnal visszatér
+    fn compile_try(&mut self, operand: &Expr) -> Result<BasicValueEnum<'ctx>, Diagnostic> {
+        let ty = self.expr_type(operand.span).ok_or_else(|| codegen_error("`?` on an untyped value"))?;
+        let (success, failure) = match &ty {
+            Type::Enum(name, _) if name == OPTION_TYPE => (OPTION_SOME, OPTION_NONE),
+            Type::Enum(..) => (RESULT_OK, RESULT_ERR),
+            _ => return Err(codegen_error("`?` on a value that is neither `Opcio` nor `Eredmeny`"))
+        };
+        let return_type = self.return_type.clone().ok_or_else(|| codegen_error("`?` outside of a function"))?;
+        let value = self.compile_expr(operand)?;
+        self.llvm_type(&ty)?;
+        self.llvm_type(&return_type)?;
+        let (key, return_key) = (ty.to_string(), return_type.to_string());
+        let success_index = self.variant_index(&key, success).unwrap();
+        let failure_index = self.variant_index(&key, failure).unwrap();
+
+        let function = self.current_function();
+        let ok_block = self.context.append_basic_block(function, "kerdojel.ok");
+        let fail_block = self.context.append_basic_block(function, "kerdojel.hiba");
+        let is_success = self.build_variant_test(&key, value, success_index);
+        self.builder.build_conditional_branch(is_success, ok_block, fail_block);
+
+        self.builder.position_at_end(fail_block);
+        let fields = self.build_variant_fields(&key, value, failure_index);
+        let residual = self.build_variant(&return_key, self.variant_index(&return_key, failure).unwrap(), &fields);
+        if let Some(result) = self.async_frame.as_ref().map(|async_frame| async_frame.result) {
+            self.builder.build_store(result, residual);
+            self.finish_async();
+        } else {
+            self.builder.build_return(Some(&residual));
+        }
+
+        self.builder.position_at_end(ok_block);
+        Ok(self.build_variant_fields(&key, value, success_index)[0])
+    }
+
+    // Soha vissza nem térő hívás után: a folytatás elérhetetlen, ezért egy új (halott) blokkba kerül
+    fn build_diverge(&mut self) -> BasicValueEnum<'ctx> {
+        self.builder.build_unreachable();
+        let dead = self.context.append_basic_block(self.current_function(), "halott");
+        self.builder.position_at_end(dead);
+        self.unit_value()
+    }
+
+    // A runtime függvényeket az első használatkor deklaráljuk
+    fn runtime_fn(&self, name: &str, fn_type: FunctionType<'ctx>) -> FunctionValue<'ctx> {
+        self.module.get_function(name)
+            .unwrap_or_else(|| self.module.add_function(name, fn_type, None))
+    }
+
+    fn ptr_type(&self) -> PointerType<'ctx> {
+        self.context.i8_type().ptr_type(AddressSpace::Generic)
+    }
+    
+    fn int_type(&self, ty: IntTy) -> IntType<'ctx> {
+        self.context.custom_width_int_type(ty.bits())
+    }
+
+    fn float_type(&self, ty: FloatTy) -> FloatType<'ctx> {
+        match ty {
+            FloatTy::F32 => self.context.f32_type(),
+            FloatTy::F64 => self.context.f64_type()
+        }
+    }
+
+    fn llvm_type(&mut self, ty: &Type) -> Result<BasicTypeEnum<'ctx>, Diagnostic> {
+        Ok(match ty {
+            Type::Int(int_ty) => self.int_type(*int_ty).into(),
+            Type::Float(float_ty) => self.float_type(*float_ty).into(),
+            Type::Bool => self.context.bool_type().into(),
+            Type::Char => self.context.i32_type().into(),
+            Type::String | Type::ByteString => self.ptr_type().into(),
+            Type::Void | Type::Never => self.context.struct_type(&[], false).into(),
+            Type::Tuple(elements) => {
+                let types = elements.iter().map(|element| self.llvm_type(element)).collect::<Result<Vec<_>, _>>()?;
+                self.context.struct_type(&types, false).into()
+            },
+            Type::Range(element) => {
+                let element = self.llvm_type(element)?;
+                self.context.struct_type(&[element, element, element, self.context.bool_type().into()], false).into()
+            },
+            Type::Array(element, len) => self.llvm_type(element)?.array_type(*len as u32).into(),
+            // Szelet: `{ első elem címe, hossz }`
+            Type::Slice(element) => {
+                let element = self.llvm_type(element)?.ptr_type(AddressSpace::Generic);
+                self.context.struct_type(&[element.into(), self.int_type(IntTy::Usize).into()], false).into()
+            },
+            Type::Struct(..) | Type::Enum(..) => self.named_type(ty)?.into(),
+            // Trait objektum: `{ adat, vtable }`
+            Type::Dyn(_) => self.context.struct_type(&[self.ptr_type().into(), self.ptr_type().into()], false).into(),
+            // Lezárás: `{ függvény, környezet }`
+            Type::Function(..) => self.context.struct_type(&[self.ptr_type().into(), self.ptr_type().into()], false).into(),
+            other => return Err(codegen_error(format!("unknown type: `{}`", other)))
+        })
+    }
+
+    // Típusjelölés feloldása; a generikus paraméterek helyére a `subst` szerinti típus kerül
+    fn annot_type(&self, annot: &TypeAnnot, subst: &HashMap<String, Type>) -> Result<Type, Diagnostic> {
+        if annot.is_dyn {
+            return Ok(Type::Dyn(annot.name.clone()));
+        }
+        if annot.is_fn() {
+            let params = annot.args.iter().map(|param| self.annot_type(param, subst)).collect::<Result<Vec<_>, _>>()?;
+            let return_type = match &annot.return_type {
+                Some(ret) => self.annot_type(ret, subst)?,
+                None => Type::Void
+            };
+            return Ok(Type::Function(params, Box::new(return_type)));
+        }
+        if annot.is_array() {
+            let element = Box::new(self.annot_type(&annot.args[0], subst)?);
+            return Ok(match annot.array_len {
+                Some(len) => Type::Array(element, len),
+                None => Type::Slice(element)
+            });
+        }
+        if let Some(ty) = primitive_type(&annot.name).or_else(|| subst.get(&annot.name).cloned()) {
+            return Ok(ty);
+        }
+        let args = annot.args.iter().map(|arg| self.annot_type(arg, subst)).collect::<Result<Vec<_>, _>>()?;
+        if self.struct_defs.contains_key(&annot.name) {
+            Ok(Type::Struct(annot.name.clone(), args))
+        } else if self.enum_defs.contains_key(&annot.name) {
+            Ok(Type::Enum(annot.name.clone(), args))
+        } else {
+            Err(codegen_error(format!("unknown type: `{}`", annot)))
+        }
+    }
+
+    // A jelölés nélküli paraméter típusát a típusellenőrző következtette ki
+    fn param_type(&mut self, param: &Param) -> Result<BasicTypeEnum<'ctx>, Diagnostic> {
+        if param.type_annot.is_some() {
+            return self.parse_type(&param.type_annot);
+        }
+        let ty = self.expr_type(param.span).ok_or_else(|| codegen_error("missing type annotation"))?;
+        self.llvm_type(&ty)
+    }
+
+    fn parse_type(&mut self, annot: &Option<TypeAnnot>) -> Result<BasicTypeEnum<'ctx>, Diagnostic> {
+        let annot = annot.as_ref().ok_or_else(|| codegen_error("missing type annotation"))?;
+        let ty = self.annot_type(annot, &self.type_args)?;
+        self.llvm_type(&ty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::modules::Module;
+    use crate::parser::Parser;
+    use crate::source_map::SourceMap;
+    use crate::typechecker::TypeChecker;
+    use crate::{prelude, resolver};
+
+    // A `main` lépései a kódgenerálásig; az eredmény az ellenőrzött modul IR-je
+    fn generate(src: &str) -> Result<String, Diagnostic> {
+        let mut source_map = SourceMap::new();
+        let file = source_map.add_file("teszt.dlang".to_string(), src.to_string());
+        let (program, diagnostics) = Parser::new(Lexer::new(&source_map.get(file).src, file)).parse_program();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        let (mut program, diagnostics) = resolver::resolve(vec![Module { path: Vec::new(), file, program }]);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+        let prelude_id = source_map.add_file("<prelude>".to_string(), prelude::SOURCE.to_string());
+        let (prelude, _) = Parser::new(Lexer::new(&source_map.get(prelude_id).src, prelude_id)).parse_program();
+        program.traits.splice(0..0, prelude.traits);
+        program.structs.splice(0..0, prelude.structs);
+        program.enums.splice(0..0, prelude.enums);
+
+        let mut typechecker = TypeChecker::new().with_prelude(prelude_id);
+        let diagnostics = typechecker.check_program(&program);
+        assert!(diagnostics.iter().all(|diag| !diag.is_error()), "{:?}", diagnostics);
+
+        let context = Context::create();
+        let mut codegen = CodeGen::new(&context, typechecker.take_expr_types(), typechecker.take_instantiations(), typechecker.take_coercions(), typechecker.take_captures(), typechecker.take_const_values());
+        codegen.generate(&program)?;
+        Ok(codegen.module.print_to_string().to_string())
+    }
+
+    // Egy függvény IR-je a modulból
+    fn function_ir<'a>(ir: &'a str, name: &str) -> &'a str {
+        let header = format!("@{}(", name);
+        ir.split("\ndefine ").find(|function| function.lines().next().is_some_and(|line| line.contains(&header))).unwrap()
+    }
+
+    #[test]
+    fn struct_lowers_to_named_llvm_struct() {
+        let src = "
+strukt Pont {
+    x: f64,
+    y: f64,
+}
+
+fv osszeg(p: Pont) -> f64 {
+    vissza p.x + p.y;
+}
+
+fv main() {
+    legyen p = Pont { y: 2.0, x: 1.0 };
+    legyen s = osszeg(p);
+    print(\"{s}\");
+}
+";
+        let ir = generate(src).unwrap();
+        assert!(ir.contains("%Pont = type { double, double }"), "{}", ir);
+        let osszeg = function_ir(&ir, "osszeg");
+        assert_eq!(osszeg.matches("extractvalue %Pont").count(), 2, "{}", osszeg);
+    }
+
+    #[test]
+    fn methods_lower_to_mangled_functions() {
+        let src = "
+strukt Pont {
+    x: f64,
+    y: f64,
+}
+
+impl Pont {
+    fv uj(x: f64, y: f64) -> Pont {
+        vissza Pont { x: x, y: y };
+    }
+
+    fv hossz(self) -> f64 {
+        vissza self.x * self.x + self.y * self.y;
+    }
+}
+
+fv main() {
+    legyen p = Pont::uj(3.0, 4.0);
+    legyen h = p.hossz();
+    legyen q = Pont::hossz(p);
+    print(\"{h} {q}\");
+}
+";
+        let ir = generate(src).unwrap();
+        assert!(ir.contains("define double @_ZN4Pont5hosszE(%Pont") && ir.contains("@_ZN4Pont2ujE("), "{}", ir);
+        // A metódushívás és a `Pont::hossz(p)` alak ugyanazt a függvényt hívja
+        let main = function_ir(&ir, "main");
+        assert_eq!(main.matches("call double @_ZN4Pont5hosszE(").count(), 2, "{}", main);
+    }
+
+    #[test]
+    fn enum_layouts_use_tags_and_niches() {
+        let src = "
+enum Alak {
+    Kor(f64),
+    Teglalap(f64, f64),
+}
+
+fv main() {
+    legyen a = Alak::Teglalap(1.0, 2.0);
+    legyen s: Opcio<str> = Opcio::Van(\"alma\");
+    legyen c: Opcio<char> = Opcio::Nincs;
+    legyen n: Opcio<i32> = Opcio::Van(1);
+}
+";
+        let ir = generate(src).unwrap();
+        // Általános eset: tag és a legnagyobb változat méretű adatterület
+        assert!(ir.contains("%Alak = type { i8, [2 x i64] }"), "{}", ir);
+        assert!(ir.contains("%\"Opcio<i32>\" = type { i8, [1 x i32] }"), "{}", ir);
+        // Niche: a `str` null mutatója, a `char` 0x10FFFF fölötti értéke jelenti a `Nincs`-et
+        assert!(ir.contains("%\"Opcio<str>\" = type { i8* }"), "{}", ir);
+        assert!(ir.contains("%\"Opcio<char>\" = type { i32 }"), "{}", ir);
+        assert!(ir.contains("i32 1114112"), "{}", ir);
+    }
+
+    #[test]
+    fn dyn_calls_go_through_vtable_shims() {
+        let src = "
+vonas Alakzat {
+    fv terulet(self) -> f64;
+    fv leiras(self) -> str {
+        vissza \"alakzat\";
+    }
+}
+
+strukt Kor {
+    r: f64,
+}
+
+strukt Negyzet {
+    a: f64,
+}
+
+impl Alakzat for Kor {
+    fv terulet(self) -> f64 {
+        vissza self.r * self.r * 3.14;
+    }
+}
+
+impl Alakzat for Negyzet {
+    fv terulet(self) -> f64 {
+        vissza self.a * self.a;
+    }
+}
+
+fv terulet(a: dyn Alakzat) -> f64 {
+    vissza a.terulet();
+}
+
+fv main() {
+    legyen k = terulet(Kor { r: 1.0 });
+    legyen n = terulet(Negyzet { a: 2.0 });
+    print(\"{k} {n}\");
+}
+";
+        let ir = generate(src).unwrap();
+        // Típusonként egy vtable, a trait minden metódusára (az alapértelmezettre is) egy shimmel
+        for ty in ["Kor", "Negyzet"] {
+            assert!(ir.contains(&format!("@vtable.{}.Alakzat = ", ty)), "{}", ir);
+            let shim = function_ir(&ir, &format!("vtable.{}.Alakzat.terulet", ty));
+            assert!(shim.contains(&format!("@{}(", mangle_method(ty, "terulet"))), "{}", shim);
+            function_ir(&ir, &format!("vtable.{}.Alakzat.leiras", ty));
+        }
+        let terulet = function_ir(&ir, "terulet");
+        assert!(terulet.contains("dyn.entry") && terulet.contains("%dyncall = call double %dyn.fn("), "{}", terulet);
+    }
+
+    #[test]
+    fn constants_fold_or_become_globals() {
+        let src = "
+konst K: i32 = 6 * 7;
+konst T: [i32; 3] = [1, 2, K];
+
+fv main() {
+    legyen x = K + 1;
+    legyen y = T[2];
+    print(\"{x} {y}\");
+}
+";
+        let ir = generate(src).unwrap();
+        // A tömb konstans globálisba kerül a kiértékelt elemekkel, a skalár csak azonnali érték
+        assert!(ir.contains("@konst.T = constant [3 x i32] [i32 1, i32 2, i32 42]"), "{}", ir);
+        assert!(!ir.contains("@konst.K"), "{}", ir);
+    }
+
+    #[test]
+    fn compound_assignment_updates_the_field_in_place() {
+        let src = "
+strukt Pont {
+    x: i32,
+    y: i32,
+}
+
+fv main() {
+    var p = Pont { x: 1, y: 2 };
+    p.y -= 1;
+    legyen k = p.y;
+    print(\"{k}\");
+}
+";
+        let ir = generate(src).unwrap();
+        let main = function_ir(&ir, "main");
+        assert!(main.contains("%y = getelementptr inbounds %Pont, %Pont* %p, i32 0, i32 1"), "{}", main);
+        assert!(main.contains("load i32, i32* %y"), "{}", main);
+        assert!(main.contains("store i32 %") && main.matches("i32* %y").count() >= 2, "{}", main);
+    }
+
+    #[test]
+    fn casts_and_division_are_checked_at_runtime() {
+        let src = "
+fv szukit(a: i64) -> u8 {
+    vissza a as u8;
+}
+
+fv tagit(a: i32) -> i64 {
+    vissza a as i64;
+}
+
+fv elojeles(a: i32, b: i32) -> i32 {
+    vissza a / b;
+}
+
+fv elojel_nelkuli(a: u32, b: u32) -> u32 {
+    vissza a / b;
+}
+
+fv main() {
+    legyen x = szukit(300);
+    legyen y = tagit(-1);
+    legyen z = elojeles(-7, 2) + elojel_nelkuli(7, 2) as i32;
+    print(\"{x} {y} {z}\");
+}
+";
+        let ir = generate(src).unwrap();
+        // Szűkítés után ellenőrzés és `dlang_panic`, bővítésnél nincs mit ellenőrizni
+        let narrowing = function_ir(&ir, "szukit");
+        assert!(narrowing.contains("trunc i64") && narrowing.contains("call void @dlang_panic"), "{}", narrowing);
+        assert!(ir.contains("cast overflow: the value does not fit in `u8`"), "{}", ir);
+        let widening = function_ir(&ir, "tagit");
+        assert!(widening.contains("sext i32") && !widening.contains("dlang_panic"), "{}", widening);
+
+        let signed = function_ir(&ir, "elojeles");
+        assert!(signed.contains("sdiv i32") && !signed.contains("udiv"), "{}", signed);
+        let unsigned = function_ir(&ir, "elojel_nelkuli");
+        assert!(unsigned.contains("udiv i32") && !unsigned.contains("sdiv"), "{}", unsigned);
+        assert!(ir.contains("attempt to divide by zero") && ir.contains("attempt to divide with overflow"), "{}", ir);
+    }
+
+    #[test]
+    fn try_returns_the_error_early() {
+        let src = "
+fv oszt(a: i32, b: i32) -> Eredmeny<i32, str> {
+    ha b == 0 {
+        vissza Eredmeny::Hiba(\"division by zero\");
+    }
+    vissza Eredmeny::Rendben(a / b);
+}
+
+fv ketszer(a: i32, b: i32) -> Eredmeny<i32, str> {
+    legyen q = oszt(a, b)?;
+    vissza Eredmeny::Rendben(q * 2);
+}
+
+fv main() {
+    eset ketszer(4, 0) {
+        Eredmeny::Rendben(q) => print(\"{q}\"),
+        Eredmeny::Hiba(uzenet) => print(uzenet),
+    }
+}
+";
+        let ir = generate(src).unwrap();
+        let function = function_ir(&ir, "ketszer");
+        assert!(function.contains("label %kerdojel.ok, label %kerdojel.hiba"), "{}", function);
+        // A hibaágon a `Hiba` változatot építjük újra a függvény visszatérési típusában, és azonnal visszatérünk
+        let failure = function.split("kerdojel.hiba:").nth(1).unwrap().split("\n\n").next().unwrap();
+        assert!(failure.contains("store i8 1"), "{}", failure);
+        assert!(failure.contains("ret %\"Eredmeny<i32, str>\""), "{}", failure);
+    }
+
+    #[test]
+    fn await_in_assignment_resumes_with_valid_place() {
+        let src = "
+aszink fv ertek() -> i32 {
+    vissza 7;
+}
+
+aszink fv tolt(i: usize) -> i32 {
+    var t = [1, 2, 3];
+    t[i] = akkor ertek();
+    t[i] += akkor ertek();
+    legyen x = akkor ertek();
+    vissza t[i] + x;
+}
+
+fv main() {
+    legyen x = futtat(tolt(1));
+    print(\"{x}\");
+}
+";
+        generate(src).unwrap();
+    }
+
+    #[test]
+    fn nested_await_keeps_earlier_operands_in_the_frame() {
+        let src = "
+aszink fv ertek() -> i32 {
+    vissza 7;
+}
+
+fv osszeg(a: i32, b: i32) -> i32 {
+    vissza a + b;
+}
+
+fv harom() -> i32 {
+    vissza 3;
+}
+
+aszink fv kevert(n: i32) -> i32 {
+    legyen x = n * 2 + akkor ertek();
+    legyen y = osszeg(harom(), akkor ertek());
+    vissza x + y;
+}
+
+fv main() {
+    legyen r = futtat(kevert(4));
+    print(\"{r}\");
+}
+";
+        // A felfüggesztés előtt kiszámolt `n * 2` és `harom()` a keretből töltődik vissza
+        let ir = generate(src).unwrap();
+        assert!(ir.contains("akkor.ertek"), "{}", ir);
+    }
+
+    #[test]
+    fn async_function_awaits_a_timer() {
+        let src = "
+aszink fv kesleltetett(ms: i64) -> i32 {
+    akkor alszik(ms);
+    vissza 7;
+}
+
+fv main() {
+    legyen x = futtat(kesleltetett(20));
+    print(\"{x}\");
+}
+";
+        // Az `alszik` jövője az executor időzítőjén készül el; a `futtat` addig nem pollol újra
+        let ir = generate(src).unwrap();
+        assert!(ir.contains("@dlang_future_sleep(") && ir.contains("@dlang_executor_block_on("), "{}", ir);
+    }
+
+    #[test]
+    fn polymorphic_recursion_hits_instance_limit() {
+        let src = "
+fv f<T>(x: T) {
+    f([x]);
+}
+
+fv main() {
+    f(1);
+}
+";
+        let diag = generate(src).unwrap_err();
+        assert!(diag.message.contains("recursion limit"), "{}", diag.message);
+    }
+
+    #[test]
+    fn logical_operators_short_circuit() {
+        let src = "
+fv nulla_e(t: [i32], i: usize) -> bool {
+    vissza i < t.len() && t[i] == 0;
+}
+
+fv nagy_hanyados(a: i32, b: i32) -> bool {
+    vissza b != 0 && a / b > 1;
+}
+
+fv ures_vagy_elso(t: [i32]) -> bool {
+    vissza t.len() == 0 || t[0] == 0;
+}
+
+fv main() {
+    legyen t = [1, 0, 3];
+    legyen a = nulla_e(t[0..3], 5);
+    legyen b = nagy_hanyados(4, 0);
+    legyen c = ures_vagy_elso(t[0..3]);
+}
+";
+        let ir = generate(src).unwrap();
+        // Az osztás és az indexelés a jobb oldal blokkjában, a feltételes ugrás után fut
+        for (name, guarded) in [("nagy_hanyados", "sdiv"), ("nulla_e", "dlang_panic_bounds")] {
+            let function = function_ir(&ir, name);
+            let rhs = function.find("logikai.jobb:").unwrap();
+            assert!(function.find(guarded).unwrap() > rhs, "{}", function);
+        }
+    }
+
+    #[test]
+    fn match_switches_on_tags_and_literals() {
+        let src = "
+enum Alak {
+    Kor(f64),
+    Teglalap(i32, i32),
+    Pont,
+}
+
+fv osztaly(a: Alak, n: i32) -> i32 {
+    vissza eset (a, n) {
+        (Alak::Kor(_), 0) => 1,
+        (Alak::Teglalap(w, h), m) ha w > h => m,
+        (Alak::Teglalap(0, _), _) => 2,
+        (Alak::Pont, 1) => 3,
+        (_, 2) => 4,
+        _ => 5,
+    };
+}
+
+fv melyseg(x: Opcio<Opcio<i32>>) -> i32 {
+    vissza eset x {
+        Opcio::Van(Opcio::Van(7)) => 7,
+        Opcio::Van(Opcio::Van(n)) => n,
+        Opcio::Van(Opcio::Nincs) => 1,
+        Opcio::Nincs => 0,
+    };
+}
+
+fv main() {
+    legyen a = osztaly(Alak::Teglalap(3, 2), 9);
+    legyen b = melyseg(Opcio::Van(Opcio::Van(5)));
+    print(\"{a} {b}\");
+}
+";
+        let ir = generate(src).unwrap();
+        // A tag egyetlen `switch`-ben dől el (nem áganként újra vizsgálva), az egész oszlop is `switch`
+        let osztaly = function_ir(&ir, "osztaly");
+        assert_eq!(osztaly.matches("switch i8").count(), 1, "{}", osztaly);
+        assert!(osztaly.contains("switch i32") && !osztaly.contains("is_variant"), "{}", osztaly);
+        let melyseg = function_ir(&ir, "melyseg");
+        assert_eq!(melyseg.matches("switch i8").count(), 2, "{}", melyseg);
+        assert!(melyseg.contains("switch i32"), "{}", melyseg);
+    }
+
+    #[test]
+    fn diverging_match_arm_is_left_out_of_the_phi() {
+        let src = "
+fv valaszt(y: i32) -> i32 {
+    legyen x = eset y {
+        1 => 5,
+        2 => kilep(2),
+        _ => panik(\"ismeretlen\"),
+    };
+    vissza x;
+}
+
+fv main() {
+    legyen x = valaszt(1);
+}
+";
+        generate(src).unwrap();
+    }
+}
@@ -0,0 +1,309 @@
+use crate::ast::{ BinaryOp, Expr, ExprKind, FloatTy, IntTy, InterpPart, Literal, UnaryOp };
+use crate::diagnostic::{ codes, Diagnostic };
+use crate::source_map::Span;
+use crate::typechecker::{ Type, TypeTable };
+use std::cmp::Ordering;
+use std::fmt;
+
+// Fordítási idejű érték. Az egészeket a típusuk szerint előjelesen vagy előjel nélkül
+// tároljuk, így a teljes `i128` és `u128` tartomány is ábrázolható.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    Int(i128),
+    UInt(u128),
+    Float(f64),
+    Bool(bool),
+    Char(char),
+    String(String),
+    Tuple(Vec<ConstValue>),
+    Array(Vec<ConstValue>),
+}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConstValue::Int(value) => write!(f, "{}", value),
+            ConstValue::UInt(value) => write!(f, "{}", value),
+            ConstValue::Float(value) => write!(f, "{}", value),
+            ConstValue::Bool(true) => write!(f, "igaz"),
+            ConstValue::Bool(false) => write!(f, "hamis"),
+            ConstValue::Char(c) => write!(f, "{}", c),
+            ConstValue::String(s) => write!(f, "{}", s),
+            ConstValue::Tuple(elements) => {
+                let elements: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                write!(f, "({})", elements.join(", "))
+            },
+            ConstValue::Array(elements) => {
+                let elements: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                write!(f, "[{}]", elements.join(", "))
+            }
+        }
+    }
+}
+
+impl ConstValue {
+    // Az egész érték bitmintája (a kódgenerálás két 64 bites szóként adja át)
+    pub fn bits(&self) -> Option<u128> {
+        match self {
+            ConstValue::Int(value) => Some(*value as u128),
+            ConstValue::UInt(value) => Some(*value),
+            _ => None
+        }
+    }
+}
+
+// Egy (már típusellenőrzött) konstans kifejezés kiértékelése. A részkifejezések típusa a
+// `types` táblából jön; a nevek értékét a `lookup` adja, vagy hibát, ha a név nem konstans.
+pub fn evaluate(
+    expr: &Expr,
+    types: &TypeTable,
+    lookup: &dyn Fn(&str, Span) -> Result<ConstValue, Diagnostic>
+) -> Result<ConstValue, Diagnostic> {
+    Evaluator { types, lookup }.expr(expr)
+}
+
+struct Evaluator<'a> {
+    types: &'a TypeTable,
+    lookup: &'a dyn Fn(&str, Span) -> Result<ConstValue, Diagnostic>,
+}
+
+impl Evaluator<'_> {
+    fn expr(&self, expr: &Expr) -> Result<ConstValue, Diagnostic> {
+        match &expr.kind {
+            ExprKind::Literal(lit) => self.literal(lit, false, expr),
+            ExprKind::Ident(name) => (self.lookup)(name, expr.span),
+            ExprKind::Unary { op: UnaryOp::Neg, operand } => {
+                // `-128i8`: a literál pozitív alakja nem férne el a típusban
+                if let ExprKind::Literal(lit) = &operand.kind {
+                    return self.literal(lit, true, expr);
+                }
+                match self.expr(operand)? {
+                    ConstValue::Int(value) => {
+                        let negated = value.checked_neg().ok_or_else(|| self.overflow(expr, "negation"))?;
+                        self.fit(ConstValue::Int(negated), expr, "negation")
+                    },
+                    ConstValue::Float(value) => Ok(ConstValue::Float(-value)),
+                    _ => Err(not_const(expr, "this operand"))
+                }
+            },
+            ExprKind::Unary { op: UnaryOp::Not, operand } => match self.expr(operand)? {
+                ConstValue::Bool(value) => Ok(ConstValue::Bool(!value)),
+                _ => Err(not_const(expr, "this operand"))
+            },
+            ExprKind::BinaryOp { left, op, right } => {
+                // A logikai operátorok rövidzárasak, a jobb oldal hibája is csak kiértékeléskor számít
+                let lhs = self.expr(left)?;
+                match (op, &lhs) {
+                    (BinaryOp::And, ConstValue::Bool(false)) | (BinaryOp::Or, ConstValue::Bool(true)) => return Ok(lhs),
+                    _ => {}
+                }
+                let rhs = self.expr(right)?;
+                self.binary(expr, *op, lhs, rhs, right.span)
+            },
+            ExprKind::Interpolated(parts) => {
+                let mut result = String::new();
+                for part in parts {
+                    match part {
+                        InterpPart::Text(text) => result.push_str(text),
+                        InterpPart::Expr(part) => match self.expr(part)? {
+                            value @ (ConstValue::Tuple(_) | ConstValue::Array(_)) => return Err(not_const(part, &format!("`{}`", value))),
+                            value => result.push_str(&value.to_string())
+                        }
+                    }
+                }
+                Ok(ConstValue::String(result))
+            },
+            ExprKind::Tuple(elements) => {
+                let elements = elements.iter().map(|element| self.expr(element)).collect::<Result<Vec<_>, _>>()?;
+                Ok(ConstValue::Tuple(elements))
+            },
+            ExprKind::Array(elements) => {
+                let elements = elements.iter().map(|element| self.expr(element)).collect::<Result<Vec<_>, _>>()?;
+                Ok(ConstValue::Array(elements))
+            },
+            ExprKind::Index { object, index } => {
+                let ConstValue::Array(elements) = self.expr(object)? else {
+                    return Err(not_const(expr, "this indexing"));
+                };
+                let position = match self.expr(index)? {
+                    ConstValue::Int(value) => usize::try_from(value).ok(),
+                    ConstValue::UInt(value) => usize::try_from(value).ok(),
+                    _ => return Err(not_const(index, "this index"))
+                };
+                match position.and_then(|position| elements.get(position)) {
+                    Some(element) => Ok(element.clone()),
+                    None => Err(Diagnostic::error("evaluation of constant value failed")
+                        .with_code(codes::INDEX_OUT_OF_BOUNDS)
+                        .with_primary(expr.span, format!("index out of bounds: the length is {}", elements.len()))
+                        .with_secondary(index.span, format!("the index evaluates to `{}`", self.expr(index)?)))
+                }
+            },
+            ExprKind::Cast { expr: operand, .. } => {
+                let value = self.expr(operand)?;
+                self.cast(expr, value)
+            },
+            ExprKind::Call { callee, .. } => Err(not_const(expr, &format!("the call to `{}`", callee))
+                .with_note("function calls are evaluated at run time")),
+            _ => Err(not_const(expr, "this expression"))
+        }
+    }
+
+    fn literal(&self, lit: &Literal, negative: bool, expr: &Expr) -> Result<ConstValue, Diagnostic> {
+        match (lit, self.type_of(expr)) {
+            (Literal::Int(value, _), Some(Type::Int(ty))) if ty.is_signed() => {
+                let value = if negative { 0i128.checked_sub_unsigned(*value) } else { i128::try_from(*value).ok() };
+                let value = value.ok_or_else(|| self.overflow(expr, "literal"))?;
+                self.fit(ConstValue::Int(value), expr, "literal")
+            },
+            (Literal::Int(value, _), _) if !negative => self.fit(ConstValue::UInt(*value), expr, "literal"),
+            (Literal::Float(value, _), _) => {
+                let value = if negative { -value } else { *value };
+                Ok(self.round(ConstValue::Float(value), expr))
+            },
+            (Literal::Bool(value), _) => Ok(ConstValue::Bool(*value)),
+            (Literal::Char(c), _) => Ok(ConstValue::Char(*c)),
+            (Literal::String(s), _) => Ok(ConstValue::String(s.clone())),
+            _ => Err(not_const(expr, "this literal"))
+        }
+    }
+
+    fn binary(&self, expr: &Expr, op: BinaryOp, lhs: ConstValue, rhs: ConstValue, rhs_span: Span) -> Result<ConstValue, Diagnostic> {
+        let operation = match op {
+            BinaryOp::Add => "addition",
+            BinaryOp::Sub => "subtraction",
+            BinaryOp::Mul => "multiplication",
+            BinaryOp::Div => "division",
+            // Ha a bal oldal nem döntötte el, az eredmény a jobb oldal
+            BinaryOp::And | BinaryOp::Or => return Ok(rhs),
+            _ => return self.compare(expr, op, &lhs, &rhs)
+        };
+        if op == BinaryOp::Div && matches!(rhs, ConstValue::Int(0) | ConstValue::UInt(0)) {
+            return Err(Diagnostic::error("this operation will panic at run time")
+                .with_code(codes::DIVISION_BY_ZERO)
+                .with_primary(expr.span, "attempt to divide by zero")
+                .with_secondary(rhs_span, "the divisor evaluates to `0`"));
+        }
+
+        let result = match (lhs, rhs) {
+            (ConstValue::Int(a), ConstValue::Int(b)) => match op {
+                BinaryOp::Add => a.checked_add(b),
+                BinaryOp::Sub => a.checked_sub(b),
+                BinaryOp::Mul => a.checked_mul(b),
+                _ => a.checked_div(b)
+            }.map(ConstValue::Int),
+            (ConstValue::UInt(a), ConstValue::UInt(b)) => match op {
+                BinaryOp::Add => a.checked_add(b),
+                BinaryOp::Sub => a.checked_sub(b),
+                BinaryOp::Mul => a.checked_mul(b),
+                _ => a.checked_div(b)
+            }.map(ConstValue::UInt),
+            (ConstValue::Float(a), ConstValue::Float(b)) => {
+                let value = match op {
+                    BinaryOp::Add => a + b,
+                    BinaryOp::Sub => a - b,
+                    BinaryOp::Mul => a * b,
+                    _ => a / b
+                };
+                return Ok(self.round(ConstValue::Float(value), expr));
+            },
+            _ => return Err(not_const(expr, "this operation"))
+        };
+        let result = result.ok_or_else(|| self.overflow(expr, operation))?;
+        self.fit(result, expr, operation)
+    }
+
+    // Az `as` itt is ellenőrzött: a cél típusban el nem férő érték fordítási hiba
+    fn cast(&self, expr: &Expr, value: ConstValue) -> Result<ConstValue, Diagnostic> {
+        let value = match value {
+            ConstValue::Bool(value) => ConstValue::UInt(value as u128),
+            ConstValue::Char(c) => ConstValue::UInt(c as u128),
+            value => value
+        };
+        let result = match (value, self.type_of(expr)) {
+            (ConstValue::Int(value), Some(Type::Int(ty))) if !ty.is_signed() => u128::try_from(value).ok().map(ConstValue::UInt),
+            (ConstValue::UInt(value), Some(Type::Int(ty))) if ty.is_signed() => i128::try_from(value).ok().map(ConstValue::Int),
+            // A tört rész elvész; NaN és a tartományon kívüli érték hiba
+            (ConstValue::Float(value), Some(Type::Int(ty))) => {
+                let value = value.trunc();
+                if ty.is_signed() {
+                    (value >= -(2f64.powi(127)) && value < 2f64.powi(127)).then_some(ConstValue::Int(value as i128))
+                } else {
+                    (value >= 0.0 && value < 2f64.powi(128)).then_some(ConstValue::UInt(value as u128))
+                }
+            },
+            (ConstValue::Int(value), Some(Type::Float(_))) => return Ok(self.round(ConstValue::Float(value as f64), expr)),
+            (ConstValue::UInt(value), Some(Type::Float(_))) => return Ok(self.round(ConstValue::Float(value as f64), expr)),
+            (ConstValue::Float(value), Some(Type::Float(_))) => return Ok(self.round(ConstValue::Float(value), expr)),
+            (ConstValue::UInt(value), Some(Type::Char)) => char::from_u32(value as u32).map(ConstValue::Char),
+            (value, _) => Some(value)
+        };
+        let result = result.ok_or_else(|| self.overflow(expr, "cast"))?;
+        self.fit(result, expr, "cast")
+    }
+
+    fn compare(&self, expr: &Expr, op: BinaryOp, lhs: &ConstValue, rhs: &ConstValue) -> Result<ConstValue, Diagnostic> {
+        let ordering = match (lhs, rhs) {
+            (ConstValue::Int(a), ConstValue::Int(b)) => a.partial_cmp(b),
+            (ConstValue::UInt(a), ConstValue::UInt(b)) => a.partial_cmp(b),
+            (ConstValue::Float(a), ConstValue::Float(b)) => a.partial_cmp(b),
+            (ConstValue::Bool(a), ConstValue::Bool(b)) => a.partial_cmp(b),
+            (ConstValue::Char(a), ConstValue::Char(b)) => a.partial_cmp(b),
+            (ConstValue::String(a), ConstValue::String(b)) => a.partial_cmp(b),
+            _ => return Err(not_const(expr, "this comparison"))
+        };
+        // NaN-nal minden összehasonlítás hamis, kivéve a `!=`
+        let result = match (op, ordering) {
+            (BinaryOp::Neq, None) => true,
+            (_, None) => false,
+            (BinaryOp::Eq, Some(ordering)) => ordering == Ordering::Equal,
+            (BinaryOp::Neq, Some(ordering)) => ordering != Ordering::Equal,
+            (BinaryOp::Lt, Some(ordering)) => ordering == Ordering::Less,
+            (BinaryOp::Gt, Some(ordering)) => ordering == Ordering::Greater,
+            (BinaryOp::Le, Some(ordering)) => ordering != Ordering::Greater,
+            (_, Some(ordering)) => ordering != Ordering::Less
+        };
+        Ok(ConstValue::Bool(result))
+    }
+
+    fn type_of(&self, expr: &Expr) -> Option<Type> {
+        self.types.get(&expr.span).cloned()
+    }
+
+    // Az egész eredmény elfér-e a kifejezés típusában
+    fn fit(&self, value: ConstValue, expr: &Expr, operation: &str) -> Result<ConstValue, Diagnostic> {
+        let Some(Type::Int(ty)) = self.type_of(expr) else { return Ok(value) };
+        let fits = match value {
+            ConstValue::Int(value) if value < 0 => value.unsigned_abs() <= ty.min_magnitude(),
+            ConstValue::Int(value) => value as u128 <= ty.max_value(),
+            ConstValue::UInt(value) => value <= ty.max_value(),
+            _ => true
+        };
+        if fits { Ok(value) } else { Err(self.overflow(expr, operation)) }
+    }
+
+    // `f32` eredmény kerekítése, hogy a fordítási idejű érték egyezzen a futásidejűvel
+    fn round(&self, value: ConstValue, expr: &Expr) -> ConstValue {
+        match (value, self.type_of(expr)) {
+            (ConstValue::Float(value), Some(Type::Float(FloatTy::F32))) => ConstValue::Float(value as f32 as f64),
+            (value, _) => value
+        }
+    }
+
+    fn overflow(&self, expr: &Expr, operation: &str) -> Diagnostic {
+        let ty = match self.type_of(expr) {
+            Some(Type::Int(ty)) => ty,
+            _ => IntTy::I128
+        };
+        let min = if ty.is_signed() { format!("-{}", ty.min_magnitude()) } else { "0".to_string() };
+        Diagnostic::error(format!("evaluation of constant value failed: {} overflow", operation))
+            .with_code(codes::CONST_OVERFLOW)
+            .with_primary(expr.span, format!("attempt to compute a value outside the range of `{}`", ty.name()))
+            .with_note(format!("the range of `{}` is `{}..={}`", ty.name(), min, ty.max_value()))
+    }
+}
+
+fn not_const(expr: &Expr, what: &str) -> Diagnostic {
+    Diagnostic::error("constant expression expected")
+        .with_code(codes::NOT_CONST)
+        .with_primary(expr.span, format!("{} cannot be evaluated at compile time", what))
+}
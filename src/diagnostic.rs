@@ -0,0 +1,288 @@
+use crate::source_map::{ FileId, SourceMap, Span };
+use std::fmt::Write;
+
+// Hibakódok nyilvántartása
+pub mod codes {
+    // Szintaktikai hibák
+    pub const UNEXPECTED_TOKEN: &str = "E0001";
+    pub const UNEXPECTED_EOF: &str = "E0002";
+    pub const INVALID_TOKEN: &str = "E0003";
+
+    // Típushibák
+    pub const UNDEFINED_NAME: &str = "E0100";
+    pub const MISMATCHED_TYPES: &str = "E0101";
+    pub const WRONG_ARG_COUNT: &str = "E0102";
+    pub const NOT_A_FUNCTION: &str = "E0103";
+    pub const INVALID_OPERANDS: &str = "E0104";
+    pub const NON_BOOL_CONDITION: &str = "E0105";
+    pub const LITERAL_OUT_OF_RANGE: &str = "E0106";
+    pub const NOT_PRINTABLE: &str = "E0107";
+    pub const UNKNOWN_FIELD: &str = "E0108";
+    pub const MISSING_FIELDS: &str = "E0109";
+    pub const DUPLICATE_DEFINITION: &str = "E0110";
+    pub const UNKNOWN_METHOD: &str = "E0111";
+    pub const NON_EXHAUSTIVE_PATTERNS: &str = "E0112";
+    pub const INVALID_PATTERN: &str = "E0113";
+    pub const NOT_ITERABLE: &str = "E0114";
+    pub const RECURSIVE_TYPE: &str = "E0115";
+    pub const UNSATISFIED_BOUND: &str = "E0116";
+    pub const WRONG_TYPE_ARGS: &str = "E0117";
+    pub const CANNOT_INFER: &str = "E0118";
+    pub const NOT_OBJECT_SAFE: &str = "E0119";
+    pub const MISSING_TRAIT_ITEMS: &str = "E0120";
+    pub const NOT_A_TRAIT_MEMBER: &str = "E0121";
+    pub const INCOMPATIBLE_TRAIT_METHOD: &str = "E0122";
+    pub const ESCAPING_CLOSURE: &str = "E0123";
+    pub const INVALID_AWAIT: &str = "E0124";
+    pub const ASSIGN_TO_IMMUTABLE: &str = "E0125";
+    pub const INVALID_ASSIGNMENT: &str = "E0126";
+    pub const NOT_INDEXABLE: &str = "E0127";
+    pub const INDEX_OUT_OF_BOUNDS: &str = "E0128";
+    pub const MISSING_RETURN: &str = "E0129";
+    pub const INVALID_CAST: &str = "E0130";
+    pub const INVALID_TRY: &str = "E0131";
+    pub const UNUSED_RESULT: &str = "E0132";
+
+    // Névfeloldás (modulok)
+    pub const UNRESOLVED_IMPORT: &str = "E0200";
+    pub const PRIVATE_ITEM: &str = "E0201";
+
+    // Fordítási idejű kiértékelés (`konst`)
+    pub const CONST_OVERFLOW: &str = "E0300";
+    pub const DIVISION_BY_ZERO: &str = "E0301";
+    pub const NOT_CONST: &str = "E0302";
+    pub const CONST_CYCLE: &str = "E0303";
+
+    // Kódgenerálás
+    pub const CODEGEN: &str = "E0900";
+
+    // Figyelmeztetések
+    pub const MIXED_SCRIPT_IDENT: &str = "W0001";
+    pub const CONFUSABLE_IDENT: &str = "W0002";
+    pub const UNREACHABLE_PATTERN: &str = "W0003";
+    pub const UNUSED_MUT: &str = "W0004";
+    pub const UNREACHABLE_CODE: &str = "W0005";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note
+}
+
+impl Severity {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note"
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        match self {
+            Severity::Error => "\x1b[1;31m",
+            Severity::Warning => "\x1b[1;33m",
+            Severity::Note => "\x1b[1;32m"
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+    pub primary: bool
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Option<&'static str>,
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Option<String>
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code: None,
+            message: message.into(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+            help: None
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn with_primary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label { span, message: message.into(), primary: true });
+        self
+    }
+
+    pub fn with_secondary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label { span, message: message.into(), primary: false });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    pub fn primary_span(&self) -> Option<Span> {
+        self.labels.iter().find(|l| l.primary).or(self.labels.first()).map(|l| l.span)
+    }
+}
+
+// Rustc-stílusú megjelenítés: fejléc, forrássor, aláhúzás, megjegyzések
+pub struct Renderer<'a> {
+    source_map: &'a SourceMap,
+    colored: bool
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(source_map: &'a SourceMap, colored: bool) -> Self {
+        Self { source_map, colored }
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.colored {
+            format!("{}{}\x1b[0m", color, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    pub fn render(&self, diag: &Diagnostic) -> String {
+        let mut out = String::new();
+        let blue = "\x1b[1;34m";
+
+        let header = match diag.code {
+            Some(code) => format!("{}[{}]", diag.severity.as_str(), code),
+            None => diag.severity.as_str().to_string()
+        };
+        let _ = writeln!(
+            out,
+            "{}{}",
+            self.paint(diag.severity.color(), &header),
+            self.paint("\x1b[1m", &format!(": {}", diag.message))
+        );
+
+        // Címkék csoportosítása fájl szerint, az elsődleges fájl kerül előre
+        let mut files: Vec<FileId> = Vec::new();
+        if let Some(span) = diag.primary_span() {
+            files.push(span.file);
+        }
+        for label in &diag.labels {
+            if !files.contains(&label.span.file) {
+                files.push(label.span.file);
+            }
+        }
+
+        let max_line = diag.labels.iter()
+            .map(|l| self.source_map.location(l.span).line)
+            .max()
+            .unwrap_or(0);
+        let width = max_line.to_string().len();
+        let gutter = " ".repeat(width);
+
+        for (i, file_id) in files.iter().enumerate() {
+            let file = self.source_map.get(*file_id);
+            let mut labels: Vec<&Label> = diag.labels.iter().filter(|l| l.span.file == *file_id).collect();
+            labels.sort_by_key(|l| (l.span.start, !l.primary));
+
+            let anchor = if i == 0 {
+                diag.primary_span().filter(|s| s.file == *file_id).unwrap_or(labels[0].span)
+            } else {
+                labels[0].span
+            };
+            let arrow = if i == 0 { "-->" } else { ":::" };
+            let _ = writeln!(out, "{}{} {}", gutter, self.paint(blue, arrow), self.source_map.location(anchor));
+            let _ = writeln!(out, "{} {}", gutter, self.paint(blue, "|"));
+
+            let mut lines: Vec<usize> = labels.iter().map(|l| file.line_index(l.span.start)).collect();
+            lines.dedup();
+
+            let mut prev_line: Option<usize> = None;
+            for line in lines {
+                if let Some(prev) = prev_line {
+                    if line > prev + 1 {
+                        let _ = writeln!(out, "{}", self.paint(blue, "..."));
+                    }
+                }
+                prev_line = Some(line);
+
+                let text = file.line_text(line);
+                let line_no = format!("{:>width$}", line + 1, width = width);
+                let _ = writeln!(out, "{} {} {}", self.paint(blue, &line_no), self.paint(blue, "|"), text);
+
+                for label in labels.iter().filter(|l| file.line_index(l.span.start) == line) {
+                    let (_, col) = file.line_col(label.span.start);
+                    // Többsoros tartománynál csak az első sor végéig húzunk alá
+                    let line_len = text.chars().count();
+                    let (end_line, end_col) = file.line_col(label.span.end);
+                    let end_col = if end_line - 1 == line { end_col } else { line_len + 1 };
+                    let len = end_col.saturating_sub(col).max(1);
+
+                    let (marker, color) = if label.primary {
+                        ("^", diag.severity.color())
+                    } else {
+                        ("-", blue)
+                    };
+                    let underline = marker.repeat(len);
+                    let message = if label.message.is_empty() {
+                        underline
+                    } else {
+                        format!("{} {}", underline, label.message)
+                    };
+                    let _ = writeln!(
+                        out,
+                        "{} {} {}{}",
+                        gutter,
+                        self.paint(blue, "|"),
+                        " ".repeat(col - 1),
+                        self.paint(color, &message)
+                    );
+                }
+            }
+        }
+
+        if !diag.labels.is_empty() && (!diag.notes.is_empty() || diag.help.is_some()) {
+            let _ = writeln!(out, "{} {}", gutter, self.paint(blue, "|"));
+        }
+        for note in &diag.notes {
+            let _ = writeln!(out, "{} {} {}", gutter, self.paint(blue, "="), format_args!("{}: {}", self.paint("\x1b[1m", "note"), note));
+        }
+        if let Some(help) = &diag.help {
+            let _ = writeln!(out, "{} {} {}", gutter, self.paint(blue, "="), format_args!("{}: {}", self.paint("\x1b[1m", "help"), help));
+        }
+
+        out
+    }
+}
@@ -0,0 +1,449 @@
+use crate::ast::{ Expr, ExprKind, GenericParam, Literal, Pattern, PatternKind, UnaryOp };
+use crate::typechecker::Type;
+use std::collections::HashMap;
+
+// Az `eset` ágainak teljesség- és elérhetőség-vizsgálata a "usefulness" algoritmussal
+// (Maranget: Warnings for pattern matching). A mintákat konstruktorokra bontjuk, az
+// egész és karakter tartományokat pedig a mintákban szereplő határok mentén daraboljuk.
+
+#[derive(Debug, Clone, PartialEq)]
+enum Ctor {
+    Bool(bool),
+    // Zárt tartomány normalizált értékekkel (lásd `normalize`)
+    Range(u128, u128),
+    // Struct vagy tuple: egyetlen konstruktor, a mezők az argumentumok
+    Single,
+    // Enum-változat a deklarációbeli indexével; az adatmezők az argumentumok
+    Variant(usize),
+    // Végtelen típus (string, float) literálja; csak önmagát fedi
+    Opaque(String)
+}
+
+#[derive(Debug, Clone)]
+enum Pat {
+    Wild,
+    Ctor(Ctor, Vec<Pat>)
+}
+
+pub struct MatchReport {
+    // Az elérhetetlen ágak indexei
+    pub unreachable: Vec<usize>,
+    // Egy le nem fedett érték mintaként, ha az illesztés nem teljes
+    pub missing: Option<String>
+}
+
+// Az egész értékeket eltoljuk, hogy a típus legkisebb értéke 0 legyen, így minden
+// egész típus tartománya elfér egy u128-ban
+fn normalize(value: u128, negative: bool, ty: &Type) -> u128 {
+    match ty {
+        Type::Int(int_ty) if negative => int_ty.min_magnitude().wrapping_sub(value),
+        Type::Int(int_ty) => int_ty.min_magnitude().wrapping_add(value),
+        _ => value
+    }
+}
+
+// A (már típusellenőrzött) literál minta konstruktora
+fn literal_ctor(expr: &Expr, ty: &Type) -> Ctor {
+    let (lit, negative) = match &expr.kind {
+        ExprKind::Unary { op: UnaryOp::Neg, operand } => match &operand.kind {
+            ExprKind::Literal(lit) => (lit, true),
+            _ => return Ctor::Opaque(String::new())
+        },
+        ExprKind::Literal(lit) => (lit, false),
+        _ => return Ctor::Opaque(String::new())
+    };
+
+    match lit {
+        Literal::Int(value, _) => {
+            let value = normalize(*value, negative, ty);
+            Ctor::Range(value, value)
+        },
+        Literal::Char(c) => Ctor::Range(*c as u128, *c as u128),
+        Literal::Bool(b) => Ctor::Bool(*b),
+        Literal::Float(value, _) => Ctor::Opaque(format!("{}{}", if negative { "-" } else { "" }, value)),
+        Literal::String(s) => Ctor::Opaque(format!("{:?}", s)),
+        Literal::ByteString(bytes) => Ctor::Opaque(format!("b{:?}", String::from_utf8_lossy(bytes)))
+    }
+}
+
+fn literal_value(expr: &Expr, ty: &Type) -> Option<u128> {
+    match literal_ctor(expr, ty) {
+        Ctor::Range(value, _) => Some(value),
+        _ => None
+    }
+}
+
+// Üres-e a tartomány minta (pl. `5..=1` vagy `3..3`)
+pub fn is_empty_range(start: &Expr, end: &Expr, inclusive: bool, ty: &Type) -> bool {
+    match (literal_value(start, ty), literal_value(end, ty)) {
+        (Some(lo), Some(hi)) => if inclusive { lo > hi } else { lo >= hi },
+        _ => false
+    }
+}
+
+fn covers(ctor: &Ctor, other: &Ctor) -> bool {
+    match (ctor, other) {
+        (Ctor::Range(lo, hi), Ctor::Range(a, b)) => lo <= a && b <= hi,
+        _ => ctor == other
+    }
+}
+
+// Egy konstruktor helyettesítése a sor elején az argumentumaival (vagy a sor elhagyása)
+fn specialize(row: &[Pat], ctor: &Ctor, arity: usize) -> Option<Vec<Pat>> {
+    let mut specialized = match &row[0] {
+        Pat::Wild => vec![Pat::Wild; arity],
+        Pat::Ctor(head, args) if covers(head, ctor) => args.clone(),
+        Pat::Ctor(..) => return None
+    };
+    specialized.extend_from_slice(&row[1..]);
+    Some(specialized)
+}
+
+pub struct Matcher<'a> {
+    structs: &'a HashMap<String, Vec<(String, Type)>>,
+    enums: &'a HashMap<String, Vec<(String, Vec<Type>)>>,
+    generics: &'a HashMap<String, Vec<GenericParam>>
+}
+
+impl<'a> Matcher<'a> {
+    pub fn new(
+        structs: &'a HashMap<String, Vec<(String, Type)>>,
+        enums: &'a HashMap<String, Vec<(String, Vec<Type>)>>,
+        generics: &'a HashMap<String, Vec<GenericParam>>
+    ) -> Self {
+        Self { structs, enums, generics }
+    }
+
+    // A generikus struct vagy enum mezőtípusai a típusargumentumokkal helyettesítve
+    fn instantiate(&self, name: &str, args: &[Type], ty: &Type) -> Type {
+        let subst: HashMap<String, Type> = self.generics.get(name)
+            .map(|generics| generics.iter().map(|g| g.name.clone()).zip(args.iter().cloned()).collect())
+            .unwrap_or_default();
+        ty.substitute(&subst)
+    }
+
+    pub fn check(&self, ty: &Type, arms: &[(&Pattern, bool)]) -> MatchReport {
+        let mut rows: Vec<Vec<Pat>> = Vec::new();
+        let mut unreachable = Vec::new();
+        let tys = [ty.clone()];
+
+        for (i, (pattern, guarded)) in arms.iter().enumerate() {
+            let pat = self.lower(pattern, ty);
+            if self.useful(&rows, std::slice::from_ref(&pat), &tys).is_none() {
+                unreachable.push(i);
+            }
+            // Az őrfeltételes ág nem feltétlenül illeszkedik, így nem fed le semmit
+            if !guarded {
+                rows.push(vec![pat]);
+            }
+        }
+
+        let missing = self.useful(&rows, &[Pat::Wild], &tys).map(|witness| self.display(&witness[0], ty));
+        MatchReport { unreachable, missing }
+    }
+
+    fn lower(&self, pattern: &Pattern, ty: &Type) -> Pat {
+        match &pattern.kind {
+            PatternKind::Wildcard | PatternKind::Binding(_) => Pat::Wild,
+            PatternKind::Literal(expr) => Pat::Ctor(literal_ctor(expr, ty), Vec::new()),
+            PatternKind::Range { start, end, inclusive } => {
+                let (Some(lo), Some(hi)) = (literal_value(start, ty), literal_value(end, ty)) else {
+                    return Pat::Ctor(Ctor::Opaque(String::new()), Vec::new());
+                };
+                let hi = if *inclusive { hi } else { hi - 1 };
+                Pat::Ctor(Ctor::Range(lo, hi), Vec::new())
+            },
+            PatternKind::Tuple(elements) => {
+                let Type::Tuple(tys) = ty else { return Pat::Wild };
+                let args = elements.iter().zip(tys).map(|(element, ty)| self.lower(element, ty)).collect();
+                Pat::Ctor(Ctor::Single, args)
+            },
+            PatternKind::Struct { name, fields, .. } => {
+                let Some(defs) = self.structs.get(name) else { return Pat::Wild };
+                let type_args = match ty {
+                    Type::Struct(_, type_args) => type_args.as_slice(),
+                    _ => &[]
+                };
+                // A mezők a deklaráció sorrendjében, a kihagyottak `_`-ként
+                let args = defs.iter()
+                    .map(|(field_name, field_ty)| {
+                        fields.iter()
+                            .find(|field| field.name == *field_name)
+                            .map_or(Pat::Wild, |field| self.lower(&field.pattern, &self.instantiate(name, type_args, field_ty)))
+                    })
+                    .collect();
+                Pat::Ctor(Ctor::Single, args)
+            },
+            PatternKind::Variant { variant, fields, .. } => {
+                let Type::Enum(name, type_args) = ty else { return Pat::Wild };
+                let Some(index) = self.enums.get(name).and_then(|variants| variants.iter().position(|(v, _)| v == variant)) else {
+                    return Pat::Wild;
+                };
+                let args = fields.iter()
+                    .zip(&self.enums[name][index].1)
+                    .map(|(field, field_ty)| self.lower(field, &self.instantiate(name, type_args, field_ty)))
+                    .collect();
+                Pat::Ctor(Ctor::Variant(index), args)
+            }
+        }
+    }
+
+    // A típus összes konstruktora; végtelen típusnál None
+    fn all_ctors(&self, ty: &Type) -> Option<Vec<Ctor>> {
+        match ty {
+            Type::Bool => Some(vec![Ctor::Bool(false), Ctor::Bool(true)]),
+            Type::Int(int_ty) => Some(vec![Ctor::Range(0, int_ty.max_value() + int_ty.min_magnitude())]),
+            // A surrogate tartomány (D800-DFFF) nem érvényes karakter
+            Type::Char => Some(vec![Ctor::Range(0, 0xD7FF), Ctor::Range(0xE000, 0x10FFFF)]),
+            Type::Struct(..) | Type::Tuple(_) => Some(vec![Ctor::Single]),
+            // A változat nélküli enumnak nincs értéke, így bármely illesztés teljes rá
+            Type::Enum(name, _) => Some((0..self.enums.get(name).map_or(0, |variants| variants.len())).map(Ctor::Variant).collect()),
+            _ => None
+        }
+    }
+
+    fn sub_types(&self, ctor: &Ctor, ty: &Type) -> Vec<Type> {
+        match (ctor, ty) {
+            (Ctor::Single, Type::Tuple(tys)) => tys.clone(),
+            (Ctor::Single, Type::Struct(name, args)) => self.structs.get(name)
+                .map(|fields| fields.iter().map(|(_, ty)| self.instantiate(name, args, ty)).collect())
+                .unwrap_or_default(),
+            (Ctor::Variant(index), Type::Enum(name, args)) => self.enums.get(name)
+                .and_then(|variants| variants.get(*index))
+                .map(|(_, fields)| fields.iter().map(|ty| self.instantiate(name, args, ty)).collect())
+                .unwrap_or_default(),
+            _ => Vec::new()
+        }
+    }
+
+    // Tartomány felosztása az oszlopban szereplő tartományok határai mentén, hogy minden
+    // darabot bármely sor vagy teljesen lefedjen, vagy egyáltalán ne
+    fn split(&self, ctor: &Ctor, column: &[&Ctor]) -> Vec<Ctor> {
+        let Ctor::Range(lo, hi) = *ctor else { return vec![ctor.clone()] };
+
+        let mut starts = vec![lo];
+        for other in column {
+            if let Ctor::Range(a, b) = **other {
+                if a > lo && a <= hi {
+                    starts.push(a);
+                }
+                if b >= lo && b < hi {
+                    starts.push(b + 1);
+                }
+            }
+        }
+        starts.sort_unstable();
+        starts.dedup();
+
+        starts.iter()
+            .enumerate()
+            .map(|(i, &start)| Ctor::Range(start, starts.get(i + 1).map_or(hi, |next| next - 1)))
+            .collect()
+    }
+
+    // Ha a `v` sor illeszthet olyan értékre, amelyet a `rows` egyike sem, visszaad egy
+    // ilyen értéket (tanút) oszloponként
+    fn useful(&self, rows: &[Vec<Pat>], v: &[Pat], tys: &[Type]) -> Option<Vec<Pat>> {
+        let Some((head, rest)) = v.split_first() else {
+            return if rows.is_empty() { Some(Vec::new()) } else { None };
+        };
+        let ty = &tys[0];
+        let column: Vec<&Ctor> = rows.iter()
+            .filter_map(|row| match &row[0] {
+                Pat::Ctor(ctor, _) => Some(ctor),
+                Pat::Wild => None
+            })
+            .collect();
+
+        if let Pat::Ctor(ctor, _) = head {
+            return self.split(ctor, &column)
+                .iter()
+                .find_map(|sub| self.useful_specialized(rows, v, tys, sub));
+        }
+
+        let all: Option<Vec<Ctor>> = self.all_ctors(ty)
+            .map(|all| all.iter().flat_map(|ctor| self.split(ctor, &column)).collect());
+        let missing: Vec<Ctor> = all.iter()
+            .flatten()
+            .filter(|ctor| !column.iter().any(|head| covers(head, ctor)))
+            .cloned()
+            .collect();
+
+        match all {
+            // Minden konstruktor előfordul: konstruktoronként vizsgáljuk
+            Some(all) if missing.is_empty() => all.iter().find_map(|ctor| self.useful_specialized(rows, v, tys, ctor)),
+            // Van hiányzó konstruktor: elég a `_`-os sorokat tovább vizsgálni
+            _ => {
+                let default: Vec<Vec<Pat>> = rows.iter()
+                    .filter(|row| matches!(row[0], Pat::Wild))
+                    .map(|row| row[1..].to_vec())
+                    .collect();
+                let mut witness = self.useful(&default, rest, &tys[1..])?;
+                let head = match missing.into_iter().next() {
+                    Some(ctor) if !column.is_empty() => {
+                        let arity = self.sub_types(&ctor, ty).len();
+                        Pat::Ctor(ctor, vec![Pat::Wild; arity])
+                    },
+                    _ => Pat::Wild
+                };
+                witness.insert(0, head);
+                Some(witness)
+            }
+        }
+    }
+
+    fn useful_specialized(&self, rows: &[Vec<Pat>], v: &[Pat], tys: &[Type], ctor: &Ctor) -> Option<Vec<Pat>> {
+        let sub_types = self.sub_types(ctor, &tys[0]);
+        let arity = sub_types.len();
+        let rows: Vec<Vec<Pat>> = rows.iter().filter_map(|row| specialize(row, ctor, arity)).collect();
+        let v = specialize(v, ctor, arity)?;
+        let tys: Vec<Type> = sub_types.into_iter().chain(tys[1..].iter().cloned()).collect();
+
+        let mut witness = self.useful(&rows, &v, &tys)?;
+        let args = witness.drain(..arity).collect();
+        witness.insert(0, Pat::Ctor(ctor.clone(), args));
+        Some(witness)
+    }
+
+    fn display(&self, pat: &Pat, ty: &Type) -> String {
+        let Pat::Ctor(ctor, args) = pat else { return "_".to_string() };
+        match ctor {
+            Ctor::Bool(b) => if *b { "igaz" } else { "hamis" }.to_string(),
+            Ctor::Range(lo, hi) if lo == hi => self.display_value(*lo, ty),
+            Ctor::Range(lo, hi) => format!("{}..={}", self.display_value(*lo, ty), self.display_value(*hi, ty)),
+            Ctor::Opaque(text) => text.clone(),
+            Ctor::Variant(index) => {
+                let Type::Enum(name, _) = ty else { return "_".to_string() };
+                let sub_types = self.sub_types(ctor, ty);
+                let args: Vec<String> = args.iter().zip(&sub_types).map(|(arg, ty)| self.display(arg, ty)).collect();
+                let variant = &self.enums[name][*index].0;
+                if args.is_empty() {
+                    format!("{}::{}", name, variant)
+                } else {
+                    format!("{}::{}({})", name, variant, args.join(", "))
+                }
+            },
+            Ctor::Single => {
+                let sub_types = self.sub_types(ctor, ty);
+                let args: Vec<String> = args.iter().zip(&sub_types).map(|(arg, ty)| self.display(arg, ty)).collect();
+                match ty {
+                    Type::Struct(name, _) => {
+                        let fields: Vec<String> = self.structs[name].iter()
+                            .zip(args)
+                            .map(|((field, _), arg)| format!("{}: {}", field, arg))
+                            .collect();
+                        format!("{} {{ {} }}", name, fields.join(", "))
+                    },
+                    _ => format!("({})", args.join(", "))
+                }
+            }
+        }
+    }
+
+    fn display_value(&self, value: u128, ty: &Type) -> String {
+        match ty {
+            Type::Int(int_ty) if value < int_ty.min_magnitude() => format!("-{}", int_ty.min_magnitude() - value),
+            Type::Int(int_ty) => (value - int_ty.min_magnitude()).to_string(),
+            Type::Char => format!("{:?}", char::from_u32(value as u32).unwrap_or(char::REPLACEMENT_CHARACTER)),
+            _ => value.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{IntTy, MatchArm, StmtKind};
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::source_map::{FileId, Span};
+
+    // Az `eset x { ... }` ágai a forrásból
+    fn arms(src: &str) -> Vec<MatchArm> {
+        let src = format!("fv f() {{ eset x {{ {} }}; }}", src);
+        let (program, diagnostics) = Parser::new(Lexer::new(&src, FileId(0))).parse_program();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        let StmtKind::Expr(expr) = &program.functions[0].body[0].kind else { panic!("expected an expression statement") };
+        let ExprKind::Match { arms, .. } = &expr.kind else { panic!("expected `eset`") };
+        arms.clone()
+    }
+
+    // `enum Szin { Piros, Zold, Kek }`, `enum Alak { Kor(Szin), Pont }`, `enum Opcio<T> { Van(T), Nincs }`
+    fn check(ty: &Type, src: &str) -> MatchReport {
+        let szin = Type::Enum("Szin".to_string(), Vec::new());
+        let structs = HashMap::new();
+        let enums = HashMap::from([
+            ("Szin".to_string(), vec![("Piros".to_string(), vec![]), ("Zold".to_string(), vec![]), ("Kek".to_string(), vec![])]),
+            ("Alak".to_string(), vec![("Kor".to_string(), vec![szin]), ("Pont".to_string(), vec![])]),
+            ("Opcio".to_string(), vec![("Van".to_string(), vec![Type::Param("T".to_string())]), ("Nincs".to_string(), vec![])]),
+        ]);
+        let generics = HashMap::from([
+            ("Opcio".to_string(), vec![GenericParam { name: "T".to_string(), bounds: Vec::new(), span: Span::new(FileId(0), 0, 0) }]),
+        ]);
+        let arms = arms(src);
+        let arms: Vec<(&Pattern, bool)> = arms.iter().map(|arm| (&arm.pattern, arm.guard.is_some())).collect();
+        Matcher::new(&structs, &enums, &generics).check(ty, &arms)
+    }
+
+    fn enum_type(name: &str, args: Vec<Type>) -> Type {
+        Type::Enum(name.to_string(), args)
+    }
+
+    #[test]
+    fn nested_enum_reports_missing_inner_variant() {
+        let alak = enum_type("Alak", Vec::new());
+        let report = check(&alak, "Alak::Kor(Szin::Piros) => 1, Alak::Kor(Szin::Zold) => 2, Alak::Pont => 3");
+        assert_eq!(report.missing.as_deref(), Some("Alak::Kor(Szin::Kek)"));
+        assert!(report.unreachable.is_empty());
+
+        let report = check(&alak, "Alak::Kor(Szin::Piros) => 1, Alak::Kor(_) => 2, Alak::Pont => 3");
+        assert_eq!(report.missing, None);
+    }
+
+    #[test]
+    fn nested_generic_enum_is_instantiated() {
+        let opcio = enum_type("Opcio", vec![enum_type("Szin", Vec::new())]);
+        let report = check(&opcio, "Opcio::Van(Szin::Piros) => 1, Opcio::Van(Szin::Kek) => 2, Opcio::Nincs => 3");
+        assert_eq!(report.missing.as_deref(), Some("Opcio::Van(Szin::Zold)"));
+
+        let nested = enum_type("Opcio", vec![opcio]);
+        let report = check(&nested, "Opcio::Van(Opcio::Van(_)) => 1, Opcio::Nincs => 2");
+        assert_eq!(report.missing.as_deref(), Some("Opcio::Van(Opcio::Nincs)"));
+    }
+
+    #[test]
+    fn integer_ranges_are_split_at_their_bounds() {
+        let u8_type = Type::Int(IntTy::U8);
+        assert_eq!(check(&u8_type, "0..=100 => 1, 101..=255 => 2").missing, None);
+        assert_eq!(check(&u8_type, "0..=100 => 1, 102..=255 => 2").missing.as_deref(), Some("101"));
+        assert_eq!(check(&u8_type, "0..255 => 1").missing.as_deref(), Some("255"));
+
+        let i8_type = Type::Int(IntTy::I8);
+        assert_eq!(check(&i8_type, "-128..=-1 => 1, 0 => 2, 1..=127 => 3").missing, None);
+        assert_eq!(check(&i8_type, "-127..=127 => 1").missing.as_deref(), Some("-128"));
+    }
+
+    #[test]
+    fn guarded_arms_cover_nothing() {
+        let report = check(&Type::Bool, "igaz ha c => 1, hamis => 2");
+        assert_eq!(report.missing.as_deref(), Some("igaz"));
+
+        // A minden értékre illeszkedő, de őrfeltételes ág után a többi ág is elérhető
+        let report = check(&Type::Bool, "_ ha c => 1, igaz => 2, hamis => 3");
+        assert_eq!(report.missing, None);
+        assert!(report.unreachable.is_empty());
+    }
+
+    #[test]
+    fn redundant_arms_are_unreachable() {
+        let szin = enum_type("Szin", Vec::new());
+        let report = check(&szin, "Szin::Piros => 1, _ => 2, Szin::Zold => 3");
+        assert_eq!(report.unreachable, vec![2]);
+
+        let report = check(&szin, "Szin::Piros => 1, Szin::Piros => 2, Szin::Zold => 3, Szin::Kek => 4");
+        assert_eq!(report.unreachable, vec![1]);
+
+        let report = check(&Type::Int(IntTy::U8), "0..=10 => 1, 5..=8 => 2, _ => 3");
+        assert_eq!(report.unreachable, vec![1]);
+    }
+}
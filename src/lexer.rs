@@ -1,14 +1,410 @@
 use logos::Logos;
 use std::fmt;
+use unicode_normalization::UnicodeNormalization;
+use crate::ast::{ FloatTy, IntTy };
+use crate::source_map::{ FileId, Span };
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum LexError {
+    #[default]
+    UnexpectedChar,
+    IntegerTooLarge,
+    InvalidDigit(char, u32),
+    InvalidSuffix(String),
+    MissingDigits,
+    InvalidFloat,
+    UnterminatedString,
+    // Az escape hibák a tokenen belüli (start, end) bájt-tartományt is hordozzák
+    InvalidEscape { escape: String, start: usize, end: usize },
+    InvalidUnicodeEscape { reason: &'static str, start: usize, end: usize },
+    NonAsciiInByteString { ch: char, start: usize, end: usize },
+    EmptyChar,
+    MultipleCharsInChar,
+    // String-interpoláció: `{}` hibák
+    EmptyInterpolation { start: usize, end: usize },
+    UnclosedInterpolation { start: usize, end: usize },
+    UnmatchedBrace { start: usize, end: usize }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LexError::UnexpectedChar => write!(f, "unexpected character"),
+            LexError::IntegerTooLarge => write!(f, "integer literal is too large"),
+            LexError::InvalidDigit(c, radix) => write!(f, "invalid digit `{}` in base {} literal", c, radix),
+            LexError::InvalidSuffix(suffix) => write!(f, "invalid suffix `{}` for number literal", suffix),
+            LexError::MissingDigits => write!(f, "no valid digits found for number literal"),
+            LexError::InvalidFloat => write!(f, "invalid float literal"),
+            LexError::UnterminatedString => write!(f, "unterminated string literal"),
+            LexError::InvalidEscape { escape, .. } => write!(f, "unknown character escape: `{}`", escape),
+            LexError::InvalidUnicodeEscape { reason, .. } => write!(f, "invalid escape: {}", reason),
+            LexError::NonAsciiInByteString { ch, .. } => write!(f, "non-ASCII character `{}` in byte string literal", ch),
+            LexError::EmptyChar => write!(f, "empty character literal"),
+            LexError::MultipleCharsInChar => write!(f, "character literal may only contain one codepoint"),
+            LexError::EmptyInterpolation { .. } => write!(f, "empty interpolation `{{}}` in string literal"),
+            LexError::UnclosedInterpolation { .. } => write!(f, "unclosed `{{` in string literal"),
+            LexError::UnmatchedBrace { .. } => write!(f, "unmatched `}}` in string literal")
+        }
+    }
+}
+
+impl LexError {
+    // Helyettesítő token, amellyel a parser a hiba után tovább tud haladni
+    // (a `slice` a hibás token szövege)
+    pub fn recovery_token(&self, slice: &str) -> Option<Token> {
+        match self {
+            LexError::UnexpectedChar => None,
+            LexError::InvalidFloat => Some(Token::Float(FloatLit { value: 0.0, suffix: None })),
+            LexError::EmptyChar | LexError::MultipleCharsInChar => Some(Token::CharLit('\0')),
+            LexError::UnterminatedString
+            | LexError::InvalidEscape { .. }
+            | LexError::InvalidUnicodeEscape { .. }
+            | LexError::NonAsciiInByteString { .. }
+            | LexError::EmptyInterpolation { .. }
+            | LexError::UnclosedInterpolation { .. }
+            | LexError::UnmatchedBrace { .. } => {
+                if slice.starts_with('b') {
+                    Some(Token::ByteStringLit(Vec::new()))
+                } else if slice.starts_with('\'') {
+                    Some(Token::CharLit('\0'))
+                } else {
+                    Some(Token::StringLit(Vec::new()))
+                }
+            },
+            _ => Some(Token::Int(IntLit { value: 0, suffix: None }))
+        }
+    }
+
+    pub fn help(&self) -> Option<&'static str> {
+        match self {
+            LexError::EmptyInterpolation { .. } => Some("if you meant a literal `{}`, escape the braces: `{{}}`"),
+            LexError::UnmatchedBrace { .. } => Some("if you meant a literal `}`, escape it: `}}`"),
+            LexError::UnclosedInterpolation { .. } => Some("if you meant a literal `{`, escape it: `{{`"),
+            _ => None
+        }
+    }
+
+    // A hibás escape pontos helye a tokenen belül, ha ismert
+    pub fn relative_span(&self) -> Option<(usize, usize)> {
+        match self {
+            LexError::InvalidEscape { start, end, .. }
+            | LexError::InvalidUnicodeEscape { start, end, .. }
+            | LexError::NonAsciiInByteString { start, end, .. }
+            | LexError::EmptyInterpolation { start, end }
+            | LexError::UnclosedInterpolation { start, end }
+            | LexError::UnmatchedBrace { start, end } => Some((*start, *end)),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntLit {
+    pub value: u128,
+    pub suffix: Option<IntTy>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatLit {
+    pub value: f64,
+    pub suffix: Option<FloatTy>
+}
+
+// Interpolált string darabja: feloldott szöveg, vagy egy `{...}` kifejezés forrása
+// a kezdetének tokenen belüli bájt-pozíciójával
+#[derive(Debug, Clone, PartialEq)]
+pub enum StrSegment {
+    Text(String),
+    Expr(String, usize)
+}
+
+// Egész literál: 1_000, 0xFF, 0o17, 0b1010, opcionális típus-utótaggal (10u8)
+fn lex_int(lex: &mut logos::Lexer<Token>) -> Result<IntLit, LexError> {
+    let text = lex.slice();
+    let (radix, body) = match text.get(..2) {
+        Some("0x") => (16, &text[2..]),
+        Some("0o") => (8, &text[2..]),
+        Some("0b") => (2, &text[2..]),
+        _ => (10, text)
+    };
+
+    let digits_end = body
+        .find(|c: char| c != '_' && !c.is_digit(radix))
+        .unwrap_or(body.len());
+    let (digits, suffix) = body.split_at(digits_end);
+
+    if let Some(c) = suffix.chars().next().filter(|c| c.is_ascii_digit()) {
+        return Err(LexError::InvalidDigit(c, radix));
+    }
+
+    let digits: String = digits.chars().filter(|c| *c != '_').collect();
+    if digits.is_empty() {
+        return Err(LexError::MissingDigits);
+    }
+
+    let suffix = if suffix.is_empty() {
+        None
+    } else {
+        Some(IntTy::from_name(suffix).ok_or_else(|| LexError::InvalidSuffix(suffix.to_string()))?)
+    };
+
+    let value = u128::from_str_radix(&digits, radix).map_err(|_| LexError::IntegerTooLarge)?;
+    Ok(IntLit { value, suffix })
+}
+
+// Lebegőpontos literál: 1.5, 1e-9, 2.5E3f32, 10f64
+fn lex_float(lex: &mut logos::Lexer<Token>) -> Result<FloatLit, LexError> {
+    let text = lex.slice();
+    let suffix_start = text
+        .find(|c: char| c.is_ascii_alphabetic() && c != 'e' && c != 'E')
+        .unwrap_or(text.len());
+    let (number, suffix) = text.split_at(suffix_start);
+
+    let suffix = if suffix.is_empty() {
+        None
+    } else {
+        Some(FloatTy::from_name(suffix).ok_or_else(|| LexError::InvalidSuffix(suffix.to_string()))?)
+    };
+
+    let number: String = number.chars().filter(|c| *c != '_').collect();
+    let value = number.parse().map_err(|_| LexError::InvalidFloat)?;
+    Ok(FloatLit { value, suffix })
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum EscapeMode {
+    Str,
+    Char,
+    Byte
+}
+
+// Escape szekvenciák feloldása. Az `offset` a `body` kezdete a tokenen belül,
+// hogy a hibák a hibás escape-re mutathassanak. Bájt módban minden karakter < 256.
+fn unescape(body: &str, offset: usize, mode: EscapeMode) -> Result<Vec<char>, LexError> {
+    let mut out = Vec::new();
+    let mut chars = body.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let start = offset + i;
+        if c != '\\' {
+            if mode == EscapeMode::Byte && !c.is_ascii() {
+                return Err(LexError::NonAsciiInByteString { ch: c, start, end: start + c.len_utf8() });
+            }
+            out.push(c);
+            continue;
+        }
+
+        let Some((_, esc)) = chars.next() else {
+            return Err(LexError::InvalidEscape { escape: "\\".to_string(), start, end: start + 1 });
+        };
+        let decoded = match esc {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            // Sorvégi `\`: a sortörés és a következő sor elejének szóközei kimaradnak
+            '\n' if mode != EscapeMode::Char => {
+                while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+                continue;
+            },
+            'x' => {
+                let hex: String = (0..2).filter_map(|_| chars.next_if(|(_, c)| c.is_ascii_hexdigit())).map(|(_, c)| c).collect();
+                let end = start + 2 + hex.len();
+                if hex.len() != 2 {
+                    return Err(LexError::InvalidUnicodeEscape { reason: "numeric character escape is too short", start, end });
+                }
+                let value = u8::from_str_radix(&hex, 16).unwrap();
+                if value > 0x7F && mode != EscapeMode::Byte {
+                    return Err(LexError::InvalidUnicodeEscape { reason: "out of range hex escape (must be at most \\x7F)", start, end });
+                }
+                char::from(value)
+            },
+            'u' => {
+                if mode == EscapeMode::Byte {
+                    return Err(LexError::InvalidUnicodeEscape { reason: "unicode escape in byte string", start, end: start + 2 });
+                }
+                if chars.next_if(|(_, c)| *c == '{').is_none() {
+                    return Err(LexError::InvalidUnicodeEscape { reason: "incorrect unicode escape sequence, expected `{`", start, end: start + 2 });
+                }
+                let mut hex = String::new();
+                let mut end = start + 3;
+                loop {
+                    match chars.next() {
+                        Some((j, '}')) => {
+                            end = offset + j + 1;
+                            break;
+                        },
+                        Some((j, c)) if c.is_ascii_hexdigit() || c == '_' => {
+                            end = offset + j + 1;
+                            if c != '_' {
+                                hex.push(c);
+                            }
+                        },
+                        _ => return Err(LexError::InvalidUnicodeEscape { reason: "unterminated unicode escape", start, end })
+                    }
+                }
+                if hex.is_empty() || hex.len() > 6 {
+                    return Err(LexError::InvalidUnicodeEscape { reason: "unicode escape must have 1 to 6 hex digits", start, end });
+                }
+                let value = u32::from_str_radix(&hex, 16).unwrap();
+                char::from_u32(value).ok_or(LexError::InvalidUnicodeEscape {
+                    reason: "invalid unicode character escape (surrogate or above 10FFFF)",
+                    start,
+                    end
+                })?
+            },
+            other => {
+                return Err(LexError::InvalidEscape {
+                    escape: format!("\\{}", other),
+                    start,
+                    end: start + 1 + other.len_utf8()
+                });
+            }
+        };
+        out.push(decoded);
+    }
+
+    Ok(out)
+}
+
+// "..." (escape-ekkel és `{kifejezés}` interpolációval; `{{` és `}}` a kapcsos zárójel maga)
+fn lex_string(lex: &mut logos::Lexer<Token>) -> Result<Vec<StrSegment>, LexError> {
+    let s = lex.slice();
+    split_interpolation(&s[1..s.len() - 1], 1)
+}
+
+fn split_interpolation(body: &str, offset: usize) -> Result<Vec<StrSegment>, LexError> {
+    let bytes = body.as_bytes();
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    // A még fel nem oldott szövegrész eleje
+    let mut chunk_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => {
+                // Az escape-et egyben ugorjuk át, hogy a `\u{..}` kapcsos zárójele ne számítson
+                i += 1;
+                if body[i..].starts_with("u{") {
+                    i = body[i..].find('}').map_or(bytes.len(), |j| i + j + 1);
+                } else {
+                    i += body[i..].chars().next().map_or(0, char::len_utf8);
+                }
+            },
+            b @ (b'{' | b'}') if bytes.get(i + 1) == Some(&b) => {
+                text.extend(unescape(&body[chunk_start..i], offset + chunk_start, EscapeMode::Str)?);
+                text.push(b as char);
+                i += 2;
+                chunk_start = i;
+            },
+            b'}' => return Err(LexError::UnmatchedBrace { start: offset + i, end: offset + i + 1 }),
+            b'{' => {
+                text.extend(unescape(&body[chunk_start..i], offset + chunk_start, EscapeMode::Str)?);
+
+                // A lezáró `}` keresése (a kifejezésen belüli blokkokat is figyelembe véve)
+                let mut depth = 0usize;
+                let mut close = None;
+                for (j, b) in bytes.iter().enumerate().skip(i + 1) {
+                    match b {
+                        b'{' => depth += 1,
+                        b'}' if depth == 0 => {
+                            close = Some(j);
+                            break;
+                        },
+                        b'}' => depth -= 1,
+                        _ => {}
+                    }
+                }
+                let Some(close) = close else {
+                    return Err(LexError::UnclosedInterpolation { start: offset + i, end: offset + i + 1 });
+                };
+
+                let source = &body[i + 1..close];
+                if source.trim().is_empty() {
+                    return Err(LexError::EmptyInterpolation { start: offset + i, end: offset + close + 1 });
+                }
+                if !text.is_empty() {
+                    segments.push(StrSegment::Text(std::mem::take(&mut text)));
+                }
+                segments.push(StrSegment::Expr(source.to_string(), offset + i + 1));
+                i = close + 1;
+                chunk_start = i;
+            },
+            _ => i += 1
+        }
+    }
+
+    text.extend(unescape(&body[chunk_start..], offset + chunk_start, EscapeMode::Str)?);
+    if !text.is_empty() || segments.is_empty() {
+        segments.push(StrSegment::Text(text));
+    }
+    Ok(segments)
+}
+
+fn lex_byte_string(lex: &mut logos::Lexer<Token>) -> Result<Vec<u8>, LexError> {
+    let s = lex.slice();
+    Ok(unescape(&s[2..s.len() - 1], 2, EscapeMode::Byte)?.into_iter().map(|c| c as u8).collect())
+}
+
+fn lex_unterminated<T>(_: &mut logos::Lexer<Token>) -> Result<T, LexError> {
+    Err(LexError::UnterminatedString)
+}
+
+// r"...", r#"..."#, br"...": a nyitó rész (r, #-ek, ") már illeszkedett, a lezárót kézzel keressük
+fn lex_raw(lex: &mut logos::Lexer<Token>) -> Result<String, LexError> {
+    let hashes = lex.slice().matches('#').count();
+    let terminator = format!("\"{}", "#".repeat(hashes));
+    match lex.remainder().find(&terminator) {
+        Some(end) => {
+            let content = lex.remainder()[..end].to_string();
+            lex.bump(end + terminator.len());
+            Ok(content)
+        },
+        None => {
+            lex.bump(lex.remainder().len());
+            Err(LexError::UnterminatedString)
+        }
+    }
+}
+
+fn lex_raw_byte_string(lex: &mut logos::Lexer<Token>) -> Result<Vec<u8>, LexError> {
+    let prefix_len = lex.slice().len();
+    let content = lex_raw(lex)?;
+    if let Some((i, ch)) = content.char_indices().find(|(_, c)| !c.is_ascii()) {
+        let start = prefix_len + i;
+        return Err(LexError::NonAsciiInByteString { ch, start, end: start + ch.len_utf8() });
+    }
+    Ok(content.into_bytes())
+}
+
+fn lex_char(lex: &mut logos::Lexer<Token>) -> Result<char, LexError> {
+    let s = lex.slice();
+    let chars = unescape(&s[1..s.len() - 1], 1, EscapeMode::Char)?;
+    match chars.as_slice() {
+        [c] => Ok(*c),
+        [] => Err(LexError::EmptyChar),
+        _ => Err(LexError::MultipleCharsInChar)
+    }
+}
 
 #[derive(Logos, Debug, Clone, PartialEq)]
+#[logos(error = LexError)]
 pub enum Token {
     // Kulcsszavak
     #[token("fv")]
     KeywordFn,
     
+    // Módosítható helyi változó
     #[token("var")]
     KeywordLet,
+
+    // Nem módosítható helyi kötés: `legyen x = f();`
+    #[token("legyen")]
+    KeywordLetImmutable,
     
     #[token("konst")]
     KeywordConst,
@@ -27,12 +423,32 @@ pub enum Token {
     
     #[token("szor")]
     KeywordFor,
+
+    #[token("in")]
+    KeywordIn,
     
     #[token("strukt")]
     KeywordStruct,
     
+    #[token("enum")]
+    KeywordEnum,
+    
     #[token("impl")]
     KeywordImpl,
+
+    #[token("vonas")]
+    KeywordTrait,
+
+    // `impl Kiirhato for Pont`
+    #[token("for")]
+    KeywordImplFor,
+
+    #[token("dyn")]
+    KeywordDyn,
+
+    // `mozgat |x| ...`: a lezárás érték szerint veszi át a környezetét
+    #[token("mozgat")]
+    KeywordMove,
     
     #[token("aszink")]
     KeywordAsync,
@@ -42,25 +458,52 @@ pub enum Token {
 
     #[token("eset")]
     KeywordMatch,
+
+    // `import std.memory;`, `use std.memory.Lap;`
+    #[token("import")]
+    KeywordImport,
+
+    #[token("use")]
+    KeywordUse,
+
+    #[token("as")]
+    KeywordAs,
+
+    // Más modulból is elérhető elem
+    #[token("pub")]
+    KeywordPub,
     
-    // Azonosítók
-    #[regex(r"[a-zA-Z_][a-zA-Z0-9_]*", |lex| lex.slice().to_string())]
+    // Azonosítók (UAX #31, NFC-re normalizálva, így a `vált` kétféle kódolása azonos)
+    #[regex(r"[\p{XID_Start}_]\p{XID_Continue}*", |lex| lex.slice().nfc().collect::<String>())]
     Ident(String),
     
-    // Literálok
-    #[regex(r"-?\d+", |lex| lex.slice().parse().ok())]
-    Int(i64),
+    // Literálok (az előjel nem része a literálnak, az unáris `-` operátor)
+    #[regex(r"[0-9][0-9_]*[a-zA-Z0-9_]*", lex_int)]
+    Int(IntLit),
+    
+    #[regex(r"[0-9][0-9_]*\.[0-9][0-9_]*([eE][+-]?[0-9_]+)?[a-zA-Z0-9_]*", lex_float, priority = 10)]
+    #[regex(r"[0-9][0-9_]*[eE][+-]?[0-9_]+[a-zA-Z0-9_]*", lex_float, priority = 10)]
+    #[regex(r"[0-9][0-9_]*f(32|64)", lex_float, priority = 10)]
+    Float(FloatLit),
+    
+    // Lezáratlan string esetén a rövidebb (idézőjel nélküli) minta illeszkedik
+    #[regex(r#""([^"\\]|\\(.|\n))*""#, lex_string)]
+    #[regex(r#""([^"\\]|\\(.|\n))*"#, lex_unterminated)]
+    StringLit(Vec<StrSegment>),
+
+    // A nyers stringben nincs sem escape, sem interpoláció
+    #[regex(r##"r#*""##, lex_raw)]
+    RawStringLit(String),
     
-    #[regex(r"-?\d+\.\d+", |lex| lex.slice().parse().ok())]
-    Float(f64),
+    #[regex(r#"b"([^"\\]|\\(.|\n))*""#, lex_byte_string)]
+    #[regex(r#"b"([^"\\]|\\(.|\n))*"#, lex_unterminated)]
+    #[regex(r##"br#*""##, lex_raw_byte_string)]
+    ByteStringLit(Vec<u8>),
     
-    #[regex(r#""([^"\\]|\\.)*""#, |lex| {
-        let s = lex.slice();
-        s[1..s.len()-1].to_string()
-    })]
-    StringLit(String),
+    #[regex(r"'([^'\\\n]|\\[^\n])*'", lex_char)]
+    CharLit(char),
     
-    #[regex(r"igaz|hamis", |lex| lex.slice().parse().ok())]
+    #[regex(r"igaz|hamis", |lex| lex.slice() == "igaz")]
     Bool(bool),
     
     // Operátorok
@@ -81,6 +524,19 @@ pub enum Token {
     
     #[token("=")]
     Equals,
+
+    // Összetett értékadás
+    #[token("+=")]
+    PlusEquals,
+
+    #[token("-=")]
+    MinusEquals,
+
+    #[token("*=")]
+    StarEquals,
+
+    #[token("/=")]
+    SlashEquals,
     
     #[token("==")]
     DoubleEquals,
@@ -103,11 +559,18 @@ pub enum Token {
     #[token("!")]
     Bang,
     
+    #[token("?")]
+    Question,
+    
     #[token("&&")]
     And,
     
     #[token("||")]
     Or,
+
+    // Lezárás paraméterlistája: `|x, y| x + y`
+    #[token("|")]
+    Pipe,
     
     // Szimbólumok
     #[token("(")]
@@ -133,6 +596,9 @@ pub enum Token {
     
     #[token(":")]
     Colon,
+
+    #[token("::")]
+    DoubleColon,
     
     #[token(";")]
     Semicolon,
@@ -145,53 +611,271 @@ pub enum Token {
     
     #[token("..")]
     DoubleDot,
+
+    #[token("..=")]
+    DoubleDotEquals,
+
+    #[token("=>")]
+    FatArrow,
     
     // Kommentek és whitespace
     #[regex(r"//[^\n]*", logos::skip)]
     #[regex(r"/\*([^*]|\*[^/])*\*/", logos::skip)]
     Comment,
     
-    #[regex(r"[ \t\n\f]+", logos::skip)]
+    #[regex(r"[ \t\r\n\f]+", logos::skip)]
     Whitespace,
     
-    // Hibakeresés
-    #[error]
-    Error
+    // Hibakeresés (a logos hibáit erre képezzük le)
+    Error(LexError)
 }
 
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        let text = match self {
+            Token::KeywordFn => "fv",
+            Token::KeywordLet => "var",
+            Token::KeywordLetImmutable => "legyen",
+            Token::KeywordConst => "konst",
+            Token::KeywordReturn => "vissza",
+            Token::KeywordIf => "ha",
+            Token::KeywordElse => "vagy",
+            Token::KeywordWhile => "mialatt",
+            Token::KeywordFor => "szor",
+            Token::KeywordIn => "in",
+            Token::KeywordStruct => "strukt",
+            Token::KeywordEnum => "enum",
+            Token::KeywordImpl => "impl",
+            Token::KeywordTrait => "vonas",
+            Token::KeywordImplFor => "for",
+            Token::KeywordDyn => "dyn",
+            Token::KeywordMove => "mozgat",
+            Token::KeywordAsync => "aszink",
+            Token::KeywordAwait => "akkor",
+            Token::KeywordMatch => "eset",
+            Token::KeywordImport => "import",
+            Token::KeywordUse => "use",
+            Token::KeywordAs => "as",
+            Token::KeywordPub => "pub",
+            Token::Ident(name) => return write!(f, "identifier `{}`", name),
+            Token::Int(lit) => return write!(f, "integer literal `{}`", lit.value),
+            Token::Float(lit) => return write!(f, "float literal `{}`", lit.value),
+            Token::StringLit(segments) => return match segments.as_slice() {
+                [StrSegment::Text(s)] => write!(f, "string literal {:?}", s),
+                _ => write!(f, "interpolated string literal")
+            },
+            Token::RawStringLit(s) => return write!(f, "string literal {:?}", s),
+            Token::ByteStringLit(bytes) => return write!(f, "byte string literal b{:?}", String::from_utf8_lossy(bytes)),
+            Token::CharLit(c) => return write!(f, "character literal {:?}", c),
+            Token::Bool(b) => if *b { "igaz" } else { "hamis" },
+            Token::Plus => "+",
+            Token::Minus => "-",
+            Token::Star => "*",
+            Token::Slash => "/",
+            Token::Percent => "%",
+            Token::Equals => "=",
+            Token::PlusEquals => "+=",
+            Token::MinusEquals => "-=",
+            Token::StarEquals => "*=",
+            Token::SlashEquals => "/=",
+            Token::DoubleEquals => "==",
+            Token::NotEquals => "!=",
+            Token::LessThan => "<",
+            Token::LessOrEqual => "<=",
+            Token::GreaterThan => ">",
+            Token::GreaterOrEqual => ">=",
+            Token::Bang => "!",
+            Token::Question => "?",
+            Token::And => "&&",
+            Token::Or => "||",
+            Token::Pipe => "|",
+            Token::LParen => "(",
+            Token::RParen => ")",
+            Token::LBrace => "{",
+            Token::RBrace => "}",
+            Token::LSquare => "[",
+            Token::RSquare => "]",
+            Token::Comma => ",",
+            Token::Colon => ":",
+            Token::DoubleColon => "::",
+            Token::Semicolon => ";",
+            Token::Arrow => "->",
+            Token::Dot => ".",
+            Token::DoubleDot => "..",
+            Token::DoubleDotEquals => "..=",
+            Token::FatArrow => "=>",
+            Token::Comment => return write!(f, "comment"),
+            Token::Whitespace => return write!(f, "whitespace"),
+            Token::Error(_) => return write!(f, "invalid token")
+        };
+        write!(f, "`{}`", text)
     }
 }
 
 pub struct Lexer<'a> {
-    inner: logos::Lexer<'a, Token>
+    inner: logos::Lexer<'a, Token>,
+    file: FileId,
+    // A bemenet kezdete a fájlon belül (interpolált kifejezéseknél nem nulla)
+    offset: usize
 }
 
 impl<'a> Lexer<'a> {
-    pub fn new(input: &'a str) -> Self {
+    pub fn new(input: &'a str, file: FileId) -> Self {
+        Self::with_offset(input, file, 0)
+    }
+
+    pub fn with_offset(input: &'a str, file: FileId, offset: usize) -> Self {
         Self {
-            inner: Token::lexer(input)
+            inner: Token::lexer(input),
+            file,
+            offset
         }
     }
     
-    pub fn span(&self) -> (usize, usize) {
+    pub fn span(&self) -> Span {
         let span = self.inner.span();
-        (span.start, span.end)
+        Span::new(self.file, self.offset + span.start, self.offset + span.end)
     }
     
     pub fn slice(&self) -> &'a str {
         self.inner.slice()
     }
+
+    // Üres tartomány a bemenet legvégén (a "váratlan fájlvége" hibákhoz)
+    pub fn eof_span(&self) -> Span {
+        let end = self.offset + self.inner.source().len();
+        Span::new(self.file, end, end)
+    }
 }
 
 impl<'a> Iterator for Lexer<'a> {
-    type Item = (Token, (usize, usize));
+    type Item = (Token, Span);
     
     fn next(&mut self) -> Option<Self::Item> {
-        let token = self.inner.slice();
-        let span = self.inner.span();
-        Some((token, (span.start, span.end)))
+        let token = self.inner.next()?.unwrap_or_else(Token::Error);
+        Some((token, self.span()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(src: &str) -> Vec<Token> {
+        Lexer::new(src, FileId(0)).map(|(token, _)| token).collect()
+    }
+
+    fn token(src: &str) -> Token {
+        let mut tokens = tokens(src);
+        assert_eq!(tokens.len(), 1, "{:?}", tokens);
+        tokens.remove(0)
+    }
+
+    fn int(value: u128, suffix: Option<IntTy>) -> Token {
+        Token::Int(IntLit { value, suffix })
+    }
+
+    fn float(value: f64, suffix: Option<FloatTy>) -> Token {
+        Token::Float(FloatLit { value, suffix })
+    }
+
+    #[test]
+    fn number_suffixes() {
+        assert_eq!(token("1_000"), int(1000, None));
+        assert_eq!(token("10u8"), int(10, Some(IntTy::U8)));
+        assert_eq!(token("0xFFi64"), int(255, Some(IntTy::I64)));
+        assert_eq!(token("0o17_usize"), int(15, Some(IntTy::Usize)));
+        assert_eq!(token("0b1010u128"), int(10, Some(IntTy::U128)));
+        assert_eq!(token("2.5E3f32"), float(2500.0, Some(FloatTy::F32)));
+        assert_eq!(token("1e-9"), float(1e-9, None));
+        assert_eq!(token("10f64"), float(10.0, Some(FloatTy::F64)));
+        assert_eq!(token("10u7"), Token::Error(LexError::InvalidSuffix("u7".to_string())));
+        assert_eq!(token("1.5f16"), Token::Error(LexError::InvalidSuffix("f16".to_string())));
+    }
+
+    #[test]
+    fn out_of_range_literals() {
+        assert_eq!(token("340282366920938463463374607431768211455"), int(u128::MAX, None));
+        assert_eq!(token("340282366920938463463374607431768211456"), Token::Error(LexError::IntegerTooLarge));
+        // A típus tartományát a típusellenőrző vizsgálja: a lexer a `256u8`-at még elfogadja
+        assert_eq!(token("256u8"), int(256, Some(IntTy::U8)));
+        assert!(256 > IntTy::U8.max_value());
+    }
+
+    #[test]
+    fn negative_literal_is_minus_and_magnitude() {
+        assert_eq!(tokens("-128i8"), vec![Token::Minus, int(128, Some(IntTy::I8))]);
+        // A `128` csak tagadva fér el `i8`-ban
+        assert!(128 > IntTy::I8.max_value());
+        assert_eq!(IntTy::I8.min_magnitude(), 128);
+        assert_eq!(IntTy::I128.min_magnitude(), 1 << 127);
+        assert_eq!(IntTy::U64.min_magnitude(), 0);
+        assert_eq!(IntTy::U128.max_value(), u128::MAX);
+    }
+
+    #[test]
+    fn malformed_number_literals() {
+        // Az elválasztók száma és helye (a számjegyek után) tetszőleges
+        assert_eq!(token("1__"), int(1, None));
+        assert_eq!(token("0x"), Token::Error(LexError::MissingDigits));
+        assert_eq!(token("0x_"), Token::Error(LexError::MissingDigits));
+        assert_eq!(token("0b102"), Token::Error(LexError::InvalidDigit('2', 2)));
+        assert_eq!(token("0o8"), Token::Error(LexError::InvalidDigit('8', 8)));
+        // A `.` után számjegynek kell állnia, különben mezőhozzáférés
+        assert_eq!(tokens("1.e5"), vec![int(1, None), Token::Dot, Token::Ident("e5".to_string())]);
+    }
+
+    fn text(value: &str) -> Token {
+        Token::StringLit(vec![StrSegment::Text(value.to_string())])
+    }
+
+    #[test]
+    fn string_escapes() {
+        assert_eq!(token(r#""a\n\t\\\"\0""#), text("a\n\t\\\"\0"));
+        assert_eq!(token(r#""\x41\u{48}\u{1F600}""#), text("AH\u{1F600}"));
+        assert_eq!(token("\"egy \\\n    ketto\""), text("egy ketto"));
+        assert_eq!(token(r"'\''"), Token::CharLit('\''));
+        assert_eq!(token(r#""\q""#), Token::Error(LexError::InvalidEscape { escape: "\\q".to_string(), start: 1, end: 3 }));
+        assert!(matches!(token(r#""\x80""#), Token::Error(LexError::InvalidUnicodeEscape { start: 1, end: 5, .. })));
+        assert!(matches!(token(r#""\u{D800}""#), Token::Error(LexError::InvalidUnicodeEscape { .. })));
+        assert!(matches!(token(r#""\u{1234567}""#), Token::Error(LexError::InvalidUnicodeEscape { .. })));
+        assert_eq!(token("\"abc"), Token::Error(LexError::UnterminatedString));
+        assert_eq!(token("''"), Token::Error(LexError::EmptyChar));
+        assert_eq!(token("'ab'"), Token::Error(LexError::MultipleCharsInChar));
+    }
+
+    #[test]
+    fn raw_strings() {
+        assert_eq!(token(r#"r"a\nb""#), Token::RawStringLit("a\\nb".to_string()));
+        assert_eq!(token(r##"r#"mondta: "szia" {x}"#"##), Token::RawStringLit("mondta: \"szia\" {x}".to_string()));
+        assert_eq!(token(r#"r#"nincs vege""#), Token::Error(LexError::UnterminatedString));
+    }
+
+    #[test]
+    fn byte_strings() {
+        assert_eq!(token(r#"b"A\xFF\n""#), Token::ByteStringLit(vec![b'A', 0xFF, b'\n']));
+        assert_eq!(token(r##"br#"\x"#"##), Token::ByteStringLit(b"\\x".to_vec()));
+        assert_eq!(token("b\"\u{e9}\""), Token::Error(LexError::NonAsciiInByteString { ch: '\u{e9}', start: 2, end: 4 }));
+        assert!(matches!(token(r#"b"\u{41}""#), Token::Error(LexError::InvalidUnicodeEscape { .. })));
+    }
+
+    #[test]
+    fn interpolation_segments() {
+        let expr = |source: &str, start| StrSegment::Expr(source.to_string(), start);
+        let text = |value: &str| StrSegment::Text(value.to_string());
+        assert_eq!(token(r#""x = {x + 1}!""#), Token::StringLit(vec![text("x = "), expr("x + 1", 6), text("!")]));
+        assert_eq!(token(r#""{a}{b}""#), Token::StringLit(vec![expr("a", 2), expr("b", 5)]));
+        // A kifejezésen belüli blokk kapcsos zárójelei nem zárják le az interpolációt
+        assert_eq!(token(r#""{eset x { _ => 1 }}""#), Token::StringLit(vec![expr("eset x { _ => 1 }", 2)]));
+        assert_eq!(token(r#""{{}} \u{7B}""#), Token::StringLit(vec![text("{} {")]));
+        assert_eq!(token("\"\""), Token::StringLit(vec![text("")]));
+    }
+
+    #[test]
+    fn malformed_interpolation() {
+        assert_eq!(token(r#""a { } b""#), Token::Error(LexError::EmptyInterpolation { start: 3, end: 6 }));
+        assert_eq!(token(r#""a {x""#), Token::Error(LexError::UnclosedInterpolation { start: 3, end: 4 }));
+        assert_eq!(token(r#""a } b""#), Token::Error(LexError::UnmatchedBrace { start: 3, end: 4 }));
+        assert_eq!(token(r#""{x} \q""#), Token::Error(LexError::InvalidEscape { escape: "\\q".to_string(), start: 5, end: 7 }));
     }
 }
@@ -0,0 +1,69 @@
+use crate::diagnostic::{ codes, Diagnostic };
+use crate::lexer::{ Lexer, StrSegment, Token };
+use crate::source_map::Span;
+use std::collections::{ HashMap, HashSet };
+use unicode_security::{ skeleton, MixedScript };
+
+// Azonosítók ellenőrzése (UTS #39): vegyes írásrendszer és összetéveszthető nevek.
+// Csak figyelmeztetéseket ad, a fordítást nem állítja meg.
+pub fn check_identifiers(lexer: Lexer) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    // skeleton -> (első előfordulás neve, helye)
+    let mut seen: HashMap<String, (String, Span)> = HashMap::new();
+    // Minden nevet csak az első előfordulásánál jelzünk
+    let mut checked: HashSet<String> = HashSet::new();
+
+    let mut idents = Vec::new();
+    collect_identifiers(lexer, &mut idents);
+
+    for (name, span) in idents {
+        if !checked.insert(name.clone()) {
+            continue;
+        }
+
+        if !name.is_ascii() && !name.as_str().is_single_script() {
+            diagnostics.push(
+                Diagnostic::warning(format!("identifier `{}` mixes characters from different scripts", name))
+                    .with_code(codes::MIXED_SCRIPT_IDENT)
+                    .with_primary(span, "mixed-script identifier")
+                    .with_help("use characters from a single script (e.g. only Latin letters)")
+            );
+        }
+
+        let key: String = skeleton(&name).collect();
+        match seen.get(&key) {
+            // Két tisztán ASCII név összetévesztését (pl. `rn` és `m`) nem jelezzük
+            Some((other, other_span)) if *other != name && !(name.is_ascii() && other.is_ascii()) => {
+                diagnostics.push(
+                    Diagnostic::warning(format!("identifier `{}` is confusable with `{}`", name, other))
+                        .with_code(codes::CONFUSABLE_IDENT)
+                        .with_primary(span, "this identifier")
+                        .with_secondary(*other_span, "looks like this one")
+                );
+            }
+            Some(_) => {}
+            None => {
+                seen.insert(key, (name, span));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+// Az interpolált stringek kifejezéseiben szereplő neveket is összegyűjtjük
+fn collect_identifiers(lexer: Lexer, idents: &mut Vec<(String, Span)>) {
+    for (token, span) in lexer {
+        match token {
+            Token::Ident(name) => idents.push((name, span)),
+            Token::StringLit(segments) => {
+                for segment in segments {
+                    if let StrSegment::Expr(source, offset) = segment {
+                        collect_identifiers(Lexer::with_offset(&source, span.file, span.start + offset), idents);
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+}
@@ -1,10 +1,36 @@
+mod ast;
+mod captures;
+mod consteval;
+mod diagnostic;
+mod exhaustiveness;
 mod lexer;
+mod lint;
+mod modules;
 mod parser;
-mod typecheck;
+mod prelude;
+mod resolver;
+mod source_map;
+mod typechecker;
 mod codegen;
 
+use std::collections::HashSet;
 use std::fs;
+use std::io::IsTerminal;
+use std::path::Path;
 use inkwell::context::Context;
+use diagnostic::{Diagnostic, Renderer};
+use source_map::SourceMap;
+
+fn report(source_map: &SourceMap, diag: &Diagnostic) {
+    let renderer = Renderer::new(source_map, std::io::stderr().is_terminal());
+    eprintln!("{}", renderer.render(diag));
+}
+
+fn abort(error_count: usize) -> ! {
+    let plural = if error_count == 1 { "" } else { "s" };
+    eprintln!("error: aborting due to {} previous error{}", error_count, plural);
+    std::process::exit(1);
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = std::env::args().collect();
@@ -12,26 +38,58 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         eprintln!("Usage: {} <input.dlang>", args[0]);
         std::process::exit(1);
     }
-    
+
     let input_path = &args[1];
     let source_code = fs::read_to_string(input_path)?;
-    
-    // 1. Tokenizálás
-    let lexer = lexer::Lexer::new(&source_code);
-    
-    // 2. Parselés
-    let mut parser = parser::Parser::new(lexer);
-    let program = parser.parse_program();
-    
-    // 3. Típusellenőrzés
-    let mut typechecker = typecheck::TypeChecker::new();
-    typechecker.check_program(&program)?;
-    
+
+    let mut source_map = SourceMap::new();
+    let file_id = source_map.add_file(input_path.clone(), source_code);
+
+    // 1-2. Tokenizálás és parselés, a gyökérmodulból elérhető összes modulra
+    let (modules, mut diagnostics) = modules::load(&mut source_map, file_id, Path::new(input_path));
+    for module in &modules {
+        diagnostics.extend(lint::check_identifiers(lexer::Lexer::new(&source_map.get(module.file).src, module.file)));
+    }
+
+    // Névfeloldás: a modulok egyetlen programmá állnak össze; a beépített traitek a program elejére kerülnek
+    let (mut program, resolve_diagnostics) = resolver::resolve(modules);
+    diagnostics.extend(resolve_diagnostics);
+
+    let prelude_id = source_map.add_file("<prelude>".to_string(), prelude::SOURCE.to_string());
+    let (mut prelude, prelude_diagnostics) = parser::Parser::new(lexer::Lexer::new(&source_map.get(prelude_id).src, prelude_id)).parse_program();
+    diagnostics.extend(prelude_diagnostics);
+    // A program saját traitjei és típusai elfedik az azonos nevű beépítetteket
+    let defined: HashSet<String> = program.traits.iter().map(|def| def.name.clone())
+        .chain(program.structs.iter().map(|def| def.name.clone()))
+        .chain(program.enums.iter().map(|def| def.name.clone()))
+        .collect();
+    prelude.traits.retain(|def| !defined.contains(&def.name));
+    prelude.structs.retain(|def| !defined.contains(&def.name));
+    prelude.enums.retain(|def| !defined.contains(&def.name));
+    program.traits.splice(0..0, prelude.traits);
+    program.structs.splice(0..0, prelude.structs);
+    program.enums.splice(0..0, prelude.enums);
+
+    // 3. Típusellenőrzés (szintaktikai hibák esetén is, a részleges AST-n)
+    let mut typechecker = typechecker::TypeChecker::new().with_prelude(prelude_id);
+    diagnostics.extend(typechecker.check_program(&program));
+
+    for diag in &diagnostics {
+        report(&source_map, diag);
+    }
+    let error_count = diagnostics.iter().filter(|d| d.is_error()).count();
+    if error_count > 0 {
+        abort(error_count);
+    }
+
     // 4. Kódgenerálás
     let context = Context::create();
-    let mut codegen = codegen::Codegen::new(&context);
-    codegen.compile(&program)?;
-    
+    let mut codegen = codegen::CodeGen::new(&context, typechecker.take_expr_types(), typechecker.take_instantiations(), typechecker.take_coercions(), typechecker.take_captures(), typechecker.take_const_values());
+    if let Err(diag) = codegen.compile(&program) {
+        report(&source_map, &diag);
+        abort(1);
+    }
+
     println!("Successfully compiled to output.ll!");
     Ok(())
-}
\ No newline at end of file
+}
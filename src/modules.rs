@@ -0,0 +1,104 @@
+use crate::ast::{ Import, Program };
+use crate::diagnostic::{ codes, Diagnostic };
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::source_map::{ FileId, SourceMap };
+use std::fs;
+use std::path::{ Path, PathBuf };
+
+// A modulfa: minden fájl egy modul, a könyvtárak csomagok. Az `a.b` modul a keresési
+// könyvtárak valamelyikében `a/b.dlang` vagy `a/b/mod.dlang`.
+pub const SOURCE_EXTENSION: &str = "dlang";
+pub const PACKAGE_FILE: &str = "mod.dlang";
+// További keresési könyvtárak (például a `std` csomag helye), `PATH`-szerű listaként
+pub const SEARCH_PATH_VAR: &str = "DLANG_PATH";
+
+pub struct Module {
+    // A gyökérmodul (a parancssorban megadott fájl) útvonala üres
+    pub path: Vec<String>,
+    pub file: FileId,
+    pub program: Program,
+}
+
+impl Module {
+    pub fn display_path(&self) -> String {
+        self.path.join(".")
+    }
+}
+
+// Az importált modul útvonala: `use` esetén az utolsó tag a modul eleme
+pub fn module_path(import: &Import) -> &[String] {
+    if import.is_item {
+        &import.path[..import.path.len().saturating_sub(1)]
+    } else {
+        &import.path
+    }
+}
+
+// A gyökérmodulból kiindulva beolvassa és feldolgozza az összes (közvetve) importált
+// modult. Az első elem a gyökérmodul; minden modul egyszer szerepel, így a körkörös
+// importok sem okoznak gondot.
+pub fn load(source_map: &mut SourceMap, root_file: FileId, root_path: &Path) -> (Vec<Module>, Vec<Diagnostic>) {
+    let mut search_dirs = vec![root_path.parent().map(Path::to_path_buf).unwrap_or_default()];
+    if let Some(paths) = std::env::var_os(SEARCH_PATH_VAR) {
+        search_dirs.extend(std::env::split_paths(&paths));
+    }
+
+    let mut diagnostics = Vec::new();
+    let mut modules = vec![parse_module(source_map, Vec::new(), root_file, &mut diagnostics)];
+    // A nem található modulokat csak az első importnál jelentjük
+    let mut missing: Vec<Vec<String>> = Vec::new();
+    let mut next = 0;
+    while next < modules.len() {
+        let imports = modules[next].program.imports.clone();
+        next += 1;
+        for import in &imports {
+            let path = module_path(import);
+            if path.is_empty() {
+                diagnostics.push(Diagnostic::error(format!("unresolved import `{}`", import.path.join(".")))
+                    .with_code(codes::UNRESOLVED_IMPORT)
+                    .with_primary(import.span, "expected a path of the form `module.item`")
+                    .with_help(format!("to import a module, use `import {};`", import.path.join("."))));
+                continue;
+            }
+            if modules.iter().any(|module| module.path == path) || missing.iter().any(|missing| missing == path) {
+                continue;
+            }
+            match find_module(&search_dirs, path) {
+                Some((file_name, src)) => {
+                    let file = source_map.add_file(file_name.display().to_string(), src);
+                    modules.push(parse_module(source_map, path.to_vec(), file, &mut diagnostics));
+                },
+                None => {
+                    let relative: PathBuf = path.iter().collect();
+                    diagnostics.push(Diagnostic::error(format!("unresolved import `{}`", path.join(".")))
+                        .with_code(codes::UNRESOLVED_IMPORT)
+                        .with_primary(import.span, "module not found")
+                        .with_note(format!(
+                            "searched for `{}` and `{}`",
+                            relative.with_extension(SOURCE_EXTENSION).display(),
+                            relative.join(PACKAGE_FILE).display()
+                        ))
+                        .with_help(format!("library directories can be added with the `{}` environment variable", SEARCH_PATH_VAR)));
+                    missing.push(path.to_vec());
+                }
+            }
+        }
+    }
+
+    (modules, diagnostics)
+}
+
+fn parse_module(source_map: &SourceMap, path: Vec<String>, file: FileId, diagnostics: &mut Vec<Diagnostic>) -> Module {
+    let mut parser = Parser::new(Lexer::new(&source_map.get(file).src, file));
+    let (program, parse_diagnostics) = parser.parse_program();
+    diagnostics.extend(parse_diagnostics);
+    Module { path, file, program }
+}
+
+fn find_module(search_dirs: &[PathBuf], path: &[String]) -> Option<(PathBuf, String)> {
+    let relative: PathBuf = path.iter().collect();
+    search_dirs.iter()
+        .flat_map(|dir| [dir.join(&relative).with_extension(SOURCE_EXTENSION), dir.join(&relative).join(PACKAGE_FILE)])
+        .find_map(|file_name| fs::read_to_string(&file_name).ok().map(|src| (file_name, src)))
+}
@@ -1,143 +1,443 @@
-use crate::lexer::{ Lexer, Token };
-use std::iter::Peekable;
-
-#[derive(Debug)]
-pub enum Expr {
-    Literal(Literal),
-    Ident(String),
-    BinaryOp {
-        left: Box<Expr>,
-        op: BinaryOp,
-        right: Box<Expr>,
-    },
-    Call {
-        callee: String,
-        args: Vec<Expr>,
-    },
-    If {
-        condition: Box<Expr>,
-        then_branch: Vec<Stmt>,
-        else_branch: Option<Vec<Stmt>>,
-    },
-}
-
-#[derive(Debug)]
-pub enum Literal {
-    Int(i64),
-    Float(f64),
-    String(String),
-    Bool(bool),
-}
-
-#[derive(Debug)]
-pub enum BinaryOp {
-    Add,
-    Sub,
-    Mul,
-    Div,
-    Eq,
-    Neq,
-    Lt,
-    Gt,
-    Le,
-    Ge,
-    And,
-    Or,
-}
-
-#[derive(Debug)]
-pub enum Stmt {
-    Expr(Expr),
-    Let {
-        name: String,
-        type_annot: String,
-        value: Expr,
-    },
-    Return(Option<Expr>),
-    While {
-        condition: Expr,
-        body: Vec<Stmt>,
-    },
-}
-
-#[derive(Debug)]
-pub struct Function {
-    pub name: String,
-    pub params: Vec<(String, Option<String>)>,
-    pub return_type: String,
-    pub body: Vec<Stmt>,
-}
-
-#[derive(Debug)]
-pub struct Program {
-    pub functions: Vec<Function>,
-}
+use crate::ast::{
+    ARRAY_TYPE, BinaryOp, Const, Enum, Expr, ExprKind, Field, FieldInit, FieldPattern, FloatTy, FN_TYPE, Function, GenericParam, Impl, Import, IntTy,
+    InterpPart, Literal,
+    MatchArm, NEVER_TYPE, Param, Pattern, PatternKind, Program, Stmt, StmtKind, Struct, Trait, TraitMethod, TypeAnnot, UNIT_TYPE, UnaryOp, Variant,
+};
+use crate::diagnostic::{ codes, Diagnostic };
+use crate::lexer::{ Lexer, StrSegment, Token };
+use crate::source_map::Span;
 
 pub struct Parser<'a> {
-    lexer: Peekable<Lexer<'a>>,
-    current_token: Option<Token, (usize, usize)>,
+    lexer: Lexer<'a>,
+    current_token: Option<(Token, Span)>,
+    prev_span: Span,
+    eof_span: Span,
+    diagnostics: Vec<Diagnostic>,
+    // Feltételben (`ha x { ... }`) a `{` a blokkot nyitja, nem struct literált
+    struct_literals: bool,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(lexer: Lexer<'a>) -> Self {
-        let mut lexer = lexer.peekable();
-        let current_token = lexer.next();
-        Self { lexer, current_token }
+        let eof_span = lexer.eof_span();
+        let prev_span = Span::new(eof_span.file, 0, 0);
+        let mut parser = Self {
+            lexer,
+            current_token: None,
+            prev_span,
+            eof_span,
+            diagnostics: Vec::new(),
+            struct_literals: true,
+        };
+        parser.current_token = parser.next_token();
+        parser
+    }
+
+    // A következő érvényes token; a lexikai hibákat jelentjük, és vagy átugorjuk,
+    // vagy helyettesítő tokennel folytatjuk
+    fn next_token(&mut self) -> Option<(Token, Span)> {
+        loop {
+            match self.lexer.next()? {
+                (Token::Error(err), span) => {
+                    // Escape hibáknál csak a hibás escape-et húzzuk alá
+                    let label_span = match err.relative_span() {
+                        Some((start, end)) => Span::new(span.file, span.start + start, span.start + end),
+                        None => span,
+                    };
+                    let mut diag = Diagnostic::error(err.to_string())
+                        .with_code(codes::INVALID_TOKEN)
+                        .with_primary(label_span, "");
+                    if let Some(help) = err.help() {
+                        diag = diag.with_help(help);
+                    }
+                    self.diagnostics.push(diag);
+                    if let Some(token) = err.recovery_token(self.lexer.slice()) {
+                        return Some((token, span));
+                    }
+                }
+                token => return Some(token),
+            }
+        }
     }
 
-    fn peek_token(&mut self) -> Option<&Token> {
-        self.lexer.peek().map(|(token, _)| token)
+    fn peek_token(&self) -> Option<&Token> {
+        self.current_token.as_ref().map(|(token, _)| token)
+    }
+
+    fn current_span(&self) -> Span {
+        self.current_token.as_ref().map(|(_, span)| *span).unwrap_or(self.eof_span)
     }
 
     fn consume_token(&mut self) {
-        self.current_token = self.lexer.next();
+        if let Some((_, span)) = &self.current_token {
+            self.prev_span = *span;
+        }
+        self.current_token = self.next_token();
+    }
+
+    // A `start`-tól az utoljára elfogyasztott tokenig tartó tartomány
+    fn span_from(&self, start: Span) -> Span {
+        start.to(self.prev_span)
     }
 
-    fn expect_token(&mut self, expected: Token) -> Result<(), String> {
+    // "expected X, found Y" hiba az aktuális tokenre
+    fn unexpected(&self, expected: &str) -> Diagnostic {
         match &self.current_token {
-            Some((token, _)) if *token == expected => {
+            Some((token, span)) => Diagnostic::error(format!("expected {}, found {}", expected, token))
+                .with_code(codes::UNEXPECTED_TOKEN)
+                .with_primary(*span, format!("expected {}", expected)),
+            None => Diagnostic::error(format!("expected {}, but reached end of input", expected))
+                .with_code(codes::UNEXPECTED_EOF)
+                .with_primary(self.eof_span, "unexpected end of input"),
+        }
+    }
+
+    fn expect_token(&mut self, expected: Token) -> Result<Span, Diagnostic> {
+        match &self.current_token {
+            Some((token, span)) if *token == expected => {
+                let span = *span;
                 self.consume_token();
-                Ok(())
+                Ok(span)
             }
-            Some((_, span)) =>
-                Err(
-                    format!(
-                        "Expected{:?}, found {:?} at position {:?}",
-                        expected,
-                        self.current_token,
-                        span
-                    )
-                ),
-            None => Err(format!("Expected {:?}, but reached end of input", expected)),
+            _ => Err(self.unexpected(&expected.to_string())),
         }
     }
 
-    pub fn parse_program(&mut self) -> Result<Program, String> {
+    fn expect_ident(&mut self, what: &str) -> Result<(String, Span), Diagnostic> {
+        match self.current_token.clone() {
+            Some((Token::Ident(name), span)) => {
+                self.consume_token();
+                Ok((name, span))
+            }
+            _ => Err(self.unexpected(what)),
+        }
+    }
+
+    // Legfelső szintű deklaráció kezdete
+    fn at_item_start(&self) -> bool {
+        matches!(
+            self.peek_token(),
+            Some(Token::KeywordFn) | Some(Token::KeywordAsync) | Some(Token::KeywordStruct) | Some(Token::KeywordEnum) | Some(Token::KeywordImpl)
+                | Some(Token::KeywordTrait) | Some(Token::KeywordPub) | Some(Token::KeywordImport) | Some(Token::KeywordUse)
+        )
+    }
+
+    // Panic-mode helyreállítás: átugorjuk a hibás utasítást a következő `;`-ig,
+    // a blokkot lezáró `}`-ig vagy a következő deklarációig
+    fn synchronize(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.peek_token() {
+            if self.at_item_start() {
+                return;
+            }
+            match token {
+                Token::Semicolon if depth == 0 => {
+                    self.consume_token();
+                    return;
+                }
+                Token::RBrace if depth == 0 => return,
+                Token::LBrace => depth += 1,
+                Token::RBrace => depth -= 1,
+                _ => {}
+            }
+            self.consume_token();
+        }
+    }
+
+    // Legfelső szintű helyreállítás: a következő deklarációig ugrunk. A `konst` blokkban is
+    // állhat, ezért csak itt számít deklaráció kezdetének.
+    fn synchronize_item(&mut self) {
+        while self.current_token.is_some() && !self.at_item_start() && !matches!(self.peek_token(), Some(Token::KeywordConst)) {
+            self.consume_token();
+        }
+    }
+
+    // A teljes fájlt feldolgozza; a hibák után is folytatja, és a részleges AST-t
+    // az összes összegyűjtött hibával együtt adja vissza
+    pub fn parse_program(&mut self) -> (Program, Vec<Diagnostic>) {
+        let mut imports = Vec::new();
+        let mut structs = Vec::new();
+        let mut enums = Vec::new();
+        let mut traits = Vec::new();
+        let mut impls = Vec::new();
+        let mut consts = Vec::new();
         let mut functions = Vec::new();
 
         while self.current_token.is_some() {
-            if let Some((Token::KeywordFn, _)) = &self.current_token() {
-                functions.push(self.parse_function()?);
+            let is_pub = matches!(self.peek_token(), Some(Token::KeywordPub));
+            if is_pub {
+                self.consume_token();
+            }
+            let result = match self.peek_token() {
+                Some(Token::KeywordFn | Token::KeywordAsync) => self.parse_function().map(|function| functions.push(Function { is_pub, ..function })),
+                Some(Token::KeywordStruct) => self.parse_struct().map(|def| structs.push(Struct { is_pub, ..def })),
+                Some(Token::KeywordEnum) => self.parse_enum().map(|def| enums.push(Enum { is_pub, ..def })),
+                Some(Token::KeywordTrait) => self.parse_trait().map(|def| traits.push(Trait { is_pub, ..def })),
+                Some(Token::KeywordConst) => self.parse_const().map(|def| consts.push(Const { is_pub, ..def })),
+                Some(Token::KeywordImpl) if !is_pub => self.parse_impl().map(|block| impls.push(block)),
+                Some(Token::KeywordImport | Token::KeywordUse) if !is_pub => self.parse_import().map(|import| imports.push(import)),
+                _ if is_pub => Err(self.unexpected("function, struct, enum, trait or constant declaration after `pub`")),
+                _ => Err(self.unexpected("function, struct, enum, trait, impl, constant or import declaration")),
+            };
+            if let Err(diag) = result {
+                self.diagnostics.push(diag);
+                self.synchronize_item();
+            }
+        }
+
+        (Program { imports, structs, enums, traits, impls, consts, functions }, std::mem::take(&mut self.diagnostics))
+    }
+
+    // `import std.memory;`, `import std.memory as mem;`, `use std.memory.Lap;`
+    fn parse_import(&mut self) -> Result<Import, Diagnostic> {
+        let start = self.current_span();
+        let is_item = matches!(self.peek_token(), Some(Token::KeywordUse));
+        self.consume_token();
+
+        let mut path = vec![self.expect_ident("module name")?.0];
+        while let Some(Token::Dot) = self.peek_token() {
+            self.consume_token();
+            path.push(self.expect_ident(if is_item { "module or item name" } else { "module name" })?.0);
+        }
+        let alias = if let Some(Token::KeywordAs) = self.peek_token() {
+            self.consume_token();
+            Some(self.expect_ident("alias")?.0)
+        } else {
+            None
+        };
+        self.expect_token(Token::Semicolon)?;
+
+        Ok(Import {
+            path,
+            alias,
+            is_item,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_struct(&mut self) -> Result<Struct, Diagnostic> {
+        let start = self.expect_token(Token::KeywordStruct)?;
+        let (name, _) = self.expect_ident("struct name")?;
+        let generics = self.parse_generics()?;
+        self.expect_token(Token::LBrace)?;
+
+        let mut fields = Vec::new();
+        while let Some((Token::Ident(field_name), field_start)) = self.current_token.clone() {
+            self.consume_token();
+            self.expect_token(Token::Colon)?;
+            let type_annot = self.parse_type_annotation()?;
+
+            fields.push(Field {
+                name: field_name,
+                type_annot,
+                span: self.span_from(field_start),
+            });
+
+            if let Some(Token::Comma) = self.peek_token() {
+                self.consume_token();
             } else {
-                return Err(
-                    format!("Expected function declaration, found {:?}", self.current_token)
-                );
+                break;
             }
         }
 
-        Ok(Program { functions })
+        self.expect_token(Token::RBrace)?;
+        Ok(Struct {
+            name,
+            is_pub: false,
+            generics,
+            fields,
+            span: self.span_from(start),
+        })
     }
 
-    fn parse_function(&mut self) -> Result<Function, String> {
-        self.expect_token(Token::KeywordFn)?;
+    // `enum Alak { Kor(f64), Teglalap(f64, f64), Pont }`
+    fn parse_enum(&mut self) -> Result<Enum, Diagnostic> {
+        let start = self.expect_token(Token::KeywordEnum)?;
+        let (name, _) = self.expect_ident("enum name")?;
+        let generics = self.parse_generics()?;
+        self.expect_token(Token::LBrace)?;
 
-        let name = match self.current_token.clone() {
-            Some((Token::Ident(name), _)) => name,
-            _ => {
-                return Err("Expected function name".to_string());
+        let mut variants = Vec::new();
+        while let Some((Token::Ident(variant_name), variant_start)) = self.current_token.clone() {
+            self.consume_token();
+            let mut fields = Vec::new();
+            if let Some(Token::LParen) = self.peek_token() {
+                self.consume_token();
+                while !matches!(self.peek_token(), Some(Token::RParen)) {
+                    fields.push(self.parse_type_annotation()?);
+                    if let Some(Token::Comma) = self.peek_token() {
+                        self.consume_token();
+                    } else {
+                        break;
+                    }
+                }
+                self.expect_token(Token::RParen)?;
+            }
+
+            variants.push(Variant {
+                name: variant_name,
+                fields,
+                span: self.span_from(variant_start),
+            });
+
+            if let Some(Token::Comma) = self.peek_token() {
+                self.consume_token();
+            } else {
+                break;
+            }
+        }
+
+        self.expect_token(Token::RBrace)?;
+        Ok(Enum {
+            name,
+            is_pub: false,
+            generics,
+            variants,
+            span: self.span_from(start),
+        })
+    }
+
+    // `vonas Kiirhato { fv kiir(self) -> str; fv sor(self) -> str { ... } }`
+    fn parse_trait(&mut self) -> Result<Trait, Diagnostic> {
+        let start = self.expect_token(Token::KeywordTrait)?;
+        let (name, name_span) = self.expect_ident("trait name")?;
+        self.expect_token(Token::LBrace)?;
+
+        let mut methods = Vec::new();
+        while !matches!(self.peek_token(), Some(Token::RBrace) | None) {
+            // A metódus többi részét feldolgozzuk, csak az `aszink` szót jelentjük
+            if let Some(Token::KeywordAsync) = self.peek_token() {
+                let diag = Diagnostic::error("trait methods cannot be declared `aszink`")
+                    .with_code(codes::UNEXPECTED_TOKEN)
+                    .with_primary(self.current_span(), "`aszink` is not allowed here")
+                    .with_help("declare it as returning `Jovo<T>`; implementations may still be `aszink`");
+                self.diagnostics.push(diag);
+                self.consume_token();
+                continue;
+            }
+            if !matches!(self.peek_token(), Some(Token::KeywordFn)) {
+                if self.at_item_start() {
+                    break;
+                }
+                let diag = self.unexpected("method declaration");
+                self.diagnostics.push(diag);
+                self.synchronize();
+                continue;
+            }
+
+            match self.parse_trait_method(name_span) {
+                Ok(method) => methods.push(method),
+                Err(diag) => {
+                    self.diagnostics.push(diag);
+                    self.synchronize();
+                }
+            }
+        }
+
+        if let Err(diag) = self.expect_token(Token::RBrace) {
+            self.diagnostics.push(diag);
+        }
+        Ok(Trait {
+            name,
+            is_pub: false,
+            methods,
+            span: self.span_from(start),
+        })
+    }
+
+    // A `;`-vel záruló metódusnak nincs alapértelmezett törzse
+    fn parse_trait_method(&mut self, trait_span: Span) -> Result<TraitMethod, Diagnostic> {
+        let mut function = self.parse_signature()?;
+        let has_default = if let Some(Token::Semicolon) = self.peek_token() {
+            self.consume_token();
+            false
+        } else {
+            self.parse_body(&mut function)?;
+            true
+        };
+        // A típus nélküli `self` az implementáló típus: `Self`
+        if let Some(receiver) = function.params.first_mut() {
+            if receiver.name == "self" && receiver.type_annot.is_none() {
+                receiver.type_annot = Some(TypeAnnot::named("Self", trait_span));
             }
+        }
+        Ok(TraitMethod { function, has_default })
+    }
+
+    fn parse_impl(&mut self) -> Result<Impl, Diagnostic> {
+        let start = self.expect_token(Token::KeywordImpl)?;
+        let generics = self.parse_generics()?;
+        let mut self_type = self.parse_type_annotation()?;
+        // `impl Trait for Tipus`: az első név a trait
+        let trait_name = if let Some(Token::KeywordImplFor) = self.peek_token() {
+            self.consume_token();
+            let trait_annot = std::mem::replace(&mut self_type, self.parse_type_annotation()?);
+            Some(trait_annot.name)
+        } else {
+            None
         };
-        self.consume_token();
+        self.expect_token(Token::LBrace)?;
+
+        let mut methods = Vec::new();
+        while !matches!(self.peek_token(), Some(Token::RBrace) | None) {
+            if !matches!(self.peek_token(), Some(Token::KeywordFn | Token::KeywordAsync)) {
+                // Másik deklaráció: valószínűleg hiányzik az impl blokk `}`-je
+                if self.at_item_start() {
+                    break;
+                }
+                let diag = self.unexpected("method declaration");
+                self.diagnostics.push(diag);
+                self.synchronize();
+                continue;
+            }
+
+            match self.parse_function() {
+                Ok(mut method) => {
+                    // A típus nélküli `self` paraméter a blokk típusát kapja
+                    if let Some(receiver) = method.params.first_mut() {
+                        if receiver.name == "self" && receiver.type_annot.is_none() {
+                            receiver.type_annot = Some(self_type.clone());
+                        }
+                    }
+                    methods.push(method);
+                }
+                Err(diag) => {
+                    self.diagnostics.push(diag);
+                    self.synchronize();
+                }
+            }
+        }
+
+        // A hiányzó `}` miatt nem dobjuk el a már feldolgozott metódusokat
+        if let Err(diag) = self.expect_token(Token::RBrace) {
+            self.diagnostics.push(diag);
+        }
+        Ok(Impl {
+            generics,
+            trait_name,
+            type_name: self_type.name,
+            type_args: self_type.args,
+            methods,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_function(&mut self) -> Result<Function, Diagnostic> {
+        let mut function = self.parse_signature()?;
+        self.parse_body(&mut function)?;
+        Ok(function)
+    }
+
+    // `fv nev<T>(a: T) -> T`, törzs nélkül
+    fn parse_signature(&mut self) -> Result<Function, Diagnostic> {
+        let start = self.current_span();
+        let is_async = matches!(self.peek_token(), Some(Token::KeywordAsync));
+        if is_async {
+            self.consume_token();
+        }
+        self.expect_token(Token::KeywordFn)?;
+        let (name, _) = self.expect_ident("function name")?;
+        let generics = self.parse_generics()?;
 
         self.expect_token(Token::LParen)?;
         let params = self.parse_params()?;
@@ -145,31 +445,38 @@ impl<'a> Parser<'a> {
 
         let return_type = if let Some(Token::Arrow) = self.peek_token() {
             self.consume_token();
-            self.parse_type_annotation()?
+            Some(self.parse_type_annotation()?)
         } else {
             None
         };
 
-        self.expect_token(Token::LBrace)?;
-        let body = self.parse_block()?;
-        self.expect_token(Token::RBrace)?;
+        Ok(Function {
+            name,
+            is_pub: false,
+            is_async,
+            generics,
+            params,
+            return_type,
+            body: Vec::new(),
+            span: self.span_from(start),
+        })
+    }
 
-        if let Some(name) = name {
-            Ok(Function {
-                name: name.unwrap_or_else(|| "".to_string()),
-                params,
-                return_type,
-                body,
-            })
-        } else {
-            Err("Function name is missing".to_string())
+    fn parse_body(&mut self, function: &mut Function) -> Result<(), Diagnostic> {
+        self.expect_token(Token::LBrace)?;
+        function.body = self.parse_block();
+        // A hiányzó `}` miatt nem dobjuk el a már feldolgozott törzset
+        if let Err(diag) = self.expect_token(Token::RBrace) {
+            self.diagnostics.push(diag);
         }
+        function.span = self.span_from(function.span);
+        Ok(())
     }
 
-    fn parse_params(&mut self) -> Result<Vec<(String, Option<String>)>, String> {
+    fn parse_params(&mut self) -> Result<Vec<Param>, Diagnostic> {
         let mut params = Vec::new();
 
-        while let Some((Token::Ident(name), _)) = self.current_token.clone() {
+        while let Some((Token::Ident(name), start)) = self.current_token.clone() {
             self.consume_token();
 
             let type_annot = if let Some(Token::Colon) = self.peek_token() {
@@ -179,7 +486,11 @@ impl<'a> Parser<'a> {
                 None
             };
 
-            params.push((name, type_annot));
+            params.push(Param {
+                name,
+                type_annot,
+                span: self.span_from(start),
+            });
 
             if let Some(Token::Comma) = self.peek_token() {
                 self.consume_token();
@@ -187,89 +498,189 @@ impl<'a> Parser<'a> {
                 break;
             }
         }
-        Ok(params) // Javítva (return a cikluson belül volt)
+        Ok(params)
     }
 
-    fn parse_block(&mut self) -> Result<Vec<Stmt>, String> {
+    fn parse_block(&mut self) -> Vec<Stmt> {
         let mut stmts = Vec::new();
 
-        while self.current_token.is_some() && !matches!(self.peek_token(), Some(Token::RBrace)) {
-            stmts.push(self.parse_stmt()?);
+        while self.current_token.is_some() && !matches!(self.peek_token(), Some(Token::RBrace)) && !self.at_item_start() {
+            match self.parse_stmt() {
+                Ok(stmt) => stmts.push(stmt),
+                Err(diag) => {
+                    self.diagnostics.push(diag);
+                    self.synchronize();
+                    continue;
+                }
+            }
 
             if let Some(Token::Semicolon) = self.peek_token() {
                 self.consume_token();
             }
         }
 
-        Ok(stmts)
+        stmts
     }
 
-    fn parse_stmt(&mut self) -> Result<Stmt, String> {
-        match self.current_token.clone() {
-            Some((Token::KeywordLet, _)) => self.parse_let_stmt(),
-            Some((Token::KeywordReturn, _)) => self.parse_return_stmt(),
-            Some((Token::KeywordWhile, _)) => self.parse_while_stmt(),
-            Some((Token::KeywordIf, _)) => self.parse_if_expr().map(Stmt::Expr),
-            _ => self.parse_expr().map(Stmt::Expr),
+    fn parse_stmt(&mut self) -> Result<Stmt, Diagnostic> {
+        match self.peek_token() {
+            Some(Token::KeywordLet | Token::KeywordLetImmutable) => self.parse_let_stmt(),
+            Some(Token::KeywordConst) => {
+                let def = self.parse_const()?;
+                let span = def.span;
+                Ok(Stmt { kind: StmtKind::Const(def), span })
+            },
+            Some(Token::KeywordReturn) => self.parse_return_stmt(),
+            Some(Token::KeywordWhile) => self.parse_while_stmt(),
+            Some(Token::KeywordFor) => self.parse_for_stmt(),
+            _ => {
+                let expr = self.parse_expr()?;
+                let op = match self.peek_token() {
+                    Some(Token::Equals) => None,
+                    Some(Token::PlusEquals) => Some(BinaryOp::Add),
+                    Some(Token::MinusEquals) => Some(BinaryOp::Sub),
+                    Some(Token::StarEquals) => Some(BinaryOp::Mul),
+                    Some(Token::SlashEquals) => Some(BinaryOp::Div),
+                    _ => {
+                        let span = expr.span;
+                        return Ok(Stmt { kind: StmtKind::Expr(expr), span });
+                    }
+                };
+                // Értékadás: a bal oldal helyességét a típusellenőrző vizsgálja
+                self.consume_token();
+                let value = self.parse_expr()?;
+                let span = expr.span.to(value.span);
+                Ok(Stmt { kind: StmtKind::Assign { target: expr, op, value }, span })
+            }
         }
     }
 
-    fn parse_let_stmt(&mut self) -> Result<Stmt, String> {
-        self.expect_token(Token::KeywordLet)?;
+    fn parse_let_stmt(&mut self) -> Result<Stmt, Diagnostic> {
+        let mutable = matches!(self.peek_token(), Some(Token::KeywordLet));
+        let start = self.expect_token(if mutable { Token::KeywordLet } else { Token::KeywordLetImmutable })?;
+        let (name, _) = self.expect_ident("variable name")?;
 
-        let name = match self.current_token.clone() {
-            Some((Token::Ident(name), _)) => name,
-            _ => {
-                return Err("Expected variable name".to_string());
-            }
+        let type_annot = if let Some(Token::Colon) = self.peek_token() {
+            self.consume_token();
+            Some(self.parse_type_annotation()?)
+        } else {
+            None
         };
-        self.consume_token();
+
+        self.expect_token(Token::Equals)?;
+        let value = self.parse_expr()?;
+
+        Ok(Stmt {
+            kind: StmtKind::Let {
+                name,
+                mutable,
+                type_annot,
+                value,
+            },
+            span: self.span_from(start),
+        })
+    }
+
+    // `konst NEV: T = kifejezés;` (a típus elhagyható)
+    fn parse_const(&mut self) -> Result<Const, Diagnostic> {
+        let start = self.expect_token(Token::KeywordConst)?;
+        let (name, _) = self.expect_ident("constant name")?;
 
         let type_annot = if let Some(Token::Colon) = self.peek_token() {
             self.consume_token();
+            Some(self.parse_type_annotation()?)
         } else {
             None
         };
 
         self.expect_token(Token::Equals)?;
         let value = self.parse_expr()?;
+        let span = self.span_from(start);
+        self.expect_token(Token::Semicolon)?;
 
-        Ok(Stmt::Let {
-            name,
-            type_annot,
-            value,
-        })
+        Ok(Const { name, is_pub: false, type_annot, value, span })
     }
 
-    fn parse_return_stmt(&mut self) -> Result<Stmt, String> {
-        self.expect_token(Token::KeywordReturn)?;
+    fn parse_return_stmt(&mut self) -> Result<Stmt, Diagnostic> {
+        let start = self.expect_token(Token::KeywordReturn)?;
 
-        let expr = if !matches!(self.peek_token(), Some(Token::Semicolon)) {
+        let expr = if !matches!(self.peek_token(), Some(Token::Semicolon) | Some(Token::RBrace)) {
             Some(self.parse_expr()?)
         } else {
             None
         };
 
-        Ok(Stmt::Return(expr))
+        Ok(Stmt {
+            kind: StmtKind::Return(expr),
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_while_stmt(&mut self) -> Result<Stmt, Diagnostic> {
+        let start = self.expect_token(Token::KeywordWhile)?;
+
+        let condition = self.parse_condition()?;
+        self.expect_token(Token::LBrace)?;
+        let body = self.parse_block();
+        self.expect_token(Token::RBrace)?;
+
+        Ok(Stmt {
+            kind: StmtKind::While { condition, body },
+            span: self.span_from(start),
+        })
     }
 
-    fn parse_while_stmt(&mut self) -> Result<Stmt, String> {
-        self.expect_token(Token::KeywordWhile)?;
+    fn parse_for_stmt(&mut self) -> Result<Stmt, Diagnostic> {
+        let start = self.expect_token(Token::KeywordFor)?;
 
-        let condition = self.parse_expr()?;
+        let pattern = self.parse_pattern()?;
+        self.expect_token(Token::KeywordIn)?;
+        let iterable = self.parse_condition()?;
         self.expect_token(Token::LBrace)?;
-        let body = self.parse_block()?;
+        let body = self.parse_block();
         self.expect_token(Token::RBrace)?;
 
-        Ok(Stmt::While { condition, body })
+        Ok(Stmt {
+            kind: StmtKind::For { pattern: Box::new(pattern), iterable, body },
+            span: self.span_from(start),
+        })
+    }
+
+    // A tartomány (`a..b`, `a..=b`) köt a leggyengébben, minden bináris operátor után
+    fn parse_expr(&mut self) -> Result<Expr, Diagnostic> {
+        let start = self.parse_binary_expr(0)?;
+        let inclusive = match self.peek_token() {
+            Some(Token::DoubleDot) => false,
+            Some(Token::DoubleDotEquals) => true,
+            _ => return Ok(start),
+        };
+        self.consume_token();
+
+        let end = self.parse_binary_expr(0)?;
+        Ok(Expr {
+            span: start.span.to(end.span),
+            kind: ExprKind::Range {
+                start: Box::new(start),
+                end: Box::new(end),
+                inclusive,
+            },
+        })
+    }
+
+    // `ha`/`mialatt` feltétele: itt nem lehet zárójel nélküli struct literál
+    fn parse_condition(&mut self) -> Result<Expr, Diagnostic> {
+        self.with_struct_literals(false, Self::parse_expr)
     }
 
-    fn parse_expr(&mut self) -> Result<Expr, String> {
-        self.parse_binary_expr(0)
+    fn with_struct_literals<T>(&mut self, allowed: bool, parse: impl FnOnce(&mut Self) -> T) -> T {
+        let saved = std::mem::replace(&mut self.struct_literals, allowed);
+        let result = parse(self);
+        self.struct_literals = saved;
+        result
     }
 
-    fn parse_binary_expr(&mut self, precedence: u8) -> Result<Expr, String> {
-        let mut left = self.parse_primary_expr()?;
+    fn parse_binary_expr(&mut self, precedence: u8) -> Result<Expr, Diagnostic> {
+        let mut left = self.parse_cast_expr()?;
 
         while let Some(op) = self.current_binary_op() {
             let op_prec = self.op_precedence(&op);
@@ -279,65 +690,358 @@ impl<'a> Parser<'a> {
 
             self.consume_token();
             let right = self.parse_binary_expr(op_prec + 1)?;
-            left = Expr::BinaryOp {
-                left: Expr::BinaryOp,
-                op,
-                right: Box::new(right),
+            let span = left.span.to(right.span);
+            left = Expr {
+                kind: ExprKind::BinaryOp {
+                    left: Box::new(left),
+                    op,
+                    right: Box::new(right),
+                },
+                span,
             };
         }
 
         Ok(left)
     }
 
-    fn parse_primary_expr(&mut self) -> Result<Expr, String> {
-        match self.current_token.clone() {
-            Some((Token::Int(n), _)) => {
+    // `x as u8`: erősebben köt minden bináris operátornál, de gyengébben az unárisoknál (`-x as u8`)
+    fn parse_cast_expr(&mut self) -> Result<Expr, Diagnostic> {
+        let mut expr = self.parse_unary_expr()?;
+
+        while let Some(Token::KeywordAs) = self.peek_token() {
+            self.consume_token();
+            // Primitív cél után a `<` összehasonlítás, nem generikus argumentumlista (`x as i64 < y`)
+            let target = match self.current_token.clone() {
+                Some((Token::Ident(name), span)) if IntTy::from_name(&name).is_some() || FloatTy::from_name(&name).is_some() || name == "bool" || name == "char" => {
+                    self.consume_token();
+                    TypeAnnot::named(&name, span)
+                },
+                _ => self.parse_type_annotation()?
+            };
+            let start = expr.span;
+            expr = Expr {
+                kind: ExprKind::Cast {
+                    expr: Box::new(expr),
+                    target,
+                },
+                span: self.span_from(start),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_unary_expr(&mut self) -> Result<Expr, Diagnostic> {
+        let op = match self.peek_token() {
+            Some(Token::Minus) => UnaryOp::Neg,
+            Some(Token::Bang) => UnaryOp::Not,
+            Some(Token::KeywordAwait) => return self.parse_await_expr(),
+            _ => return self.parse_postfix_expr(),
+        };
+        let start = self.current_span();
+        self.consume_token();
+
+        let operand = self.parse_unary_expr()?;
+        Ok(Expr {
+            span: start.to(operand.span),
+            kind: ExprKind::Unary {
+                op,
+                operand: Box::new(operand),
+            },
+        })
+    }
+
+    // `akkor jovo`: a bevárt kifejezés a teljes unáris kifejezés (`akkor a.letolt()`)
+    fn parse_await_expr(&mut self) -> Result<Expr, Diagnostic> {
+        let start = self.expect_token(Token::KeywordAwait)?;
+        let future = self.parse_unary_expr()?;
+        Ok(Expr {
+            span: start.to(future.span),
+            kind: ExprKind::Await(Box::new(future)),
+        })
+    }
+
+    // Mezőelérés és metódushívás: `p.x`, `a.b.c`, `p.hossz()`; indexelés és a `?` operátor
+    fn parse_postfix_expr(&mut self) -> Result<Expr, Diagnostic> {
+        let mut expr = self.parse_primary_expr()?;
+
+        loop {
+            if let Some(Token::Question) = self.peek_token() {
+                self.consume_token();
+                let start = expr.span;
+                expr = Expr {
+                    kind: ExprKind::Try(Box::new(expr)),
+                    span: self.span_from(start),
+                };
+                continue;
+            }
+            if let Some(Token::LSquare) = self.peek_token() {
+                self.consume_token();
+                let index = self.with_struct_literals(true, Self::parse_expr)?;
+                self.expect_token(Token::RSquare)?;
+                let start = expr.span;
+                expr = Expr {
+                    kind: ExprKind::Index {
+                        object: Box::new(expr),
+                        index: Box::new(index),
+                    },
+                    span: self.span_from(start),
+                };
+                continue;
+            }
+            if !matches!(self.peek_token(), Some(Token::Dot)) {
+                break;
+            }
+            self.consume_token();
+            let (name, _) = self.expect_ident("field or method name")?;
+            let start = expr.span;
+            let kind = if let Some(Token::LParen) = self.peek_token() {
+                let args = self.parse_call_args()?;
+                ExprKind::MethodCall {
+                    receiver: Box::new(expr),
+                    method: name,
+                    args,
+                }
+            } else {
+                ExprKind::Field {
+                    object: Box::new(expr),
+                    field: name,
+                }
+            };
+            expr = Expr {
+                span: self.span_from(start),
+                kind,
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_primary_expr(&mut self) -> Result<Expr, Diagnostic> {
+        let start = self.current_span();
+        let kind = match self.current_token.clone() {
+            Some((Token::Int(lit), _)) => {
+                self.consume_token();
+                ExprKind::Literal(Literal::Int(lit.value, lit.suffix))
+            }
+
+            Some((Token::Float(lit), _)) => {
+                self.consume_token();
+                ExprKind::Literal(Literal::Float(lit.value, lit.suffix))
+            }
+
+            Some((Token::StringLit(segments), _)) => {
                 self.consume_token();
-                Ok(Expr::Literal(Literal::Int(n)))
+                match segments.as_slice() {
+                    [] => ExprKind::Literal(Literal::String(String::new())),
+                    [StrSegment::Text(s)] => ExprKind::Literal(Literal::String(s.clone())),
+                    _ => return self.parse_interpolation(segments, start),
+                }
             }
 
-            Some((Token::Float(n), _)) => {
+            Some((Token::RawStringLit(s), _)) => {
                 self.consume_token();
-                Ok(Expr::Literal(Literal::Float(n)))
+                ExprKind::Literal(Literal::String(s))
             }
 
-            Some((Token::StringLit(s), _)) => {
+            Some((Token::ByteStringLit(bytes), _)) => {
                 self.consume_token();
-                Ok(Expr::Literal(Literal::String(s)))
+                ExprKind::Literal(Literal::ByteString(bytes))
             }
 
-            Some((Token::Bool(bool), _)) => {
+            Some((Token::CharLit(c), _)) => {
                 self.consume_token();
-                Ok(Expr::Literal(Literal::Bool(b)))
+                ExprKind::Literal(Literal::Char(c))
+            }
+
+            Some((Token::Bool(b), _)) => {
+                self.consume_token();
+                ExprKind::Literal(Literal::Bool(b))
             }
 
             Some((Token::Ident(name), _)) => {
                 self.consume_token();
-                if let Some(Token::LParen) = self.peek_token() {
-                    self.parse_call_expr(name)
-                } else {
-                    Ok(Expr::Ident(name))
+                match self.peek_token() {
+                    Some(Token::LParen) => return self.parse_call_expr(name, start),
+                    Some(Token::DoubleColon) => return self.parse_assoc_call(name, start),
+                    Some(Token::LBrace) if self.struct_literals => return self.parse_struct_lit(name, start),
+                    _ => {}
                 }
+                ExprKind::Ident(name)
             }
 
             Some((Token::LParen, _)) => {
                 self.consume_token();
-                let expr = self.parse_expr()?;
+                // `()`: az egységérték
+                if let Some(Token::RParen) = self.peek_token() {
+                    self.consume_token();
+                    return Ok(Expr {
+                        kind: ExprKind::Tuple(Vec::new()),
+                        span: self.span_from(start),
+                    });
+                }
+                let mut expr = self.with_struct_literals(true, Self::parse_expr)?;
+                if let Some(Token::Comma) = self.peek_token() {
+                    return self.parse_tuple_rest(expr, start);
+                }
                 self.expect_token(Token::RParen)?;
-                Ok(expr)
+                expr.span = self.span_from(start);
+                return Ok(expr);
+            }
+
+            // `[1, 2, 3]`, a záró vessző elhagyható
+            Some((Token::LSquare, _)) => {
+                self.consume_token();
+                let mut elements = Vec::new();
+                while !matches!(self.peek_token(), Some(Token::RSquare)) {
+                    elements.push(self.with_struct_literals(true, Self::parse_expr)?);
+                    if let Some(Token::Comma) = self.peek_token() {
+                        self.consume_token();
+                    } else {
+                        break;
+                    }
+                }
+                self.expect_token(Token::RSquare)?;
+                return Ok(Expr {
+                    kind: ExprKind::Array(elements),
+                    span: self.span_from(start),
+                });
+            }
+
+            Some((Token::KeywordIf, _)) => return self.parse_if_expr(),
+            Some((Token::KeywordMatch, _)) => return self.parse_match_expr(),
+            Some((Token::Pipe | Token::Or | Token::KeywordMove, _)) => return self.parse_closure(),
+            _ => return Err(self.unexpected("expression")),
+        };
+
+        Ok(Expr { kind, span: start })
+    }
+
+    // Az interpolált kifejezéseket külön parserrel dolgozzuk fel; a lexer eltolása
+    // miatt a tartományaik (és hibáik) a fájlbeli helyükre mutatnak
+    fn parse_interpolation(&mut self, segments: Vec<StrSegment>, span: Span) -> Result<Expr, Diagnostic> {
+        let mut parts = Vec::new();
+
+        for segment in segments {
+            match segment {
+                StrSegment::Text(text) => parts.push(InterpPart::Text(text)),
+                StrSegment::Expr(source, offset) => {
+                    let mut parser = Parser::new(Lexer::with_offset(&source, span.file, span.start + offset));
+                    let expr = parser.parse_expr();
+                    if expr.is_ok() && parser.current_token.is_some() {
+                        let diag = parser.unexpected("`}`");
+                        parser.diagnostics.push(diag);
+                    }
+                    self.diagnostics.append(&mut parser.diagnostics);
+                    parts.push(InterpPart::Expr(expr?));
+                }
+            }
+        }
+
+        Ok(Expr {
+            kind: ExprKind::Interpolated(parts),
+            span,
+        })
+    }
+
+    // `(a, b, ...)`: az első elem már feldolgozva, a záró vessző elhagyható
+    fn parse_tuple_rest(&mut self, first: Expr, start: Span) -> Result<Expr, Diagnostic> {
+        let mut elements = vec![first];
+        while let Some(Token::Comma) = self.peek_token() {
+            self.consume_token();
+            if let Some(Token::RParen) = self.peek_token() {
+                break;
+            }
+            elements.push(self.with_struct_literals(true, Self::parse_expr)?);
+        }
+
+        self.expect_token(Token::RParen)?;
+        Ok(Expr {
+            kind: ExprKind::Tuple(elements),
+            span: self.span_from(start),
+        })
+    }
+
+    // `|x, y: i32| x + y`, `|| { ... }`, `mozgat |x| -> i32 { vissza x; }`
+    fn parse_closure(&mut self) -> Result<Expr, Diagnostic> {
+        let start = self.current_span();
+        let is_move = matches!(self.peek_token(), Some(Token::KeywordMove));
+        if is_move {
+            self.consume_token();
+        }
+
+        // A `||` egyetlen tokenként érkezik: üres paraméterlista
+        let params = if let Some(Token::Or) = self.peek_token() {
+            self.consume_token();
+            Vec::new()
+        } else {
+            self.expect_token(Token::Pipe)?;
+            let params = self.parse_params()?;
+            self.expect_token(Token::Pipe)?;
+            params
+        };
+
+        // Megadott visszatérési típus után csak blokk állhat
+        let return_type = if let Some(Token::Arrow) = self.peek_token() {
+            self.consume_token();
+            Some(self.parse_type_annotation()?)
+        } else {
+            None
+        };
+        let body = if return_type.is_some() || matches!(self.peek_token(), Some(Token::LBrace)) {
+            let block_start = self.expect_token(Token::LBrace)?;
+            let stmts = self.parse_block();
+            self.expect_token(Token::RBrace)?;
+            Expr {
+                kind: ExprKind::Block(stmts),
+                span: self.span_from(block_start),
             }
+        } else {
+            self.parse_expr()?
+        };
+
+        Ok(Expr {
+            kind: ExprKind::Closure {
+                params,
+                return_type,
+                body: Box::new(body),
+                is_move,
+            },
+            span: self.span_from(start),
+        })
+    }
 
-            Some((Token::KeywordIf, _)) => self.parse_if_expr(),
-            _ => Err("Expected expression".to_string()),
+    fn parse_call_expr(&mut self, callee: String, start: Span) -> Result<Expr, Diagnostic> {
+        let args = self.parse_call_args()?;
+        Ok(Expr {
+            kind: ExprKind::Call { callee, args },
+            span: self.span_from(start),
+        })
+    }
+
+    // `Pont::uj(...)`, vagy zárójelek nélkül `Szin::Piros`
+    fn parse_assoc_call(&mut self, type_name: String, start: Span) -> Result<Expr, Diagnostic> {
+        self.expect_token(Token::DoubleColon)?;
+        let (name, _) = self.expect_ident("associated function name")?;
+        if !matches!(self.peek_token(), Some(Token::LParen)) {
+            return Ok(Expr {
+                kind: ExprKind::Path { type_name, name },
+                span: self.span_from(start),
+            });
         }
+        let args = self.parse_call_args()?;
+        Ok(Expr {
+            kind: ExprKind::AssocCall { type_name, name, args },
+            span: self.span_from(start),
+        })
     }
 
-    fn parse_call_expr(&mut self, callee: String) -> Result<Expr, String> {
+    fn parse_call_args(&mut self) -> Result<Vec<Expr>, Diagnostic> {
         self.expect_token(Token::LParen)?;
 
         let mut args = Vec::new();
         while !matches!(self.peek_token(), Some(Token::RParen)) {
-            args.push(self.parse_expr()?);
+            args.push(self.with_struct_literals(true, Self::parse_expr)?);
 
             if let Some(Token::Comma) = self.peek_token() {
                 self.consume_token();
@@ -347,35 +1051,296 @@ impl<'a> Parser<'a> {
         }
 
         self.expect_token(Token::RParen)?;
-        Ok(Expr::Call { callee, args })
+        Ok(args)
     }
 
-    fn parse_if_expr(&mut self) -> Result<Expr, String> {
-        self.expect_token(Token::KeywordIf)?;
+    // `Pont { x: 1.0, y }` (a mezőnév önmagában az azonos nevű változót jelenti)
+    fn parse_struct_lit(&mut self, name: String, start: Span) -> Result<Expr, Diagnostic> {
+        self.expect_token(Token::LBrace)?;
+
+        let mut fields = Vec::new();
+        while let Some((Token::Ident(field_name), field_start)) = self.current_token.clone() {
+            self.consume_token();
+            let value = if let Some(Token::Colon) = self.peek_token() {
+                self.consume_token();
+                self.with_struct_literals(true, Self::parse_expr)?
+            } else {
+                Expr {
+                    kind: ExprKind::Ident(field_name.clone()),
+                    span: field_start,
+                }
+            };
+
+            fields.push(FieldInit {
+                name: field_name,
+                value,
+                span: self.span_from(field_start),
+            });
 
-        let condition = Box::new(self.parse_expr()?);
+            if let Some(Token::Comma) = self.peek_token() {
+                self.consume_token();
+            } else {
+                break;
+            }
+        }
+
+        self.expect_token(Token::RBrace)?;
+        Ok(Expr {
+            kind: ExprKind::StructLit { name, fields },
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_if_expr(&mut self) -> Result<Expr, Diagnostic> {
+        let start = self.expect_token(Token::KeywordIf)?;
+
+        let condition = Box::new(self.parse_condition()?);
         self.expect_token(Token::LBrace)?;
-        let then_branch = self.parse_block()?;
+        let then_branch = self.parse_block();
         self.expect_token(Token::RBrace)?;
 
         let else_branch = if let Some(Token::KeywordElse) = self.peek_token() {
             self.consume_token();
             self.expect_token(Token::LBrace)?;
-            let else_branch = self.parse_block()?;
+            let else_branch = self.parse_block();
             self.expect_token(Token::RBrace)?;
             Some(else_branch)
         } else {
             None
         };
 
-        Ok(Expr::If {
-            condition,
-            then_branch,
-            else_branch,
+        Ok(Expr {
+            kind: ExprKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            },
+            span: self.span_from(start),
+        })
+    }
+
+    // eset x { minta ha őrfeltétel => kifejezés, minta => { utasítások } }
+    fn parse_match_expr(&mut self) -> Result<Expr, Diagnostic> {
+        let start = self.expect_token(Token::KeywordMatch)?;
+        let scrutinee = Box::new(self.parse_condition()?);
+        self.expect_token(Token::LBrace)?;
+
+        let mut arms = Vec::new();
+        while !matches!(self.peek_token(), Some(Token::RBrace) | None) {
+            let arm_start = self.current_span();
+            let pattern = self.parse_pattern()?;
+
+            let guard = if let Some(Token::KeywordIf) = self.peek_token() {
+                self.consume_token();
+                Some(self.parse_expr()?)
+            } else {
+                None
+            };
+            self.expect_token(Token::FatArrow)?;
+
+            let is_block = matches!(self.peek_token(), Some(Token::LBrace));
+            let body = if is_block {
+                let block_start = self.expect_token(Token::LBrace)?;
+                let stmts = self.parse_block();
+                self.expect_token(Token::RBrace)?;
+                Expr {
+                    kind: ExprKind::Block(stmts),
+                    span: self.span_from(block_start),
+                }
+            } else {
+                self.parse_expr()?
+            };
+
+            arms.push(MatchArm {
+                pattern,
+                guard,
+                body,
+                span: self.span_from(arm_start),
+            });
+
+            // Blokk után a vessző elhagyható
+            if let Some(Token::Comma) = self.peek_token() {
+                self.consume_token();
+            } else if !is_block {
+                break;
+            }
+        }
+
+        self.expect_token(Token::RBrace)?;
+        Ok(Expr {
+            kind: ExprKind::Match { scrutinee, arms },
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_pattern(&mut self) -> Result<Pattern, Diagnostic> {
+        let start = self.current_span();
+        let kind = match self.current_token.clone() {
+            Some((Token::Ident(name), _)) => {
+                self.consume_token();
+                if name == "_" {
+                    PatternKind::Wildcard
+                } else if let Some(Token::LBrace) = self.peek_token() {
+                    return self.parse_struct_pattern(name, start);
+                } else if let Some(Token::DoubleColon) = self.peek_token() {
+                    return self.parse_variant_pattern(name, start);
+                } else {
+                    PatternKind::Binding(name)
+                }
+            }
+
+            Some((Token::LParen, _)) => {
+                self.consume_token();
+                let mut elements = Vec::new();
+                let mut trailing_comma = false;
+                while !matches!(self.peek_token(), Some(Token::RParen)) {
+                    elements.push(self.parse_pattern()?);
+                    trailing_comma = matches!(self.peek_token(), Some(Token::Comma));
+                    if trailing_comma {
+                        self.consume_token();
+                    } else {
+                        break;
+                    }
+                }
+                self.expect_token(Token::RParen)?;
+
+                // `(p)` csak zárójelezés, nem egyelemű tuple
+                if elements.len() == 1 && !trailing_comma {
+                    let mut pattern = elements.pop().unwrap();
+                    pattern.span = self.span_from(start);
+                    return Ok(pattern);
+                }
+                PatternKind::Tuple(elements)
+            }
+
+            _ => {
+                let lit = self.parse_literal_pattern()?;
+                match self.peek_token() {
+                    Some(Token::DoubleDot) | Some(Token::DoubleDotEquals) => {
+                        let inclusive = matches!(self.peek_token(), Some(Token::DoubleDotEquals));
+                        self.consume_token();
+                        let end = self.parse_literal_pattern()?;
+                        PatternKind::Range { start: lit, end, inclusive }
+                    }
+                    _ => PatternKind::Literal(lit),
+                }
+            }
+        };
+
+        Ok(Pattern {
+            kind,
+            span: self.span_from(start),
+        })
+    }
+
+    // Szám (akár negatív), string, karakter vagy logikai literál
+    fn parse_literal_pattern(&mut self) -> Result<Expr, Diagnostic> {
+        match self.peek_token() {
+            Some(Token::Minus) => {
+                let start = self.current_span();
+                self.consume_token();
+                if !matches!(self.peek_token(), Some(Token::Int(_)) | Some(Token::Float(_))) {
+                    return Err(self.unexpected("number literal"));
+                }
+                let operand = self.parse_primary_expr()?;
+                Ok(Expr {
+                    span: start.to(operand.span),
+                    kind: ExprKind::Unary {
+                        op: UnaryOp::Neg,
+                        operand: Box::new(operand),
+                    },
+                })
+            }
+            Some(Token::Int(_))
+            | Some(Token::Float(_))
+            | Some(Token::StringLit(_))
+            | Some(Token::RawStringLit(_))
+            | Some(Token::CharLit(_))
+            | Some(Token::Bool(_)) => {
+                let expr = self.parse_primary_expr()?;
+                if let ExprKind::Interpolated(_) = expr.kind {
+                    return Err(Diagnostic::error("interpolated strings cannot be used as patterns")
+                        .with_code(codes::INVALID_PATTERN)
+                        .with_primary(expr.span, "not a constant pattern"));
+                }
+                Ok(expr)
+            }
+            _ => Err(self.unexpected("pattern")),
+        }
+    }
+
+    // `Alak::Kor(r)`, `Szin::Piros`
+    fn parse_variant_pattern(&mut self, enum_name: String, start: Span) -> Result<Pattern, Diagnostic> {
+        self.expect_token(Token::DoubleColon)?;
+        let (variant, _) = self.expect_ident("enum variant")?;
+
+        let mut fields = Vec::new();
+        if let Some(Token::LParen) = self.peek_token() {
+            self.consume_token();
+            while !matches!(self.peek_token(), Some(Token::RParen)) {
+                fields.push(self.parse_pattern()?);
+                if let Some(Token::Comma) = self.peek_token() {
+                    self.consume_token();
+                } else {
+                    break;
+                }
+            }
+            self.expect_token(Token::RParen)?;
+        }
+
+        Ok(Pattern {
+            kind: PatternKind::Variant { enum_name, variant, fields },
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_struct_pattern(&mut self, name: String, start: Span) -> Result<Pattern, Diagnostic> {
+        self.expect_token(Token::LBrace)?;
+
+        let mut fields = Vec::new();
+        let mut rest = false;
+        loop {
+            match self.current_token.clone() {
+                Some((Token::DoubleDot, _)) => {
+                    self.consume_token();
+                    rest = true;
+                    break;
+                }
+                Some((Token::Ident(field_name), field_start)) => {
+                    self.consume_token();
+                    let pattern = if let Some(Token::Colon) = self.peek_token() {
+                        self.consume_token();
+                        self.parse_pattern()?
+                    } else {
+                        Pattern {
+                            kind: PatternKind::Binding(field_name.clone()),
+                            span: field_start,
+                        }
+                    };
+                    fields.push(FieldPattern {
+                        name: field_name,
+                        pattern,
+                        span: self.span_from(field_start),
+                    });
+
+                    if let Some(Token::Comma) = self.peek_token() {
+                        self.consume_token();
+                    } else {
+                        break;
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        self.expect_token(Token::RBrace)?;
+        Ok(Pattern {
+            kind: PatternKind::Struct { name, fields, rest },
+            span: self.span_from(start),
         })
     }
 
-    fn current_binary_op(&mut self) -> Option<BinaryOp> {
+    fn current_binary_op(&self) -> Option<BinaryOp> {
         match self.peek_token()? {
             Token::Plus => Some(BinaryOp::Add),
             Token::Minus => Some(BinaryOp::Sub),
@@ -384,7 +1349,7 @@ impl<'a> Parser<'a> {
             Token::DoubleEquals => Some(BinaryOp::Eq),
             Token::NotEquals => Some(BinaryOp::Neq),
             Token::LessThan => Some(BinaryOp::Lt),
-            Token::GreateThan => Some(BinaryOp::Gt),
+            Token::GreaterThan => Some(BinaryOp::Gt),
             Token::LessOrEqual => Some(BinaryOp::Le),
             Token::GreaterOrEqual => Some(BinaryOp::Ge),
             Token::And => Some(BinaryOp::And),
@@ -408,13 +1373,142 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_type_annotation(&mut self) -> Result<String, String> {
-        match self.current_token.clone() {
-            Some((Token::Ident(ty), _)) => {
+    // `i32`, `Lista<T>`, `Par<str, Lista<i32>>`, `dyn Kiirhato`, `fv(i32) -> bool`, `[u8; 4]`, `[T]`
+    fn parse_type_annotation(&mut self) -> Result<TypeAnnot, Diagnostic> {
+        if let Some((Token::LSquare, start)) = self.current_token.clone() {
+            self.consume_token();
+            let element = self.parse_type_annotation()?;
+            let array_len = if let Some(Token::Semicolon) = self.peek_token() {
                 self.consume_token();
-                Ok(ty)
+                let Some((Token::Int(lit), _)) = self.current_token.clone() else {
+                    return Err(self.unexpected("array length"));
+                };
+                self.consume_token();
+                Some(lit.value as u64)
+            } else {
+                None
+            };
+            self.expect_token(Token::RSquare)?;
+            return Ok(TypeAnnot {
+                args: vec![element],
+                array_len,
+                ..TypeAnnot::named(ARRAY_TYPE, self.span_from(start))
+            });
+        }
+        if let Some((Token::Bang, start)) = self.current_token.clone() {
+            self.consume_token();
+            return Ok(TypeAnnot::named(NEVER_TYPE, self.span_from(start)));
+        }
+        if let Some((Token::LParen, start)) = self.current_token.clone() {
+            self.consume_token();
+            self.expect_token(Token::RParen)?;
+            return Ok(TypeAnnot::named(UNIT_TYPE, self.span_from(start)));
+        }
+        if let Some((Token::KeywordDyn, start)) = self.current_token.clone() {
+            self.consume_token();
+            let (name, _) = self.expect_ident("trait name")?;
+            return Ok(TypeAnnot {
+                name,
+                args: Vec::new(),
+                is_dyn: true,
+                return_type: None,
+                array_len: None,
+                span: self.span_from(start),
+            });
+        }
+        if let Some((Token::KeywordFn, start)) = self.current_token.clone() {
+            self.consume_token();
+            self.expect_token(Token::LParen)?;
+            let mut params = Vec::new();
+            while !matches!(self.peek_token(), Some(Token::RParen)) {
+                params.push(self.parse_type_annotation()?);
+                if let Some(Token::Comma) = self.peek_token() {
+                    self.consume_token();
+                } else {
+                    break;
+                }
             }
-            _ => Err("Expected type annotation".to_string()),
+            self.expect_token(Token::RParen)?;
+            let return_type = if let Some(Token::Arrow) = self.peek_token() {
+                self.consume_token();
+                Some(Box::new(self.parse_type_annotation()?))
+            } else {
+                None
+            };
+            return Ok(TypeAnnot {
+                name: FN_TYPE.to_string(),
+                args: params,
+                is_dyn: false,
+                return_type,
+                array_len: None,
+                span: self.span_from(start),
+            });
         }
+        let Some((Token::Ident(name), start)) = self.current_token.clone() else {
+            return Err(self.unexpected("type annotation"));
+        };
+        self.consume_token();
+
+        let mut args = Vec::new();
+        if let Some(Token::LessThan) = self.peek_token() {
+            self.consume_token();
+            loop {
+                args.push(self.parse_type_annotation()?);
+                if let Some(Token::Comma) = self.peek_token() {
+                    self.consume_token();
+                } else {
+                    break;
+                }
+            }
+            self.expect_token(Token::GreaterThan)?;
+        }
+
+        Ok(TypeAnnot {
+            name,
+            args,
+            is_dyn: false,
+            return_type: None,
+            array_len: None,
+            span: self.span_from(start),
+        })
+    }
+
+    // `<T, U: Osszehasonlithato + Kiirhato>`; üres lista, ha nincs `<`
+    fn parse_generics(&mut self) -> Result<Vec<GenericParam>, Diagnostic> {
+        let mut generics = Vec::new();
+        if !matches!(self.peek_token(), Some(Token::LessThan)) {
+            return Ok(generics);
+        }
+        self.consume_token();
+
+        while let Some((Token::Ident(name), start)) = self.current_token.clone() {
+            self.consume_token();
+            let mut bounds = Vec::new();
+            if let Some(Token::Colon) = self.peek_token() {
+                self.consume_token();
+                loop {
+                    bounds.push(self.expect_ident("trait bound")?.0);
+                    if let Some(Token::Plus) = self.peek_token() {
+                        self.consume_token();
+                    } else {
+                        break;
+                    }
+                }
+            }
+            generics.push(GenericParam {
+                name,
+                bounds,
+                span: self.span_from(start),
+            });
+
+            if let Some(Token::Comma) = self.peek_token() {
+                self.consume_token();
+            } else {
+                break;
+            }
+        }
+
+        self.expect_token(Token::GreaterThan)?;
+        Ok(generics)
     }
 }
@@ -0,0 +1,40 @@
+// Minden programhoz hozzáadott beépített traitek és típusok. A primitív típusok implementációját
+// nem ez a forrás adja: a típusellenőrző és a kódgenerálás beépítve ismeri.
+pub const SOURCE: &str = r#"
+// Szöveggé alakítás; a string-interpoláció (`"{x}"`) is ezt használja
+vonas Kiirhato {
+    fv kiir(self) -> str;
+}
+
+// Rendezés: negatív, nulla vagy pozitív, ha `self` kisebb, egyenlő vagy nagyobb, mint `masik`
+vonas Osszehasonlithato {
+    fv osszehasonlit(self, masik: Self) -> i32;
+}
+
+// `aszink fv` eredménye; a `keret` az állapotgép (vagy a runtime jövője) címe, lásd `dlang_future_poll`
+strukt Jovo<T> {
+    keret: usize,
+}
+
+// Dinamikus tömb; a `kezelo` a runtime `DlangArray` címe, lásd `dlang_array_new`
+strukt Tomb<T> {
+    kezelo: usize,
+}
+
+// `szal_indit` által indított szál; a `kezelo` a runtime szálazonosítója
+strukt Szal {
+    kezelo: usize,
+}
+
+// Esetleg hiányzó érték; a `?` operátor a `Nincs` változatot továbbadja a hívónak
+enum Opcio<T> {
+    Van(T),
+    Nincs,
+}
+
+// Sikeres eredmény vagy hiba; nem hagyható figyelmen kívül, a `?` operátor a hibát továbbadja
+enum Eredmeny<T, E> {
+    Rendben(T),
+    Hiba(E),
+}
+"#;
@@ -0,0 +1,610 @@
+use crate::ast::{
+    Const, Enum, Expr, ExprKind, Function, GenericParam, Impl, Import, InterpPart, Param, Pattern, PatternKind, Program, Stmt, StmtKind,
+    Struct, Trait, TypeAnnot,
+};
+use crate::diagnostic::{ codes, Diagnostic };
+use crate::modules::{ module_path, Module };
+use crate::source_map::Span;
+use std::collections::HashMap;
+
+// Modulok feletti névfeloldás a típusellenőrzés előtt. A modulok elemei egyetlen programba
+// kerülnek: a nem gyökérmodulok elemei minősített nevet kapnak (`std::memory::Lap`), a
+// hivatkozások pedig (`use`-zal behozott név, `mem::foglal(...)`) erre a névre íródnak át.
+// A gyökérmodul elemei megtartják a nevüket. A metódusok a típusukkal együtt láthatók.
+pub fn resolve(modules: Vec<Module>) -> (Program, Vec<Diagnostic>) {
+    let mut diagnostics = Vec::new();
+    let mut scopes: Vec<ModuleScope> = modules.iter().map(ModuleScope::declare).collect();
+    for (index, module) in modules.iter().enumerate() {
+        for import in &module.program.imports {
+            if let Err(diag) = import_into(&mut scopes, &modules, index, import) {
+                diagnostics.push(diag);
+            }
+        }
+    }
+
+    let mut program = Program {
+        imports: Vec::new(),
+        structs: Vec::new(),
+        enums: Vec::new(),
+        traits: Vec::new(),
+        impls: Vec::new(),
+        consts: Vec::new(),
+        functions: Vec::new(),
+    };
+    for (index, module) in modules.iter().enumerate() {
+        let mut rewriter = Rewriter {
+            scopes: &scopes,
+            modules: &modules,
+            current: index,
+            locals: Vec::new(),
+            generics: Vec::new(),
+            diagnostics: &mut diagnostics,
+        };
+        let mut items = module.program.clone();
+        rewriter.program(&mut items);
+        program.structs.extend(items.structs);
+        program.enums.extend(items.enums);
+        program.traits.extend(items.traits);
+        program.impls.extend(items.impls);
+        program.consts.extend(items.consts);
+        program.functions.extend(items.functions);
+    }
+
+    (program, diagnostics)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ItemKind {
+    Function,
+    Struct,
+    Enum,
+    Trait,
+    Const,
+}
+
+impl ItemKind {
+    fn describe(&self) -> &'static str {
+        match self {
+            ItemKind::Function => "function",
+            ItemKind::Struct => "struct",
+            ItemKind::Enum => "enum",
+            ItemKind::Trait => "trait",
+            ItemKind::Const => "constant",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Item {
+    qualified: String,
+    kind: ItemKind,
+    is_pub: bool,
+    span: Span,
+}
+
+impl Item {
+    // Kifejezésben álló név (a típusnevekkel szemben)
+    fn is_value(&self) -> bool {
+        matches!(self.kind, ItemKind::Function | ItemKind::Const)
+    }
+}
+
+struct ModuleScope {
+    // A modul saját elemei (ezeket lehet más modulból elérni)
+    items: HashMap<String, Item>,
+    // A modulban elérhető nevek: a saját elemek és a `use`-zal behozottak
+    names: HashMap<String, Item>,
+    // `import` után a modul neve (vagy álneve) -> a modul sorszáma
+    modules: HashMap<String, (usize, Span)>,
+}
+
+impl ModuleScope {
+    fn declare(module: &Module) -> Self {
+        let qualify = |name: &str| {
+            if module.path.is_empty() { name.to_string() } else { format!("{}::{}", module.path.join("::"), name) }
+        };
+        let program = &module.program;
+        let declared = program.functions.iter().map(|def| (&def.name, ItemKind::Function, def.is_pub, def.span))
+            .chain(program.structs.iter().map(|def| (&def.name, ItemKind::Struct, def.is_pub, def.span)))
+            .chain(program.enums.iter().map(|def| (&def.name, ItemKind::Enum, def.is_pub, def.span)))
+            .chain(program.traits.iter().map(|def| (&def.name, ItemKind::Trait, def.is_pub, def.span)))
+            .chain(program.consts.iter().map(|def| (&def.name, ItemKind::Const, def.is_pub, def.span)));
+
+        let mut items = HashMap::new();
+        // Az ismételt definíciót a típusellenőrző jelenti
+        for (name, kind, is_pub, span) in declared {
+            items.entry(name.clone()).or_insert(Item { qualified: qualify(name), kind, is_pub, span });
+        }
+        Self { names: items.clone(), items, modules: HashMap::new() }
+    }
+}
+
+fn import_into(scopes: &mut [ModuleScope], modules: &[Module], index: usize, import: &Import) -> Result<(), Diagnostic> {
+    // A nem található modult a betöltés már jelentette
+    let Some(target) = modules.iter().position(|module| module.path == module_path(import)) else { return Ok(()) };
+    let name = import.local_name().to_string();
+
+    if !import.is_item {
+        if let Some((_, prev)) = scopes[index].modules.get(&name) {
+            return Err(Diagnostic::error(format!("the name `{}` is defined multiple times", name))
+                .with_code(codes::DUPLICATE_DEFINITION)
+                .with_primary(import.span, format!("`{}` reimported here", name))
+                .with_secondary(*prev, format!("previous import of `{}` here", name))
+                .with_help(format!("use `as` to import it under a different name: `import {} as masik;`", import.path.join("."))));
+        }
+        scopes[index].modules.insert(name, (target, import.span));
+        return Ok(());
+    }
+
+    let item_name = import.path.last().cloned().unwrap_or_default();
+    let item = scopes[target].items.get(&item_name).cloned().ok_or_else(|| {
+        Diagnostic::error(format!("cannot find `{}` in module `{}`", item_name, modules[target].display_path()))
+            .with_code(codes::UNRESOLVED_IMPORT)
+            .with_primary(import.span, format!("no `{}` in `{}`", item_name, modules[target].display_path()))
+    })?;
+    if let Some(prev) = scopes[index].names.get(&name) {
+        return Err(Diagnostic::error(format!("the name `{}` is defined multiple times", name))
+            .with_code(codes::DUPLICATE_DEFINITION)
+            .with_primary(import.span, format!("`{}` reimported here", name))
+            .with_secondary(prev.span, format!("previous definition of `{}` here", name))
+            .with_help(format!("use `as` to import it under a different name: `use {} as masik;`", import.path.join("."))));
+    }
+    // A privát elemet is felvesszük, így a használatai már nem okoznak újabb hibát
+    scopes[index].names.insert(name, item.clone());
+    if !item.is_pub {
+        return Err(private_item(&item, &item_name, import.span));
+    }
+    Ok(())
+}
+
+// Más modulból csak `pub` elem érhető el
+fn private_item(item: &Item, name: &str, span: Span) -> Diagnostic {
+    Diagnostic::error(format!("{} `{}` is private", item.kind.describe(), name))
+        .with_code(codes::PRIVATE_ITEM)
+        .with_primary(span, format!("private {}", item.kind.describe()))
+        .with_secondary(item.span, format!("`{}` is declared here without `pub`", name))
+}
+
+// Egy modul AST-jének átírása minősített nevekre. A helyi változók és a generikus
+// paraméterek elfedik a modulszintű neveket.
+struct Rewriter<'a> {
+    scopes: &'a [ModuleScope],
+    modules: &'a [Module],
+    current: usize,
+    locals: Vec<Vec<String>>,
+    generics: Vec<String>,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl<'a> Rewriter<'a> {
+    fn scope(&self) -> &'a ModuleScope {
+        &self.scopes[self.current]
+    }
+
+    fn program(&mut self, program: &mut Program) {
+        for def in &mut program.structs {
+            self.struct_def(def);
+        }
+        for def in &mut program.enums {
+            self.enum_def(def);
+        }
+        for def in &mut program.traits {
+            self.trait_def(def);
+        }
+        for block in &mut program.impls {
+            self.impl_block(block);
+        }
+        for def in &mut program.consts {
+            self.rename_item(&mut def.name);
+            self.const_def(def);
+        }
+        for fv in &mut program.functions {
+            self.rename_item(&mut fv.name);
+            self.function(fv, &[]);
+        }
+    }
+
+    fn const_def(&mut self, def: &mut Const) {
+        if let Some(annot) = &mut def.type_annot {
+            self.annot(annot);
+        }
+        self.expr(&mut def.value);
+    }
+
+    fn rename_item(&self, name: &mut String) {
+        if let Some(item) = self.scope().items.get(name) {
+            *name = item.qualified.clone();
+        }
+    }
+
+    fn struct_def(&mut self, def: &mut Struct) {
+        self.rename_item(&mut def.name);
+        self.with_generics(&def.generics, |rewriter| {
+            for field in &mut def.fields {
+                rewriter.annot(&mut field.type_annot);
+            }
+        });
+        self.generic_params(&mut def.generics);
+    }
+
+    fn enum_def(&mut self, def: &mut Enum) {
+        self.rename_item(&mut def.name);
+        self.with_generics(&def.generics, |rewriter| {
+            for variant in &mut def.variants {
+                for field in &mut variant.fields {
+                    rewriter.annot(field);
+                }
+            }
+        });
+        self.generic_params(&mut def.generics);
+    }
+
+    fn trait_def(&mut self, def: &mut Trait) {
+        self.rename_item(&mut def.name);
+        for method in &mut def.methods {
+            self.function(&mut method.function, &[]);
+        }
+    }
+
+    fn impl_block(&mut self, block: &mut Impl) {
+        let generics = block.generics.clone();
+        self.type_name(&mut block.type_name);
+        if let Some(trait_name) = &mut block.trait_name {
+            self.type_name(trait_name);
+        }
+        self.with_generics(&generics, |rewriter| {
+            for arg in &mut block.type_args {
+                rewriter.annot(arg);
+            }
+        });
+        self.generic_params(&mut block.generics);
+        for method in &mut block.methods {
+            self.function(method, &generics);
+        }
+    }
+
+    fn function(&mut self, fv: &mut Function, outer_generics: &[GenericParam]) {
+        let generics: Vec<GenericParam> = outer_generics.iter().chain(&fv.generics).cloned().collect();
+        self.with_generics(&generics, |rewriter| {
+            rewriter.locals.push(Vec::new());
+            rewriter.params(&mut fv.params);
+            if let Some(annot) = &mut fv.return_type {
+                rewriter.annot(annot);
+            }
+            rewriter.stmts(&mut fv.body);
+            rewriter.locals.pop();
+        });
+        self.generic_params(&mut fv.generics);
+    }
+
+    fn with_generics(&mut self, generics: &[GenericParam], visit: impl FnOnce(&mut Self)) {
+        let outer = self.generics.len();
+        self.generics.extend(generics.iter().map(|generic| generic.name.clone()));
+        visit(self);
+        self.generics.truncate(outer);
+    }
+
+    fn generic_params(&mut self, generics: &mut [GenericParam]) {
+        for generic in generics {
+            for bound in &mut generic.bounds {
+                self.type_name(bound);
+            }
+        }
+    }
+
+    fn params(&mut self, params: &mut [Param]) {
+        for param in params {
+            if let Some(annot) = &mut param.type_annot {
+                self.annot(annot);
+            }
+            self.bind(&param.name);
+        }
+    }
+
+    fn bind(&mut self, name: &str) {
+        if let Some(scope) = self.locals.last_mut() {
+            scope.push(name.to_string());
+        }
+    }
+
+    fn is_local(&self, name: &str) -> bool {
+        self.locals.iter().any(|scope| scope.iter().any(|local| local == name))
+    }
+
+    // Függvény (hívás vagy függvényérték) vagy konstans neve
+    fn value(&self, name: &mut String) {
+        if self.is_local(name) {
+            return;
+        }
+        if let Some(item) = self.scope().names.get(name).filter(|item| item.is_value()) {
+            *name = item.qualified.clone();
+        }
+    }
+
+    // Struct, enum vagy trait neve
+    fn type_name(&self, name: &mut String) {
+        if self.generics.contains(name) {
+            return;
+        }
+        if let Some(item) = self.scope().names.get(name).filter(|item| !item.is_value()) {
+            *name = item.qualified.clone();
+        }
+    }
+
+    fn annot(&mut self, annot: &mut TypeAnnot) {
+        if !annot.is_fn() {
+            self.type_name(&mut annot.name);
+        }
+        for arg in &mut annot.args {
+            self.annot(arg);
+        }
+        if let Some(ret) = &mut annot.return_type {
+            self.annot(ret);
+        }
+    }
+
+    // `modul::elem`: az importált modul nevét a típusnevek elfedik
+    // A minősített nevet adja; a nem létező vagy nem függvény elemet a típusellenőrző jelenti
+    // (a minősített névvel), a privát elem elérését itt jelentjük, de a hívás megmarad
+    fn module_item(&mut self, module_name: &str, name: &str, span: Span) -> Option<String> {
+        if self.scope().names.get(module_name).is_some_and(|item| !item.is_value()) {
+            return None;
+        }
+        let (target, _) = *self.scope().modules.get(module_name)?;
+        let qualified = format!("{}::{}", self.modules[target].path.join("::"), name);
+        if let Some(item) = self.scopes[target].items.get(name).filter(|item| !item.is_pub) {
+            self.diagnostics.push(private_item(item, name, span));
+        }
+        Some(qualified)
+    }
+
+    fn stmts(&mut self, stmts: &mut [Stmt]) {
+        self.locals.push(Vec::new());
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+        self.locals.pop();
+    }
+
+    fn stmt(&mut self, stmt: &mut Stmt) {
+        match &mut stmt.kind {
+            StmtKind::Expr(expr) => self.expr(expr),
+            StmtKind::Let { name, type_annot, value, .. } => {
+                self.expr(value);
+                if let Some(annot) = type_annot {
+                    self.annot(annot);
+                }
+                let name = name.clone();
+                self.bind(&name);
+            },
+            StmtKind::Const(def) => {
+                self.const_def(def);
+                let name = def.name.clone();
+                self.bind(&name);
+            },
+            StmtKind::Assign { target, value, .. } => {
+                self.expr(target);
+                self.expr(value);
+            },
+            StmtKind::Return(expr) => {
+                if let Some(expr) = expr {
+                    self.expr(expr);
+                }
+            },
+            StmtKind::While { condition, body } => {
+                self.expr(condition);
+                self.stmts(body);
+            },
+            StmtKind::For { pattern, iterable, body } => {
+                self.expr(iterable);
+                self.locals.push(Vec::new());
+                self.pattern(pattern);
+                for stmt in body {
+                    self.stmt(stmt);
+                }
+                self.locals.pop();
+            }
+        }
+    }
+
+    fn pattern(&mut self, pattern: &mut Pattern) {
+        match &mut pattern.kind {
+            PatternKind::Wildcard => {},
+            PatternKind::Binding(name) => {
+                let name = name.clone();
+                self.bind(&name);
+            },
+            PatternKind::Literal(expr) => self.expr(expr),
+            PatternKind::Range { start, end, .. } => {
+                self.expr(start);
+                self.expr(end);
+            },
+            PatternKind::Tuple(elements) => {
+                for element in elements {
+                    self.pattern(element);
+                }
+            },
+            PatternKind::Struct { name, fields, .. } => {
+                self.type_name(name);
+                for field in fields {
+                    self.pattern(&mut field.pattern);
+                }
+            },
+            PatternKind::Variant { enum_name, fields, .. } => {
+                self.type_name(enum_name);
+                for field in fields {
+                    self.pattern(field);
+                }
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &mut Expr) {
+        match &mut expr.kind {
+            ExprKind::Literal(_) => {},
+            ExprKind::Ident(name) => self.value(name),
+            ExprKind::Unary { operand, .. } | ExprKind::Await(operand) | ExprKind::Try(operand) => self.expr(operand),
+            ExprKind::Cast { expr, target } => {
+                self.expr(expr);
+                self.annot(target);
+            },
+            ExprKind::BinaryOp { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            },
+            ExprKind::Call { callee, args } => {
+                self.value(callee);
+                self.exprs(args);
+            },
+            ExprKind::If { condition, then_branch, else_branch } => {
+                self.expr(condition);
+                self.stmts(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.stmts(else_branch);
+                }
+            },
+            ExprKind::Interpolated(parts) => {
+                for part in parts {
+                    if let InterpPart::Expr(expr) = part {
+                        self.expr(expr);
+                    }
+                }
+            },
+            ExprKind::StructLit { name, fields } => {
+                self.type_name(name);
+                for field in fields {
+                    self.expr(&mut field.value);
+                }
+            },
+            ExprKind::Field { object, .. } => self.expr(object),
+            ExprKind::MethodCall { receiver, args, .. } => {
+                self.expr(receiver);
+                self.exprs(args);
+            },
+            // `mem::foglal(...)`: függvényhívás egy importált modulból
+            ExprKind::AssocCall { type_name, name, args } => match self.module_item(type_name, name, expr.span) {
+                Some(callee) => {
+                    let mut args = std::mem::take(args);
+                    self.exprs(&mut args);
+                    expr.kind = ExprKind::Call { callee, args };
+                },
+                None => {
+                    self.type_name(type_name);
+                    self.exprs(args);
+                }
+            },
+            // `mem::foglal` függvényértékként
+            ExprKind::Path { type_name, name } => match self.module_item(type_name, name, expr.span) {
+                Some(qualified) => expr.kind = ExprKind::Ident(qualified),
+                None => self.type_name(type_name),
+            },
+            ExprKind::Tuple(elements) | ExprKind::Array(elements) => self.exprs(elements),
+            ExprKind::Index { object, index } => {
+                self.expr(object);
+                self.expr(index);
+            },
+            ExprKind::Match { scrutinee, arms } => {
+                self.expr(scrutinee);
+                for arm in arms {
+                    self.locals.push(Vec::new());
+                    self.pattern(&mut arm.pattern);
+                    if let Some(guard) = &mut arm.guard {
+                        self.expr(guard);
+                    }
+                    self.expr(&mut arm.body);
+                    self.locals.pop();
+                }
+            },
+            ExprKind::Block(stmts) => self.stmts(stmts),
+            ExprKind::Range { start, end, .. } => {
+                self.expr(start);
+                self.expr(end);
+            },
+            ExprKind::Closure { params, return_type, body, .. } => {
+                self.locals.push(Vec::new());
+                self.params(params);
+                if let Some(annot) = return_type {
+                    self.annot(annot);
+                }
+                self.expr(body);
+                self.locals.pop();
+            }
+        }
+    }
+
+    fn exprs(&mut self, exprs: &mut [Expr]) {
+        for expr in exprs {
+            self.expr(expr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::source_map::FileId;
+
+    fn module(path: &[&str], file: u32, src: &str) -> Module {
+        let (program, diagnostics) = Parser::new(Lexer::new(src, FileId(file))).parse_program();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        Module { path: path.iter().map(|part| part.to_string()).collect(), file: FileId(file), program }
+    }
+
+    // A gyökérmodul és a `geo.pont` modul
+    fn resolve_with_geo(root: &str) -> (Program, Vec<Diagnostic>) {
+        let pont = "
+pub strukt Pont {
+    x: f64,
+    y: f64,
+}
+
+pub fv uj(x: f64, y: f64) -> Pont {
+    vissza Pont { x: x, y: y };
+}
+
+fv titkos() {}
+";
+        resolve(vec![module(&[], 0, root), module(&["geo", "pont"], 1, pont)])
+    }
+
+    #[test]
+    fn imported_items_are_qualified() {
+        let root = "
+import geo.pont;
+use geo.pont.Pont;
+
+fv main() {
+    legyen a = pont::uj(1.0, 2.0);
+    legyen b = Pont { x: 1.0, y: 2.0 };
+}
+";
+        let (program, diagnostics) = resolve_with_geo(root);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        let names: Vec<&str> = program.functions.iter().map(|def| def.name.as_str()).collect();
+        assert_eq!(names, ["main", "geo::pont::uj", "geo::pont::titkos"]);
+        assert_eq!(program.structs[0].name, "geo::pont::Pont");
+        let StmtKind::Let { value, .. } = &program.functions[0].body[0].kind else { panic!("expected `legyen`") };
+        assert!(matches!(&value.kind, ExprKind::Call { callee, .. } if callee == "geo::pont::uj"), "{:?}", value);
+    }
+
+    #[test]
+    fn private_items_are_not_visible_from_other_modules() {
+        let root = "
+import geo.pont;
+use geo.pont.titkos;
+use geo.pont.nincs;
+
+fv main() {
+    titkos();
+    pont::titkos();
+}
+";
+        let (_, diagnostics) = resolve_with_geo(root);
+        let found: Vec<_> = diagnostics.iter().map(|diag| diag.code.unwrap_or_default()).collect();
+        // A `use`-zal behozott privát név használata már nem jelent újabb hibát
+        assert_eq!(found, [codes::PRIVATE_ITEM, codes::UNRESOLVED_IMPORT, codes::PRIVATE_ITEM]);
+        assert_eq!(diagnostics[0].message, "function `titkos` is private");
+    }
+}
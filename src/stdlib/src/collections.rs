@@ -33,7 +33,7 @@ pub extern "C" fn dlang_array_new(element_size: usize, initial_capacity: usize)
     let data = unsafe {
         libc::malloc(element_size * initial_capacity) as *mut c_void
     };
-    Box:into_raw(Box::new(DlangArray {
+    Box::into_raw(Box::new(DlangArray {
         data,
         len: 0,
         capacity: initial_capacity,
@@ -45,8 +45,8 @@ pub extern "C" fn dlang_array_new(element_size: usize, initial_capacity: usize)
 pub unsafe extern "C" fn dlang_array_push(array: *mut DlangArray, element: *const c_void) {
     let array = &mut *array;
     if array.len >= array.capacity {
-        // Megduplázzuk a kapacitást, ha tele van
-        array.capacity *= 2;
+        // Megduplázzuk a kapacitást, ha tele van (az üresen létrehozott tömbnél legalább 4)
+        array.capacity = (array.capacity * 2).max(4);
         array.data = libc::realloc(array.data as *mut libc::c_void, array.element_size * array.capacity) as *mut c_void;
     }
     let offset = array.element_size * array.len;
@@ -67,6 +67,40 @@ pub unsafe extern "C" fn dlang_array_get(array: *const DlangArray, index: usize)
     (array.data as *mut u8).add(array.element_size * index) as *mut c_void
 }
 
+/// Az elemek száma
+/// # Safety
+/// Az 'array' a `dlang_array_new` által létrehozott tömbre mutat.
+#[no_mangle]
+pub unsafe extern "C" fn dlang_array_len(array: *const DlangArray) -> usize {
+    (*array).len
+}
+
+/// Az első elem címe; a következő `dlang_array_push` érvénytelenítheti
+/// # Safety
+/// Az 'array' a `dlang_array_new` által létrehozott tömbre mutat.
+#[no_mangle]
+pub unsafe extern "C" fn dlang_array_data(array: *const DlangArray) -> *mut c_void {
+    (*array).data
+}
+
+// --- Határellenőrzés (a fordító a tömbindexelés elé generálja) ---
+
+#[no_mangle]
+pub extern "C" fn dlang_panic_bounds(index: usize, len: usize) -> ! {
+    eprintln!("hiba: index out of bounds: the len is {} but the index is {}", len, index);
+    std::process::exit(101)
+}
+
+#[no_mangle]
+pub extern "C" fn dlang_panic_slice(start: usize, end: usize, len: usize) -> ! {
+    if start > end {
+        eprintln!("hiba: slice index starts at {} but ends at {}", start, end);
+    } else {
+        eprintln!("hiba: range end index {} out of range for slice of length {}", end, len);
+    }
+    std::process::exit(101)
+}
+
 #[no_mangle]
 pub extern "C" fn dlang_map_new() -> *mut DlangMap {
     let map: HashMap<String, String> = HashMap::new();
@@ -0,0 +1,269 @@
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// --- Jövők (`Jovo<T>`) ---
+
+/// Minden jövő közös fejléce. A fordító által generált állapotgépek kerete is ezzel
+/// kezdődik (lásd a codegen `FRAME_*` eltolásait), így a runtime egységesen kezeli őket.
+#[repr(C)]
+pub struct DlangFuture {
+    /// Egy lépés futtatása; `true`, ha a jövő befejeződött
+    poll: unsafe extern "C" fn(*mut DlangFuture) -> bool,
+    /// Az eredmény helye (befejeződés után olvasható)
+    result: *mut c_void,
+    /// A jövő felszabadítása (állapotgépnél a `free`)
+    drop: unsafe extern "C" fn(*mut c_void),
+    /// Az állapotgép állapota; a runtime jövői nem használják
+    state: u32
+}
+
+/// Egy lépés futtatása
+/// # Safety
+/// A 'future' egy még fel nem szabadított jövőre mutat.
+#[no_mangle]
+pub unsafe extern "C" fn dlang_future_poll(future: *mut DlangFuture) -> bool {
+    ((*future).poll)(future)
+}
+
+/// A befejeződött jövő eredményének címe
+/// # Safety
+/// A 'future' egy befejeződött, még fel nem szabadított jövőre mutat.
+#[no_mangle]
+pub unsafe extern "C" fn dlang_future_result(future: *mut DlangFuture) -> *mut c_void {
+    (*future).result
+}
+
+/// A jövő felszabadítása (az eredmény kiolvasása után)
+/// # Safety
+/// A 'future' egy még fel nem szabadított jövőre mutat.
+#[no_mangle]
+pub unsafe extern "C" fn dlang_future_free(future: *mut DlangFuture) {
+    ((*future).drop)(future as *mut c_void)
+}
+
+// --- Executor (egyszálú) ---
+
+/// Ébresztés: a levél-jövők háttérszálai és az időzítő szál ezzel jelzik, hogy érdemes újra pollolni
+static WAKEUP: (Mutex<bool>, Condvar) = (Mutex::new(false), Condvar::new());
+
+fn wake() {
+    let (lock, condvar) = &WAKEUP;
+    *lock.lock().unwrap() = true;
+    condvar.notify_one();
+}
+
+/// A jövő futtatása a befejeződéséig (mint `block_on`). Két poll között alszik,
+/// amíg egy levél-jövő el nem készül, vagy egy időzítő le nem jár.
+/// # Safety
+/// A 'future' egy még fel nem szabadított jövőre mutat; felszabadítása a hívó dolga.
+#[no_mangle]
+pub unsafe extern "C" fn dlang_executor_block_on(future: *mut DlangFuture) {
+    let (lock, condvar) = &WAKEUP;
+    loop {
+        *lock.lock().unwrap() = false;
+        if dlang_future_poll(future) {
+            return;
+        }
+        let mut woken = lock.lock().unwrap();
+        while !*woken {
+            woken = condvar.wait(woken).unwrap();
+        }
+    }
+}
+
+// --- Időzítők ---
+
+struct Timer {
+    deadline: Instant,
+    fire: Box<dyn FnOnce() + Send>
+}
+
+/// A lejáratra váró időzítők; egyetlen, első használatkor induló szál szolgálja ki őket
+static TIMERS: (Mutex<Vec<Timer>>, Condvar) = (Mutex::new(Vec::new()), Condvar::new());
+static TIMER_THREAD: Once = Once::new();
+
+/// `delay` múlva az időzítő szálon lefut a `fire`, utána az executor újra pollol
+/// (az `alszik` jövője és a `dlang_time_set_timeout` is ezt használja)
+pub fn add_timer(delay: Duration, fire: impl FnOnce() + Send + 'static) {
+    TIMER_THREAD.call_once(|| {
+        thread::spawn(run_timers);
+    });
+    let (lock, condvar) = &TIMERS;
+    lock.lock().unwrap().push(Timer { deadline: Instant::now() + delay, fire: Box::new(fire) });
+    condvar.notify_one();
+}
+
+/// Az időzítő szál: a legközelebbi lejáratig (vagy egy új időzítőig) alszik, majd a lejárt
+/// időzítőket lejárati sorrendben futtatja
+fn run_timers() {
+    let (lock, condvar) = &TIMERS;
+    let mut timers = lock.lock().unwrap();
+    loop {
+        let now = Instant::now();
+        let (mut due, pending): (Vec<Timer>, Vec<Timer>) = timers.drain(..).partition(|timer| timer.deadline <= now);
+        *timers = pending;
+        if !due.is_empty() {
+            drop(timers);
+            due.sort_by_key(|timer| timer.deadline);
+            for timer in due {
+                (timer.fire)();
+            }
+            wake();
+            timers = lock.lock().unwrap();
+            continue;
+        }
+        timers = match timers.iter().map(|timer| timer.deadline).min() {
+            Some(deadline) => condvar.wait_timeout(timers, deadline - now).unwrap().0,
+            None => condvar.wait(timers).unwrap()
+        };
+    }
+}
+
+// --- Levél-jövők (háttérszálon vagy időzítővel végzett munka) ---
+
+#[repr(C)]
+struct LeafFuture {
+    header: DlangFuture,
+    ready: Arc<AtomicBool>,
+    /// A háttérszál eredménye címként (a nyers mutató nem `Send`)
+    value: Arc<Mutex<usize>>,
+    /// Szöveges eredmény (`fajl_olvas`); a `result` erre mutat
+    output: *mut c_char
+}
+
+unsafe extern "C" fn leaf_poll(future: *mut DlangFuture) -> bool {
+    let leaf = &mut *(future as *mut LeafFuture);
+    if !leaf.ready.load(Ordering::Acquire) {
+        return false;
+    }
+    leaf.output = *leaf.value.lock().unwrap() as *mut c_char;
+    leaf.header.result = &mut leaf.output as *mut *mut c_char as *mut c_void;
+    true
+}
+
+unsafe extern "C" fn leaf_drop(future: *mut c_void) {
+    drop(Box::from_raw(future as *mut LeafFuture));
+}
+
+/// A levél-jövő befejezése a háttérből: az eredmény beírása és az executor ébresztése
+struct LeafCompleter {
+    ready: Arc<AtomicBool>,
+    value: Arc<Mutex<usize>>
+}
+
+impl LeafCompleter {
+    fn complete(self, output: *mut c_char) {
+        *self.value.lock().unwrap() = output as usize;
+        self.ready.store(true, Ordering::Release);
+        wake();
+    }
+}
+
+fn new_leaf() -> (*mut DlangFuture, LeafCompleter) {
+    let ready = Arc::new(AtomicBool::new(false));
+    let value = Arc::new(Mutex::new(0));
+    let leaf = Box::new(LeafFuture {
+        header: DlangFuture { poll: leaf_poll, result: ptr::null_mut(), drop: leaf_drop, state: 0 },
+        ready: ready.clone(),
+        value: value.clone(),
+        output: ptr::null_mut()
+    });
+    (Box::into_raw(leaf) as *mut DlangFuture, LeafCompleter { ready, value })
+}
+
+/// Háttérszálon futtatja a munkát; a jövő akkor kész, ha a munka végzett
+fn spawn_leaf(work: impl FnOnce() -> *mut c_char + Send + 'static) -> *mut DlangFuture {
+    let (future, completer) = new_leaf();
+    thread::spawn(move || completer.complete(work()));
+    future
+}
+
+/// `alszik(ms)`: a jövő `ms` milliszekundum múlva, az időzítő szálon készül el (sem a hívó
+/// szálat, sem külön szálat nem foglal)
+#[no_mangle]
+pub extern "C" fn dlang_future_sleep(ms: i64) -> *mut DlangFuture {
+    let (future, completer) = new_leaf();
+    add_timer(Duration::from_millis(ms.max(0) as u64), move || completer.complete(ptr::null_mut()));
+    future
+}
+
+/// `fajl_olvas(utvonal)`: a fájl tartalma (hiba esetén üres string)
+/// # Safety
+/// A 'path' null-terminált C stringre mutat.
+#[no_mangle]
+pub unsafe extern "C" fn dlang_future_read_file(path: *const c_char) -> *mut DlangFuture {
+    let path = CStr::from_ptr(path).to_string_lossy().into_owned();
+    spawn_leaf(move || {
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        CString::new(content).unwrap_or_default().into_raw()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Egy `aszink fv var_majd() -> i32 { akkor alszik(20); vissza 7; }` állapotgépe, a
+    // codegen kerete szerint; a `polls` a hívásait számolja
+    #[repr(C)]
+    struct Waiter {
+        header: DlangFuture,
+        sleep: *mut DlangFuture,
+        output: i32,
+        polls: u32
+    }
+
+    unsafe extern "C" fn waiter_poll(future: *mut DlangFuture) -> bool {
+        let waiter = &mut *(future as *mut Waiter);
+        waiter.polls += 1;
+        if waiter.header.state == 0 {
+            waiter.sleep = dlang_future_sleep(20);
+            waiter.header.state = 1;
+        }
+        if !dlang_future_poll(waiter.sleep) {
+            return false;
+        }
+        dlang_future_free(waiter.sleep);
+        waiter.output = 7;
+        waiter.header.result = &mut waiter.output as *mut i32 as *mut c_void;
+        true
+    }
+
+    unsafe extern "C" fn waiter_drop(_: *mut c_void) {}
+
+    #[test]
+    fn block_on_sleeps_until_the_timer_fires() {
+        let mut waiter = Waiter {
+            header: DlangFuture { poll: waiter_poll, result: ptr::null_mut(), drop: waiter_drop, state: 0 },
+            sleep: ptr::null_mut(),
+            output: 0,
+            polls: 0
+        };
+        let start = Instant::now();
+        unsafe {
+            let future = &mut waiter.header as *mut DlangFuture;
+            dlang_executor_block_on(future);
+            assert_eq!(*(dlang_future_result(future) as *const i32), 7);
+        }
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        // Az executor az időzítőre vár, nem pollol folyamatosan
+        assert!(waiter.polls <= 3, "{} polls", waiter.polls);
+    }
+
+    #[test]
+    fn timers_fire_in_deadline_order() {
+        let fired = Arc::new(Mutex::new(Vec::new()));
+        for (name, ms) in [("kesobbi", 40), ("korabbi", 10)] {
+            let fired = fired.clone();
+            add_timer(Duration::from_millis(ms), move || fired.lock().unwrap().push(name));
+        }
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(*fired.lock().unwrap(), ["korabbi", "kesobbi"]);
+    }
+}
@@ -1,7 +1,35 @@
+use std::cell::RefCell;
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 use std::io::{self, Write};
 
+thread_local! {
+    // A legutóbbi sikertelen I/O művelet oka (lásd `dlang_last_error`)
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+fn set_last_error(message: String) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+}
+
+// C string a visszaadott szövegből; a belső NUL bájtot nem tudja ábrázolni, ekkor null
+fn into_c_string(text: &str, what: &str) -> *mut c_char {
+    match CString::new(text) {
+        Ok(text) => text.into_raw(),
+        Err(err) => {
+            set_last_error(format!("{} contains a NUL byte at position {}", what, err.nul_position()));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Az adott szálon legutóbb sikertelen (null-t vagy 'false'-t visszaadó) I/O művelet hibaüzenete;
+/// a fordító ebből építi az `Eredmeny::Hiba` értéket
+#[no_mangle]
+pub extern "C" fn dlang_last_error() -> *mut c_char {
+    LAST_ERROR.with(|last| CString::new(last.borrow().as_str()).unwrap_or_default().into_raw())
+}
+
 /// Kiír egy karakterláncot a standard kimenetre (nincs sortörés)
 /// # Safety
 /// A 'message' null-terminált C stringre mutat.
@@ -50,14 +78,21 @@ pub unsafe extern "C" fn dlang_printf(format: *const c_char) -> *mut c_char {
 
 /// Beolvas egy sort a standard bemenetről.
 /// # Return
-/// A beolvasott string (null-terminált), vagy 'null' hiba esetén.
+/// A beolvasott string (null-terminált), vagy 'null' hiba és a bemenet vége esetén.
 #[no_mangle]
 pub extern "C" fn dlang_readline() -> *mut c_char {
     let mut input = String::new();
-    if io::stdin().read_line(&mut input).is_err() {
-        return std::ptr::null_mut();
+    match io::stdin().read_line(&mut input) {
+        Ok(0) => {
+            set_last_error("end of input".to_string());
+            std::ptr::null_mut()
+        },
+        Ok(_) => into_c_string(input.trim(), "the line"),
+        Err(err) => {
+            set_last_error(format!("cannot read from standard input: {}", err));
+            std::ptr::null_mut()
+        }
     }
-    CString::new(input.trim()).unwrap().into_raw()
 }
 
 /// Felszabadít egy dinamikusan lefoglalt stringet
@@ -79,12 +114,18 @@ pub unsafe extern "C" fn dlang_free_string(ptr: *mut c_char) {
 pub unsafe extern "C" fn dlang_readfile(path: *const c_char) -> *mut c_char {
     let path_str = match CStr::from_ptr(path).to_str() {
         Ok(s) => s,
-        Err(_) => return std::ptr::null_mut()
+        Err(_) => {
+            set_last_error("the path is not valid UTF-8".to_string());
+            return std::ptr::null_mut();
+        }
     };
 
     match std::fs::read_to_string(path_str) {
-        Ok(content) => CString::new(content).unwrap().into_raw(),
-        Err(_) => std::ptr::null_mut()
+        Ok(content) => into_c_string(&content, &format!("`{}`", path_str)),
+        Err(err) => {
+            set_last_error(format!("cannot read `{}`: {}", path_str, err));
+            std::ptr::null_mut()
+        }
     }
 }
 
@@ -95,13 +136,42 @@ pub unsafe extern "C" fn dlang_readfile(path: *const c_char) -> *mut c_char {
 pub unsafe extern "C" fn dlang_writefile(path: *const c_char, content: *const c_char) -> bool {
     let path_str = match CStr::from_ptr(path).to_str() {
         Ok(s) => s,
-        Err(_) => return false
+        Err(_) => {
+            set_last_error("the path is not valid UTF-8".to_string());
+            return false;
+        }
     };
 
     let content_str = match CStr::from_ptr(content).to_str() {
         Ok(s) => s,
-        Err(_) => return false
+        Err(_) => {
+            set_last_error("the content is not valid UTF-8".to_string());
+            return false;
+        }
     };
 
-    std::fs::write(path_str, content_str).is_ok()
-}
\ No newline at end of file
+    match std::fs::write(path_str, content_str) {
+        Ok(()) => true,
+        Err(err) => {
+            set_last_error(format!("cannot write `{}`: {}", path_str, err));
+            false
+        }
+    }
+}
+
+/// `panik(uzenet)`: hibaüzenet kiírása és a program leállítása
+/// # Safety
+/// A 'message' null-terminált C stringre mutat.
+#[no_mangle]
+pub unsafe extern "C" fn dlang_panic(message: *const c_char) -> ! {
+    let _ = io::stdout().flush();
+    eprintln!("hiba: {}", CStr::from_ptr(message).to_string_lossy());
+    std::process::exit(101)
+}
+
+/// `kilep(kod)`: a program leállítása a megadott kilépési kóddal
+#[no_mangle]
+pub extern "C" fn dlang_exit(code: i32) -> ! {
+    let _ = io::stdout().flush();
+    std::process::exit(code)
+}
@@ -53,6 +53,61 @@ pub unsafe extern "C" fn dlang_string_concat(
     DlangString::from_rust_string(s1 + s2).into_raw()
 }
 
+// --- Szöveggé alakítás (string-interpolációhoz) ---
+
+/// Előjeles egész számot alakít Dlang stringgé
+#[no_mangle]
+pub extern "C" fn dlang_string_from_i64(value: i64) -> *mut DlangString {
+    Box::into_raw(Box::new(DlangString::from_rust_string(value.to_string())))
+}
+
+/// Előjel nélküli egész számot alakít Dlang stringgé
+#[no_mangle]
+pub extern "C" fn dlang_string_from_u64(value: u64) -> *mut DlangString {
+    Box::into_raw(Box::new(DlangString::from_rust_string(value.to_string())))
+}
+
+/// 128 bites előjeles egész számot alakít Dlang stringgé; az érték két 64 bites félként érkezik
+#[no_mangle]
+pub extern "C" fn dlang_string_from_i128(low: u64, high: u64) -> *mut DlangString {
+    let value = (((high as u128) << 64) | low as u128) as i128;
+    Box::into_raw(Box::new(DlangString::from_rust_string(value.to_string())))
+}
+
+/// 128 bites előjel nélküli egész számot alakít Dlang stringgé; az érték két 64 bites félként érkezik
+#[no_mangle]
+pub extern "C" fn dlang_string_from_u128(low: u64, high: u64) -> *mut DlangString {
+    let value = ((high as u128) << 64) | low as u128;
+    Box::into_raw(Box::new(DlangString::from_rust_string(value.to_string())))
+}
+
+/// Lebegőpontos számot alakít Dlang stringgé
+#[no_mangle]
+pub extern "C" fn dlang_string_from_f64(value: f64) -> *mut DlangString {
+    Box::into_raw(Box::new(DlangString::from_rust_string(value.to_string())))
+}
+
+/// Logikai értéket alakít Dlang stringgé (`igaz` / `hamis`)
+#[no_mangle]
+pub extern "C" fn dlang_string_from_bool(value: bool) -> *mut DlangString {
+    let text = if value { "igaz" } else { "hamis" };
+    Box::into_raw(Box::new(DlangString::from_rust_string(text.to_string())))
+}
+
+/// Unicode skalárértéket alakít egykarakteres Dlang stringgé
+#[no_mangle]
+pub extern "C" fn dlang_string_from_char(value: u32) -> *mut DlangString {
+    let c = char::from_u32(value).unwrap_or(char::REPLACEMENT_CHARACTER);
+    Box::into_raw(Box::new(DlangString::from_rust_string(c.to_string())))
+}
+
+/// A Dlang stringet C stringgé alakítja, és felszabadítja a Dlang stringet
+#[no_mangle]
+pub unsafe extern "C" fn dlang_string_into_cstr(s: *mut DlangString) -> *mut c_char {
+    let boxed = Box::from_raw(s);
+    boxed.data
+}
+
 /// Kivág egy részt a stringből (mint JavaScript `slice`)
 #[no_mangle]
 pub unsafe extern "C" fn dlang_string_slice(
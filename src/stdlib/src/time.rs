@@ -5,6 +5,7 @@ use std::thread;
 use std::sync::{Arc, Mutex};
 use chrono::{DateTime, NaiveDateTime, TimeZone, Local};
 use chrono_tz::Tz;
+use crate::executor::add_timer;
 
 // --- Globális időzóna (thread-local storage) ---
 thread_local! {
@@ -100,17 +101,17 @@ pub unsafe extern "C" fn dlang_time_format(
 
 // --- Időzítők ---
 
-/// Időzítő callbackkel (mint setTimeout(), nem blokkoló)
+/// Időzítő callbackkel (mint setTimeout(), nem blokkoló). Az executor időzítő szála hívja,
+/// utána a `futtat` alatt álló jövő újra pollolódik.
 #[no_mangle]
 pub unsafe extern "C" fn dlang_time_set_timeout(
     callback: extern "C" fn(*mut c_char),
     ms: i64,
     data: *mut c_char
 ) {
-    thread::spawn(move || {
-        thread::sleep(Duration::from_millis(ms as u64));
-        callback(data);
-    });
+    // A nyers mutató nem `Send`, címként kerül át
+    let data = data as usize;
+    add_timer(Duration::from_millis(ms.max(0) as u64), move || callback(data as *mut c_char));
 }
 
 // --- Időzóna kezelés ---
@@ -1,166 +1,3367 @@
-use crate::ast::{Program, Function, Stmt, Expr, Literal, BinaryOp};
-use std::collections::HashMap;
+use crate::ast::{Program, Const, Function, GenericParam, Impl, Struct, Enum, Trait, TypeAnnot, Param, Stmt, StmtKind, Expr, ExprKind, InterpPart, Literal, Pattern, PatternKind, BinaryOp, UnaryOp, IntTy, FloatTy, NEVER_TYPE, UNIT_TYPE};
+use crate::captures;
+use crate::consteval::{self, ConstValue};
+use crate::diagnostic::{codes, Diagnostic};
+use crate::exhaustiveness::{self, Matcher};
+use crate::source_map::{FileId, Span};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Type {
-    Int,
-    Float,
+    Int(IntTy),
+    Float(FloatTy),
     Bool,
+    Char,
     String,
+    ByteString,
+    Void,
+    // A soha vissza nem térő hívás (`panik`, `kilep`) típusa; bármely típus helyén állhat
+    Never,
     Function(Vec<Type>, Box<Type>),
+    Tuple(Vec<Type>),
+    Range(Box<Type>),
+    // `[T; N]`: rögzített méretű tömb, értékként másolódik
+    Array(Box<Type>, u64),
+    // `[T]`: tömb vagy `Tomb<T>` egy szakaszára mutató nézet (kezdőcím és hossz)
+    Slice(Box<Type>),
+    // Nominális típus: két struct csak azonos név (és típusargumentumok) esetén egyezik
+    Struct(String, Vec<Type>),
+    Enum(String, Vec<Type>),
+    // Generikus paraméter a generikus függvény vagy típus törzsében
+    Param(String),
+    // Trait objektum: az értéke a traitet implementáló bármely típusé lehet
+    Dyn(String),
+    // Kikövetkeztetendő típus (típusváltozó); a kötését a `TypeChecker::type_vars` tárolja
+    Var(u32),
     Error
 }
 
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Int(ty) => write!(f, "{}", ty.name()),
+            Type::Float(ty) => write!(f, "{}", ty.name()),
+            Type::Bool => write!(f, "bool"),
+            Type::Char => write!(f, "char"),
+            Type::String => write!(f, "str"),
+            Type::ByteString => write!(f, "bytes"),
+            Type::Void => write!(f, "()"),
+            Type::Never => write!(f, "!"),
+            Type::Function(params, ret) => {
+                let params: Vec<String> = params.iter().map(|p| p.to_string()).collect();
+                write!(f, "fv({}) -> {}", params.join(", "), ret)
+            },
+            Type::Tuple(elements) => {
+                let elements: Vec<String> = elements.iter().map(|e| e.to_string()).collect();
+                write!(f, "({})", elements.join(", "))
+            },
+            Type::Range(element) => write!(f, "Range<{}>", element),
+            Type::Array(element, len) => write!(f, "[{}; {}]", element, len),
+            Type::Slice(element) => write!(f, "[{}]", element),
+            Type::Struct(name, args) | Type::Enum(name, args) => {
+                write!(f, "{}", name)?;
+                if !args.is_empty() {
+                    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+                    write!(f, "<{}>", args.join(", "))?;
+                }
+                Ok(())
+            },
+            Type::Param(name) => write!(f, "{}", name),
+            Type::Dyn(trait_name) => write!(f, "dyn {}", trait_name),
+            Type::Var(_) => write!(f, "_"),
+            Type::Error => write!(f, "{{unknown}}")
+        }
+    }
+}
+
+impl Type {
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Type::Int(_) | Type::Float(_))
+    }
+
+    pub fn is_dyn_array(&self) -> bool {
+        matches!(self, Type::Struct(name, _) if name == DYN_ARRAY_TYPE)
+    }
+
+    // Szöveggé alakítható-e (string-interpolációban)
+    pub fn is_printable(&self) -> bool {
+        matches!(self, Type::Int(_) | Type::Float(_) | Type::Bool | Type::Char | Type::String)
+    }
+
+    // A generikus paraméterek helyettesítése (`Lista<T>` -> `Lista<i32>`)
+    pub fn substitute(&self, args: &HashMap<String, Type>) -> Type {
+        let all = |types: &[Type]| types.iter().map(|ty| ty.substitute(args)).collect();
+        match self {
+            Type::Param(name) => args.get(name).cloned().unwrap_or_else(|| self.clone()),
+            Type::Function(params, ret) => Type::Function(all(params), Box::new(ret.substitute(args))),
+            Type::Tuple(elements) => Type::Tuple(all(elements)),
+            Type::Range(element) => Type::Range(Box::new(element.substitute(args))),
+            Type::Array(element, len) => Type::Array(Box::new(element.substitute(args)), *len),
+            Type::Slice(element) => Type::Slice(Box::new(element.substitute(args))),
+            Type::Struct(name, type_args) => Type::Struct(name.clone(), all(type_args)),
+            Type::Enum(name, type_args) => Type::Enum(name.clone(), all(type_args)),
+            _ => self.clone()
+        }
+    }
+
+    pub fn has_params(&self) -> bool {
+        match self {
+            Type::Param(_) => true,
+            Type::Function(params, ret) => params.iter().any(Type::has_params) || ret.has_params(),
+            Type::Tuple(types) | Type::Struct(_, types) | Type::Enum(_, types) => types.iter().any(Type::has_params),
+            Type::Range(element) | Type::Array(element, _) | Type::Slice(element) => element.has_params(),
+            _ => false
+        }
+    }
+
+    // A típusváltozók cseréje (`f` a változó sorszámából adja a helyére kerülő típust)
+    pub fn map_vars(&self, f: &mut impl FnMut(u32) -> Type) -> Type {
+        let mut all = |types: &[Type]| types.iter().map(|ty| ty.map_vars(f)).collect();
+        match self {
+            Type::Var(var) => f(*var),
+            Type::Function(params, ret) => {
+                let params = all(params);
+                Type::Function(params, Box::new(ret.map_vars(f)))
+            },
+            Type::Tuple(elements) => Type::Tuple(all(elements)),
+            Type::Struct(name, type_args) => Type::Struct(name.clone(), all(type_args)),
+            Type::Enum(name, type_args) => Type::Enum(name.clone(), all(type_args)),
+            Type::Range(element) => Type::Range(Box::new(element.map_vars(f))),
+            Type::Array(element, len) => Type::Array(Box::new(element.map_vars(f)), *len),
+            Type::Slice(element) => Type::Slice(Box::new(element.map_vars(f))),
+            _ => self.clone()
+        }
+    }
+
+    // Tartalmaz-e már jelentett hibából eredő típust
+    pub fn has_error(&self) -> bool {
+        match self {
+            Type::Error => true,
+            Type::Function(params, ret) => params.iter().any(Type::has_error) || ret.has_error(),
+            Type::Tuple(types) | Type::Struct(_, types) | Type::Enum(_, types) => types.iter().any(Type::has_error),
+            Type::Range(element) | Type::Array(element, _) | Type::Slice(element) => element.has_error(),
+            _ => false
+        }
+    }
+
+    // A benne előforduló típusváltozók sorszámai
+    pub fn vars(&self) -> Vec<u32> {
+        let mut vars = Vec::new();
+        self.map_vars(&mut |var| {
+            vars.push(var);
+            Type::Var(var)
+        });
+        vars
+    }
+
+    // Előfordul-e benne a megadott generikus paraméter
+    pub fn mentions(&self, param: &str) -> bool {
+        match self {
+            Type::Param(name) => name == param,
+            Type::Function(params, ret) => params.iter().any(|ty| ty.mentions(param)) || ret.mentions(param),
+            Type::Tuple(types) | Type::Struct(_, types) | Type::Enum(_, types) => types.iter().any(|ty| ty.mentions(param)),
+            Type::Range(element) | Type::Array(element, _) | Type::Slice(element) => element.mentions(param),
+            _ => false
+        }
+    }
+}
+
+// A `pattern` generikus paramétereinek megfeleltetése az `actual` típus részeinek;
+// hamis, ha a kettő nem illeszthető össze
+fn bind_params(pattern: &Type, actual: &Type, subst: &mut HashMap<String, Type>) -> bool {
+    let all = |patterns: &[Type], actuals: &[Type], subst: &mut HashMap<String, Type>| {
+        patterns.len() == actuals.len() && patterns.iter().zip(actuals).all(|(p, a)| bind_params(p, a, subst))
+    };
+    match (pattern, actual) {
+        (_, Type::Error) => true,
+        (Type::Param(name), _) => match subst.get(name) {
+            Some(bound) => bound == actual,
+            None => {
+                subst.insert(name.clone(), actual.clone());
+                true
+            }
+        },
+        (Type::Struct(a, pattern_args), Type::Struct(b, actual_args))
+        | (Type::Enum(a, pattern_args), Type::Enum(b, actual_args)) => a == b && all(pattern_args, actual_args, subst),
+        (Type::Tuple(patterns), Type::Tuple(actuals)) => all(patterns, actuals, subst),
+        (Type::Range(pattern), Type::Range(actual)) | (Type::Slice(pattern), Type::Slice(actual)) => bind_params(pattern, actual, subst),
+        (Type::Array(pattern, pattern_len), Type::Array(actual, actual_len)) => pattern_len == actual_len && bind_params(pattern, actual, subst),
+        (Type::Function(pattern_params, pattern_ret), Type::Function(actual_params, actual_ret)) => {
+            all(pattern_params, actual_params, subst) && bind_params(pattern_ret, actual_ret, subst)
+        },
+        _ => pattern == actual
+    }
+}
+
+pub fn primitive_type(name: &str) -> Option<Type> {
+    if let Some(ty) = IntTy::from_name(name) {
+        return Some(Type::Int(ty));
+    }
+    if let Some(ty) = FloatTy::from_name(name) {
+        return Some(Type::Float(ty));
+    }
+    match name {
+        "bool" => Some(Type::Bool),
+        "char" => Some(Type::Char),
+        "str" => Some(Type::String),
+        "bytes" => Some(Type::ByteString),
+        NEVER_TYPE => Some(Type::Never),
+        UNIT_TYPE => Some(Type::Void),
+        _ => None
+    }
+}
+
+// A prelude traitjei (rendezés és szöveggé alakítás); a primitív típusok beépítve implementálják őket
+pub const BOUND_ORD: &str = "Osszehasonlithato";
+pub const ORD_METHOD: &str = "osszehasonlit";
+pub const BOUND_DISPLAY: &str = "Kiirhato";
+pub const DISPLAY_METHOD: &str = "kiir";
+
+// Traitben és impl blokkban az implementáló típus neve
+pub const SELF_TYPE: &str = "Self";
+
+// A runtime által megvalósított beépített függvények; a kódgenerálás külön kezeli őket
+pub const BUILTIN_PRINT: &str = "print";
+// `szal_indit(f: fv()) -> Szal`: a lezárást új szálon futtatja (`dlang_thread_spawn`)
+pub const BUILTIN_SPAWN: &str = "szal_indit";
+// `szal_bevar(szal: Szal)`: megvárja a szál végét (`dlang_thread_join`)
+pub const BUILTIN_JOIN: &str = "szal_bevar";
+// A prelude-ban deklarált szálazonosító típus
+pub const THREAD_TYPE: &str = "Szal";
+// `aszink fv` eredménye (prelude), `Jovo<T>`
+pub const FUTURE_TYPE: &str = "Jovo";
+// `futtat(j: Jovo<T>) -> T`: a jövőt az executoron a befejeződéséig futtatja
+pub const BUILTIN_BLOCK_ON: &str = "futtat";
+// `alszik(ms: i64) -> Jovo<()>`: időzítő (`dlang_time_set_timeout`)
+pub const BUILTIN_SLEEP: &str = "alszik";
+// `fajl_olvas(ut: str) -> Jovo<str>`: a fájl tartalma, háttérszálon beolvasva
+pub const BUILTIN_READ_FILE: &str = "fajl_olvas";
+// `panik(uzenet: str) -> !`: hibaüzenettel leállítja a programot (`dlang_panic`)
+pub const BUILTIN_PANIC: &str = "panik";
+// `kilep(kod: i32) -> !`: a megadott kilépési kóddal leállítja a programot (`dlang_exit`)
+pub const BUILTIN_EXIT: &str = "kilep";
+
+// Esetleg hiányzó érték (prelude), `Opcio<T>`: `Opcio::Van(x)` vagy `Opcio::Nincs`
+pub const OPTION_TYPE: &str = "Opcio";
+pub const OPTION_SOME: &str = "Van";
+pub const OPTION_NONE: &str = "Nincs";
+// Sikeres eredmény vagy hiba (prelude), `Eredmeny<T, E>`: `Eredmeny::Rendben(x)` vagy `Eredmeny::Hiba(e)`
+pub const RESULT_TYPE: &str = "Eredmeny";
+pub const RESULT_OK: &str = "Rendben";
+pub const RESULT_ERR: &str = "Hiba";
+// `sor_olvas() -> Opcio<str>`: egy sor a standard bemenetről (`dlang_readline`)
+pub const BUILTIN_READ_LINE: &str = "sor_olvas";
+// `fajl_beolvas(ut: str) -> Eredmeny<str, str>`: a fájl tartalma (`dlang_readfile`), hibánál az oka
+pub const BUILTIN_LOAD_FILE: &str = "fajl_beolvas";
+// `fajl_ir(ut: str, tartalom: str) -> Eredmeny<(), str>`: a fájl felülírása (`dlang_writefile`)
+pub const BUILTIN_WRITE_FILE: &str = "fajl_ir";
+
+pub fn option_type(value: Type) -> Type {
+    Type::Enum(OPTION_TYPE.to_string(), vec![value])
+}
+
+pub fn result_type(value: Type, error: Type) -> Type {
+    Type::Enum(RESULT_TYPE.to_string(), vec![value, error])
+}
+
+pub fn future_type(output: Type) -> Type {
+    Type::Struct(FUTURE_TYPE.to_string(), vec![output])
+}
+
+// Dinamikus tömb (prelude), `Tomb<T>`; a `[...]` literál ennek a helyén is állhat
+pub const DYN_ARRAY_TYPE: &str = "Tomb";
+// Tömbök, szeletek és `Tomb<T>` hossza: `t.len() -> usize`
+pub const ARRAY_LEN: &str = "len";
+// `t.hozzaad(x)`: elem hozzáfűzése a `Tomb<T>` végéhez (`dlang_array_push`)
+pub const ARRAY_PUSH: &str = "hozzaad";
+
+// Az indexelhető (és `szor` ciklussal bejárható) tömbszerű típusok elemtípusa
+pub fn array_element(ty: &Type) -> Option<Type> {
+    match ty {
+        Type::Array(element, _) | Type::Slice(element) => Some((**element).clone()),
+        Type::Struct(name, args) if name == DYN_ARRAY_TYPE => args.first().cloned(),
+        _ => None
+    }
+}
+
+// A `szor` bejárási protokollja: `darab(self) -> usize` és `elem(self, index: usize) -> T`
+pub const ITER_COUNT: &str = "darab";
+pub const ITER_GET: &str = "elem";
+// Tartomány lépésköze: `(0..10).lepes(2)`
+pub const RANGE_STEP: &str = "lepes";
+
+// Utótag nélküli (esetleg negált) szám literál, amelynek típusa a környezetből adódik
+fn is_untyped_literal(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Literal(Literal::Int(_, None)) | ExprKind::Literal(Literal::Float(_, None)) => true,
+        ExprKind::Unary { op: UnaryOp::Neg, operand } => is_untyped_literal(operand),
+        _ => false
+    }
+}
+
+// Kifejezések típusa a tartományuk szerint (a kódgenerálás ebből választ műveletet)
+pub type TypeTable = HashMap<Span, Type>;
+// Generikus hívások típusargumentumai a hívás tartománya szerint; metódusnál az impl
+// blokk paraméterei után a metódus sajátjai következnek
+pub type Instantiations = HashMap<Span, Vec<Type>>;
+// `dyn Trait` típusra alakítandó kifejezések tartománya -> a cél objektumtípus
+pub type Coercions = HashMap<Span, Type>;
+// Lezárás tartománya -> az elkapott helyi változók neve az első használat sorrendjében
+pub type Captures = HashMap<Span, Vec<String>>;
+// `konst` deklaráció tartománya -> a fordítási időben kiszámolt érték
+pub type ConstValues = HashMap<Span, ConstValue>;
+
+#[derive(Debug, Clone)]
+struct Method {
+    // A `self` paramétert is tartalmazó `Type::Function`
+    signature: Type,
+    takes_self: bool,
+    // Az impl blokk, majd a metódus generikus paraméterei
+    generics: Vec<GenericParam>,
+    impl_generics: usize,
+    span: Span
+}
+
+#[derive(Debug, Clone)]
+struct TraitInfo {
+    // A metódusok a deklaráció sorrendjében; az aláírásukban az implementáló típus `Type::Param("Self")`
+    methods: Vec<(String, Method)>,
+    // Az alapértelmezett törzzsel rendelkező metódusok
+    provided: Vec<String>,
+    span: Span
+}
+
+// Miért nem hívható a trait metódusa objektumon (`dyn Trait`) keresztül
+fn object_safety_violation(method: &Method) -> Option<&'static str> {
+    let Type::Function(params, ret) = &method.signature else { return None };
+    if !method.takes_self {
+        Some("has no `self` parameter")
+    } else if method.generics.len() > method.impl_generics {
+        Some("has generic type parameters")
+    } else if params[1..].iter().any(|ty| ty.mentions(SELF_TYPE)) || ret.mentions(SELF_TYPE) {
+        Some("references the `Self` type in its parameters or return type")
+    } else {
+        None
+    }
+}
+
+// Típusváltozóra kimondott megkötés, amely csak a típus ismeretében ellenőrizhető
+#[derive(Debug, Clone)]
+enum Pending {
+    // Aritmetikai operandus
+    Numeric,
+    // Trait korlát (összehasonlítás, interpoláció, generikus hívás)
+    Bound(String)
+}
+
+// Helyi kötés: a hatókörében látható név típusa és a módosíthatóság követése
+#[derive(Debug, Clone)]
+struct Binding {
+    name: String,
+    ty: Type,
+    kind: BindingKind,
+    span: Span,
+    // Kapott-e új értéket a deklaráció után (csak `var` esetén számít)
+    mutated: bool,
+    // Ha az érték (vagy egy része) hivatkozással elkapó lezárás: az általa elkapott változó
+    borrows: Option<String>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BindingKind {
+    // `var`
+    Mutable,
+    // `legyen`
+    Immutable,
+    // Függvény- vagy lezárásparaméter, minta kötése
+    Param,
+    Const
+}
+
+// Az értékadás bal oldalának gyökérváltozója (`p.a.b` -> `p`); None, ha a kifejezés nem hely
+fn place_root(expr: &Expr) -> Option<&str> {
+    match &expr.kind {
+        ExprKind::Ident(name) => Some(name),
+        ExprKind::Field { object, .. } | ExprKind::Index { object, .. } => place_root(object),
+        _ => None
+    }
+}
+
+// Két név szerkesztési (Levenshtein) távolsága
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+// A `name`-hez legközelebbi jelölt, ha elég közel van (a név hosszának harmadáig, de legalább
+// egy szerkesztés, és nem cserélődik ki az egész név); egyenlő távolságnál az ábécérendben első, hogy a javaslat determinisztikus legyen
+fn similar_name<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let threshold = (name.chars().count() / 3).max(1);
+    candidates
+        .filter(|candidate| *candidate != name && !candidate.contains("::"))
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= threshold && *distance < name.len().min(candidate.len()))
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+const PRIMITIVE_NAMES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+    "f32", "f64", "bool", "char", "str", "bytes"
+];
+
+// Különböző számtípusok nem keverednek implicit módon: az `as` átalakítás javaslata
+fn cast_help(diag: Diagnostic, value: &Expr, found: &Type, expected: &Type) -> Diagnostic {
+    if !found.is_numeric() || !expected.is_numeric() || found == expected {
+        return diag;
+    }
+    let diag = match place_text(value).as_str() {
+        "_" => diag.with_help(format!("numeric types are not converted implicitly; use `as` to convert the value to `{}`", expected)),
+        text => diag.with_help(format!("you can convert a `{}` to a `{}`: `{} as {}`", found, expected, text, expected))
+    };
+    let lossless = match (found, expected) {
+        (Type::Int(from), Type::Int(to)) => to.contains(*from),
+        (_, Type::Int(_)) => false,
+        _ => true
+    };
+    if lossless {
+        diag
+    } else {
+        diag.with_note(format!("the conversion panics at run time if the value does not fit in `{}`", expected))
+    }
+}
+
+fn place_text(expr: &Expr) -> String {
+    match &expr.kind {
+        ExprKind::Field { object, field } => format!("{}.{}", place_text(object), field),
+        ExprKind::Index { object, .. } => format!("{}[..]", place_text(object)),
+        ExprKind::Ident(name) => name.clone(),
+        _ => "_".to_string()
+    }
+}
+
 pub struct TypeChecker {
-    symbols: HashMap<String, Type>
+    symbols: HashMap<String, Type>,
+    // struct neve -> mezők a deklaráció sorrendjében
+    structs: HashMap<String, Vec<(String, Type)>>,
+    // enum neve -> változatok (név, adatmezők típusai) a deklaráció sorrendjében
+    enums: HashMap<String, Vec<(String, Vec<Type>)>>,
+    // struct vagy enum neve -> generikus paraméterei
+    type_generics: HashMap<String, Vec<GenericParam>>,
+    // generikus függvény neve -> generikus paraméterei
+    generic_fns: HashMap<String, Vec<GenericParam>>,
+    // Az éppen ellenőrzött deklaráció generikus paraméterei és korlátaik
+    type_params: HashMap<String, Vec<String>>,
+    instantiations: Instantiations,
+    // típus neve -> metódus neve -> metódus
+    methods: HashMap<String, HashMap<String, Method>>,
+    traits: HashMap<String, TraitInfo>,
+    // (típus neve, trait neve) -> az impl blokk generikus paraméterei és tartománya
+    trait_impls: HashMap<(String, String), (Vec<GenericParam>, Span)>,
+    // A `Self` jelentése az éppen ellenőrzött traitben vagy impl blokkban
+    self_type: Option<Type>,
+    coercions: Coercions,
+    captures: Captures,
+    // A készülő lezárások `vissza` utasításainak típusa (a legbelső lezárás az utolsó)
+    closure_returns: Vec<Vec<(Type, Span)>>,
+    // `aszink` függvény törzsét ellenőrizzük-e (a lezárások törzse sosem aszinkron)
+    in_async: bool,
+    // Az ellenőrzött függvény visszatérési típusa (`aszink` függvénynél a jövő eredménye) és jelölése
+    fn_return: Option<(Type, Option<Span>)>,
+    // A modulszintű konstansok értéke név szerint
+    consts: HashMap<String, ConstValue>,
+    const_values: ConstValues,
+    // A körülvevő lexikális hatókörök (függvény, blokk, ciklus, `eset` ág, lezárás) kötései
+    // a deklaráció sorrendjében; a legbelső hatókör az utolsó
+    scopes: Vec<Vec<Binding>>,
+    // A típusváltozók kötése sorszám szerint; None, amíg az egyesítés nem köti meg
+    type_vars: Vec<Option<Type>>,
+    // Jelölés nélküli kötések (név, típus, tartomány): a program végére a típusuk ismert kell legyen
+    inferred: Vec<(String, Type, Span)>,
+    pending: Vec<(Type, Pending, Span)>,
+    expr_types: TypeTable,
+    // A beépített forrás (prelude) fájlja, és az onnan származó, a program által el nem fedett típusok
+    prelude: Option<FileId>,
+    builtin_types: HashSet<String>,
+    diagnostics: Vec<Diagnostic>
 }
 
 impl TypeChecker {
     pub fn new() -> Self {
         Self {
-            symbols: HashMap::new()
+            symbols: HashMap::new(),
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            type_generics: HashMap::new(),
+            generic_fns: HashMap::new(),
+            type_params: HashMap::new(),
+            instantiations: HashMap::new(),
+            methods: HashMap::new(),
+            traits: HashMap::new(),
+            trait_impls: HashMap::new(),
+            self_type: None,
+            coercions: HashMap::new(),
+            captures: HashMap::new(),
+            closure_returns: Vec::new(),
+            in_async: false,
+            fn_return: None,
+            consts: HashMap::new(),
+            const_values: HashMap::new(),
+            scopes: Vec::new(),
+            type_vars: Vec::new(),
+            inferred: Vec::new(),
+            pending: Vec::new(),
+            expr_types: HashMap::new(),
+            prelude: None,
+            builtin_types: HashSet::new(),
+            diagnostics: Vec::new()
         }
     }
-    
-    pub fn check_program(&mut self, program: &Program) -> Result<(), String> {
+
+    // A `file` tartományába eső típusokat (`Opcio`, `Jovo`, ...) ismerik a beépített függvények és a `?`
+    pub fn with_prelude(mut self, file: FileId) -> Self {
+        self.prelude = Some(file);
+        self
+    }
+
+    fn is_builtin_type(&self, name: &str) -> bool {
+        self.builtin_types.contains(name)
+    }
+
+    pub fn take_expr_types(&mut self) -> TypeTable {
+        std::mem::take(&mut self.expr_types)
+    }
+
+    pub fn take_instantiations(&mut self) -> Instantiations {
+        std::mem::take(&mut self.instantiations)
+    }
+
+    pub fn take_coercions(&mut self) -> Coercions {
+        std::mem::take(&mut self.coercions)
+    }
+
+    pub fn take_captures(&mut self) -> Captures {
+        std::mem::take(&mut self.captures)
+    }
+
+    pub fn take_const_values(&mut self) -> ConstValues {
+        std::mem::take(&mut self.const_values)
+    }
+
+    // Az összes függvényt ellenőrzi, és visszaadja az összegyűjtött hibákat
+    pub fn check_program(&mut self, program: &Program) -> Vec<Diagnostic> {
+        // Először csak a neveket vesszük fel, hogy a mezők hivatkozhassanak később deklarált típusokra
+        let mut declared: HashMap<&str, Span> = HashMap::new();
+        let type_names: Vec<(&String, Span)> = program.structs.iter().map(|def| (&def.name, def.span))
+            .chain(program.enums.iter().map(|def| (&def.name, def.span)))
+            .collect();
+        for &(name, span) in &type_names {
+            if let Some(&prev) = declared.get(name.as_str()) {
+                self.diagnostics.push(Diagnostic::error(format!("the name `{}` is defined multiple times", name))
+                    .with_code(codes::DUPLICATE_DEFINITION)
+                    .with_primary(span, format!("`{}` redefined here", name))
+                    .with_secondary(prev, format!("previous definition of `{}` here", name)));
+                continue;
+            }
+            declared.insert(name, span);
+        }
+        self.builtin_types = declared.iter()
+            .filter(|(_, span)| Some(span.file) == self.prelude)
+            .map(|(name, _)| name.to_string())
+            .collect();
+        for def in &program.structs {
+            if declared.get(def.name.as_str()) == Some(&def.span) {
+                self.structs.insert(def.name.clone(), Vec::new());
+                self.type_generics.insert(def.name.clone(), def.generics.clone());
+            }
+        }
+        for def in &program.enums {
+            if declared.get(def.name.as_str()) == Some(&def.span) {
+                self.enums.insert(def.name.clone(), Vec::new());
+                self.type_generics.insert(def.name.clone(), def.generics.clone());
+            }
+        }
+        // A traitek és implementációik a mezők típusjelölésének ellenőrzése előtt ismertek
+        self.declare_traits(&program.traits);
+        for block in &program.impls {
+            self.register_trait_impl(block);
+        }
+
+        for def in &program.structs {
+            if declared.get(def.name.as_str()) == Some(&def.span) {
+                let fields = self.check_struct(def);
+                self.structs.insert(def.name.clone(), fields);
+            }
+        }
+        for def in &program.enums {
+            if declared.get(def.name.as_str()) == Some(&def.span) {
+                let variants = self.check_enum(def);
+                self.enums.insert(def.name.clone(), variants);
+            }
+        }
+        for &(name, span) in &type_names {
+            if declared.get(name.as_str()) == Some(&span) && self.is_recursive(name) {
+                self.diagnostics.push(Diagnostic::error(format!("recursive type `{}` has infinite size", name))
+                    .with_code(codes::RECURSIVE_TYPE)
+                    .with_primary(span, "recursive without indirection"));
+            }
+        }
+
+        for block in &program.impls {
+            self.declare_methods(block);
+        }
+
+        self.declare_builtins();
         for fv in &program.functions {
-            self.symbols.insert(
-                fv.name.clone(),
-                Type::Function(
-                    fv.params.iter().map(|p| self.parse_type(&p.1)).collect(),
-                    Box::new(self.parse_type(&fv.return_type))
-                )
-            );
-        }
-        
+            let fv_type = self.with_generics(&fv.generics, |checker| checker.signature(fv));
+            self.symbols.insert(fv.name.clone(), fv_type);
+            // A saját függvény elfedi az azonos nevű (esetleg generikus) beépítettet
+            if fv.generics.is_empty() {
+                self.generic_fns.remove(&fv.name);
+            } else {
+                self.generic_fns.insert(fv.name.clone(), fv.generics.clone());
+            }
+        }
+        self.check_consts(&program.consts, &program.functions);
+
+        for def in &program.traits {
+            if self.traits.get(&def.name).is_some_and(|info| info.span == def.span) {
+                let self_param = GenericParam { name: SELF_TYPE.to_string(), bounds: vec![def.name.clone()], span: def.span };
+                self.with_self_type(Some(Type::Param(SELF_TYPE.to_string())), |checker| {
+                    for item in &def.methods {
+                        checker.check_function(&item.function, std::slice::from_ref(&self_param), item.has_default);
+                    }
+                });
+            }
+        }
+        for block in &program.impls {
+            let self_type = self.impl_self_type(block);
+            self.with_self_type(self_type, |checker| {
+                for fv in &block.methods {
+                    checker.check_function(fv, &block.generics, true);
+                }
+            });
+        }
         for fv in &program.functions {
-            self.ccheck_functions(fv)?;
+            self.check_function(fv, &[], true);
         }
-        
-        Ok(())
+        self.finish_inference();
+
+        std::mem::take(&mut self.diagnostics)
     }
-    
-    fn check_function(&mut self, fv: &Function) -> Result<(), String> {
-        let mut local_Symbols = HashMap::new();
-        
-        for (name, typ) in &fv.params {
-            local_symbols.insert(name.clone(), self.parse_type(typ));
+
+    fn declare_builtins(&mut self) {
+        let unit = Box::new(Type::Void);
+        self.symbols.insert(BUILTIN_PRINT.to_string(), Type::Function(vec![Type::String], unit.clone()));
+        self.symbols.insert(BUILTIN_PANIC.to_string(), Type::Function(vec![Type::String], Box::new(Type::Never)));
+        self.symbols.insert(BUILTIN_EXIT.to_string(), Type::Function(vec![Type::Int(IntTy::I32)], Box::new(Type::Never)));
+        if self.is_builtin_type(THREAD_TYPE) {
+            let thread = Type::Struct(THREAD_TYPE.to_string(), Vec::new());
+            let task = Type::Function(Vec::new(), unit.clone());
+            self.symbols.insert(BUILTIN_SPAWN.to_string(), Type::Function(vec![task], Box::new(thread.clone())));
+            self.symbols.insert(BUILTIN_JOIN.to_string(), Type::Function(vec![thread], unit.clone()));
         }
-        
-        for stmt in &fv.body {
-            self.check_stmt(stmt, &local_symbols)?;
+        if let Some(generics) = self.type_generics.get(FUTURE_TYPE).filter(|_| self.is_builtin_type(FUTURE_TYPE)).cloned() {
+            let output = Type::Param(generics[0].name.clone());
+            self.symbols.insert(BUILTIN_BLOCK_ON.to_string(), Type::Function(vec![future_type(output.clone())], Box::new(output)));
+            self.generic_fns.insert(BUILTIN_BLOCK_ON.to_string(), generics);
+            self.symbols.insert(BUILTIN_SLEEP.to_string(), Type::Function(vec![Type::Int(IntTy::I64)], Box::new(future_type(Type::Void))));
+            self.symbols.insert(BUILTIN_READ_FILE.to_string(), Type::Function(vec![Type::String], Box::new(future_type(Type::String))));
+        }
+        if self.is_builtin_type(OPTION_TYPE) && self.is_builtin_type(RESULT_TYPE) {
+            self.symbols.insert(BUILTIN_READ_LINE.to_string(), Type::Function(Vec::new(), Box::new(option_type(Type::String))));
+            let contents = result_type(Type::String, Type::String);
+            self.symbols.insert(BUILTIN_LOAD_FILE.to_string(), Type::Function(vec![Type::String], Box::new(contents)));
+            let written = result_type(Type::Void, Type::String);
+            self.symbols.insert(BUILTIN_WRITE_FILE.to_string(), Type::Function(vec![Type::String, Type::String], Box::new(written)));
         }
-        
-        Ok(())
     }
-    
-    fn check_stmt(&self, stmt: &Stmt, symbols: &HashMap<String, Type>) -> Result<(), String> {
-        match stmt {
-            Stmt::Let { name, type_annot, value } => {
-                let value_type = self.check_expr(value, symbols)?;
-                let declared_type = type.annot.as_ref().map(|t| self.parse_type(t));
-                
-                if let Some(decl_type) = declared_type {
-                    if decl_type != value_type {
-                        return Err(format!(
-                            "Type mismatch: expected {:?}, found {:?}", decl_type, value_type
-                        ));
-                    }
-                }
-                
-                Ok(())
-            },
-            Stmt::Expr(expr) => {
-                self.check_expr(expr, symbols)?;
-                Ok(())
+
+    // A modulszintű konstansok a függőségeik után értékelődnek ki, így a deklaráció sorrendje nem számít
+    fn check_consts(&mut self, consts: &[Const], functions: &[Function]) {
+        let mut defs: HashMap<&str, &Const> = HashMap::new();
+        for def in consts {
+            let prev = defs.get(def.name.as_str()).map(|prev| prev.span)
+                .or_else(|| functions.iter().find(|fv| fv.name == def.name).map(|fv| fv.span));
+            if let Some(prev) = prev {
+                self.diagnostics.push(Diagnostic::error(format!("the name `{}` is defined multiple times", def.name))
+                    .with_code(codes::DUPLICATE_DEFINITION)
+                    .with_primary(def.span, format!("`{}` redefined here", def.name))
+                    .with_secondary(prev, format!("previous definition of `{}` here", def.name)));
+                continue;
+            }
+            defs.insert(&def.name, def);
+        }
+        let mut done = Vec::new();
+        for def in consts {
+            if defs.get(def.name.as_str()).is_some_and(|first| first.span == def.span) {
+                self.check_global_const(def, &defs, &mut Vec::new(), &mut done);
+            }
+        }
+    }
+
+    fn check_global_const<'a>(&mut self, def: &'a Const, defs: &HashMap<&str, &'a Const>, visiting: &mut Vec<&'a str>, done: &mut Vec<&'a str>) {
+        if done.contains(&def.name.as_str()) {
+            return;
+        }
+        if let Some(start) = visiting.iter().position(|name| *name == def.name) {
+            let mut cycle: Vec<&str> = visiting[start..].to_vec();
+            cycle.push(&def.name);
+            self.diagnostics.push(Diagnostic::error(format!("cycle detected when evaluating constant `{}`", def.name))
+                .with_code(codes::CONST_CYCLE)
+                .with_primary(def.span, "the value of this constant depends on itself")
+                .with_note(format!("the cycle is `{}`", cycle.join("` -> `"))));
+            // A kör tagjai nem kapnak értéket; a további hivatkozásaik már nem okoznak hibát
+            for &name in &visiting[start..] {
+                let annotated = defs[name].type_annot.as_ref().map(|annot| self.parse_type(annot)).unwrap_or(Type::Error);
+                self.symbols.insert(name.to_string(), annotated);
+                done.push(name);
+            }
+            return;
+        }
+
+        visiting.push(&def.name);
+        for name in captures::free_variables(&[], &def.value) {
+            if let Some(dep) = defs.get(name.as_str()) {
+                self.check_global_const(dep, defs, visiting, done);
+            }
+        }
+        visiting.pop();
+
+        if done.contains(&def.name.as_str()) {
+            return;
+        }
+        done.push(&def.name);
+        let ty = self.check_const(def);
+        if let Some(value) = self.const_values.get(&def.span) {
+            self.consts.insert(def.name.clone(), value.clone());
+        }
+        self.symbols.insert(def.name.clone(), ty);
+    }
+
+    // A `konst` típusa; az értéke a `const_values`-ba kerül. Hibánál a jelölt típust adja
+    // (ha van), így a konstans használatai nem okoznak újabb hibát.
+    fn check_const(&mut self, def: &Const) -> Type {
+        let decl_type = def.type_annot.as_ref().map(|annot| {
+            self.check_annotation(annot);
+            self.parse_type(annot)
+        });
+        match self.check_const_value(def, decl_type.as_ref()) {
+            Ok((ty, value)) => {
+                self.const_values.insert(def.span, value);
+                ty
             },
-            Stmt::Return(expr) => {
-                if let Some(expr) = expr {
-                    self.check_expr(expr, symbols)?;
+            Err(diag) => {
+                self.diagnostics.push(diag);
+                decl_type.unwrap_or(Type::Error)
+            }
+        }
+    }
+
+    fn check_const_value(&mut self, def: &Const, decl_type: Option<&Type>) -> Result<(Type, ConstValue), Diagnostic> {
+        let value_type = self.check_expr_expecting(&def.value, decl_type)?;
+        if let Some(decl_type) = decl_type {
+            if value_type != *decl_type {
+                return Err(Diagnostic::error("mismatched types")
+                    .with_code(codes::MISMATCHED_TYPES)
+                    .with_primary(def.value.span, format!("expected `{}`, found `{}`", decl_type, value_type))
+                    .with_secondary(def.span, "expected due to this type annotation"));
+            }
+        }
+        let lookup = |name: &str, span: Span| self.const_value(name, span);
+        let value = consteval::evaluate(&def.value, &self.expr_types, &lookup)?;
+        Ok((value_type, value))
+    }
+
+    // Név értéke konstans kifejezésben: a legbelső helyi kötés, ha az `konst`, különben a
+    // modulszintű konstans. A nevet elfedő `var`, paraméter vagy minta kötése nem konstans.
+    fn const_value(&self, name: &str, span: Span) -> Result<ConstValue, Diagnostic> {
+        let value = match self.local(name) {
+            Some(binding) if binding.kind == BindingKind::Const => self.const_values.get(&binding.span),
+            Some(_) => None,
+            None => self.consts.get(name)
+        };
+        value.cloned().ok_or_else(|| {
+            Diagnostic::error("attempt to use a non-constant value in a constant")
+                .with_code(codes::NOT_CONST)
+                .with_primary(span, "non-constant value")
+                .with_help(format!("only other `konst` items can be used here; consider declaring `{}` with `konst`", name))
+        })
+    }
+
+    fn check_struct(&mut self, def: &Struct) -> Vec<(String, Type)> {
+        self.check_generics(&def.generics);
+        self.with_generics(&def.generics, |checker| checker.check_struct_fields(def))
+    }
+
+    fn check_struct_fields(&mut self, def: &Struct) -> Vec<(String, Type)> {
+        let mut fields: Vec<(String, Type)> = Vec::new();
+        for field in &def.fields {
+            if fields.iter().any(|(name, _)| *name == field.name) {
+                self.diagnostics.push(Diagnostic::error(format!("field `{}` is already declared", field.name))
+                    .with_code(codes::DUPLICATE_DEFINITION)
+                    .with_primary(field.span, "field already declared"));
+                continue;
+            }
+            self.check_annotation(&field.type_annot);
+            fields.push((field.name.clone(), self.parse_type(&field.type_annot)));
+        }
+        fields
+    }
+
+    fn check_enum(&mut self, def: &Enum) -> Vec<(String, Vec<Type>)> {
+        self.check_generics(&def.generics);
+        self.with_generics(&def.generics, |checker| checker.check_variants(def))
+    }
+
+    fn check_variants(&mut self, def: &Enum) -> Vec<(String, Vec<Type>)> {
+        let mut variants: Vec<(String, Vec<Type>)> = Vec::new();
+        for variant in &def.variants {
+            if variants.iter().any(|(name, _)| *name == variant.name) {
+                self.diagnostics.push(Diagnostic::error(format!("variant `{}` is already declared", variant.name))
+                    .with_code(codes::DUPLICATE_DEFINITION)
+                    .with_primary(variant.span, "variant already declared"));
+                continue;
+            }
+            for field in &variant.fields {
+                self.check_annotation(field);
+            }
+            let fields = variant.fields.iter().map(|field| self.parse_type(field)).collect();
+            variants.push((variant.name.clone(), fields));
+        }
+        variants
+    }
+
+    // Tartalmazza-e a típus (közvetlenül vagy mezőin keresztül) önmagát
+    fn is_recursive(&self, name: &str) -> bool {
+        let mut stack = vec![name.to_string()];
+        let mut visited: Vec<String> = Vec::new();
+        while let Some(current) = stack.pop() {
+            let field_types: Vec<&Type> = match (self.structs.get(&current), self.enums.get(&current)) {
+                (Some(fields), _) => fields.iter().map(|(_, ty)| ty).collect(),
+                (_, Some(variants)) => variants.iter().flat_map(|(_, fields)| fields).collect(),
+                _ => Vec::new()
+            };
+            for ty in field_types {
+                let (Type::Struct(field_type, _) | Type::Enum(field_type, _)) = ty else { continue };
+                if field_type == name {
+                    return true;
                 }
-                
-                Ok(())
-            },
-            _ => unimplemented!()
+                if !visited.contains(field_type) {
+                    visited.push(field_type.clone());
+                    stack.push(field_type.clone());
+                }
+            }
+        }
+        false
+    }
+
+    // A típus generikus paramétereinek megfeleltetése a típusargumentumoknak
+    fn type_subst(&self, name: &str, args: &[Type]) -> HashMap<String, Type> {
+        self.type_generics.get(name)
+            .map(|generics| generics.iter().map(|g| g.name.clone()).zip(args.iter().cloned()).collect())
+            .unwrap_or_default()
+    }
+
+    // A változat adatmezőinek típusa a megadott típusargumentumokkal
+    fn variant(&self, enum_name: &str, variant: &str, args: &[Type]) -> Option<Vec<Type>> {
+        let subst = self.type_subst(enum_name, args);
+        self.enums.get(enum_name)
+            .and_then(|variants| variants.iter().find(|(name, _)| name == variant))
+            .map(|(_, fields)| fields.iter().map(|ty| ty.substitute(&subst)).collect())
+    }
+
+    fn struct_fields(&self, name: &str, args: &[Type]) -> Option<Vec<(String, Type)>> {
+        let subst = self.type_subst(name, args);
+        self.structs.get(name)
+            .map(|fields| fields.iter().map(|(field, ty)| (field.clone(), ty.substitute(&subst))).collect())
+    }
+
+    // A generikus típus saját paramétereivel (`Lista<T>`), ahogy a deklarációjában szerepel
+    fn generic_self_type(&self, name: &str) -> Vec<Type> {
+        self.type_generics.get(name)
+            .map(|generics| generics.iter().map(|g| Type::Param(g.name.clone())).collect())
+            .unwrap_or_default()
+    }
+
+    // `aszink fv f() -> T` hívása `Jovo<T>`-t ad
+    fn signature(&mut self, fv: &Function) -> Type {
+        let return_type = self.parse_return_type(&fv.return_type);
+        Type::Function(
+            fv.params.iter().map(|param| self.param_type(param)).collect(),
+            Box::new(if fv.is_async { future_type(return_type) } else { return_type })
+        )
+    }
+
+    // A `parse_type` a `type_params`-ban lévő neveket generikus paraméternek veszi
+    fn with_generics<T>(&mut self, generics: &[GenericParam], check: impl FnOnce(&mut Self) -> T) -> T {
+        let outer = self.type_params.clone();
+        for generic in generics {
+            self.type_params.insert(generic.name.clone(), generic.bounds.clone());
+        }
+        let result = check(self);
+        self.type_params = outer;
+        result
+    }
+
+    fn check_generics(&mut self, generics: &[GenericParam]) {
+        for (i, generic) in generics.iter().enumerate() {
+            if let Some(prev) = generics[..i].iter().find(|other| other.name == generic.name) {
+                self.diagnostics.push(Diagnostic::error(format!("the name `{}` is already used for a generic parameter", generic.name))
+                    .with_code(codes::DUPLICATE_DEFINITION)
+                    .with_primary(generic.span, "already used")
+                    .with_secondary(prev.span, format!("first use of `{}`", generic.name)));
+            }
+            for bound in &generic.bounds {
+                if !self.traits.contains_key(bound) {
+                    self.diagnostics.push(Diagnostic::error(format!("cannot find trait `{}` in this scope", bound))
+                        .with_code(codes::UNDEFINED_NAME)
+                        .with_primary(generic.span, "unknown bound"));
+                }
+            }
         }
     }
-    
-    fn check_expr(&self, expr: &Expr, symbols: &HashMap<String, Type>) -> Result<Type, String> {
-        match expr {
-            Expr::Literal(lit) => match lit {
-                Literal::Int(_) => Ok(Type::Int),
-                Literal::Float(_) => Ok(Type::Float),
-                Literal::String(_) => Ok(Type::String),
-                Literal::Bool(_) => Ok(Type::Bool)
+
+    // Teljesíti-e a típus a korlátot; generikus paraméternél a deklarált korlátai számítanak,
+    // generikus impl blokknál (`impl<T: Kiirhato> Kiirhato for Lista<T>`) a típusargumentumoké is
+    fn satisfies(&self, ty: &Type, bound: &str) -> bool {
+        match ty {
+            Type::Param(name) => self.type_params.get(name).is_some_and(|bounds| bounds.iter().any(|b| b == bound)),
+            Type::Dyn(trait_name) => trait_name == bound,
+            Type::Struct(name, args) | Type::Enum(name, args) => {
+                self.trait_impls.get(&(name.clone(), bound.to_string())).is_some_and(|(generics, _)| {
+                    generics.iter().zip(args).all(|(generic, arg)| generic.bounds.iter().all(|b| self.satisfies(arg, b)))
+                })
             },
-            Expr::Ident(name) => {
-                symbols.get(name).cloned().ok_or_else(|| format!("Undefined variable: {}", name))
+            Type::Error => true,
+            _ => match bound {
+                BOUND_ORD => matches!(ty, Type::Int(_) | Type::Float(_) | Type::Bool | Type::Char | Type::String),
+                BOUND_DISPLAY => ty.is_printable(),
+                _ => false
+            }
+        }
+    }
+
+    fn unsatisfied_bound(&self, ty: &Type, bound: &str, span: Span) -> Diagnostic {
+        let diag = Diagnostic::error(format!("the trait bound `{}: {}` is not satisfied", ty, bound))
+            .with_code(codes::UNSATISFIED_BOUND)
+            .with_primary(span, format!("the trait `{}` is not implemented for `{}`", bound, ty));
+        match ty {
+            Type::Param(name) => diag.with_help(format!("consider restricting type parameter `{}`: `{}: {}`", name, name, bound)),
+            _ => diag
+        }
+    }
+
+    // Az érték `dyn Trait` típusra alakítható-e; ha igen, az átalakítást a kódgenerálás számára rögzíti
+    fn coerces(&mut self, expr: &Expr, actual: &Type, expected: &Type) -> bool {
+        if self.resolve(actual) == Type::Never || self.unify(actual, expected) {
+            return true;
+        }
+        let (actual, expected) = (&self.resolve(actual), &self.resolve(expected));
+        match expected {
+            Type::Dyn(trait_name) if !matches!(actual, Type::Dyn(_) | Type::Error) && self.satisfies(actual, trait_name) => {
+                self.coercions.insert(expr.span, expected.clone());
+                true
             },
-            Expr::BinaryOp { left, op, right } => {
-                let left_type = self.check_expr(left, symbols)?;
-                let right_type = self.ccheck_expr(right, symbols)?;
-                
-                if left_type != right_type {
-                    return Err(format!(
-                        "Type mismatch in binary operation: {:?} vs {:?}", left_type, right_type
-                    ));
+            // Tömb és `Tomb<T>` szeletként is átadható
+            Type::Slice(element) if matches!(actual, Type::Array(..)) || actual.is_dyn_array() => {
+                if !array_element(actual).is_some_and(|actual| self.unify(&actual, element)) {
+                    return false;
                 }
-                
-                match op {
-                    BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => {
-                        if left_type == Type::Int || left_type == Type::Float {
-                            Ok(left_type)
-                        } else {
-                            Err("Arithmetic operations require numbers".to_string())
-                        }
-                    },
-                    BinaryOp::Eq | BinaryOp::Neq | BinaryOp::Lt | BinaryOp::Gt | BinaryOp::Le | BinaryOp::Ge => {
-                        Ok(Type::Bool)
-                    },
-                    BinaryOp::And | BinaryOp::Or => {
-                        if left_type == Type:Bool {
-                            Ok(Type::Bool)
-                        } else {
-                            Err("Logical operations require booleans".to_string())
-                        }
-                    }
+                self.coercions.insert(expr.span, expected.clone());
+                true
+            },
+            // A `[...]` literálból új dinamikus tömb épül
+            Type::Struct(..) if expected.is_dyn_array() && matches!(expr.kind, ExprKind::Array(_)) => {
+                let (Some(actual), Some(element)) = (array_element(actual), array_element(expected)) else { return false };
+                if !self.unify(&actual, &element) {
+                    return false;
                 }
+                self.coercions.insert(expr.span, expected.clone());
+                true
             },
-            Expr::Call { callee, args } => {
-                let fv_type = self.check_expr(&Expr::Ident(callee.clone()), symbols)?;
-                match fv_type {
-                    Type::Function(param_types, return_type) => {
-                        if args.len() != param_types.len() {
-                            return Err(format!(
-                                "Expected {} arguments, found {}", param_types.len(), args.len()
-                            ));
-                        }
-                        
-                        for (i, (arg, param_type)) in args.iter().zip(param_types.iter()).enumerate() {
-                            let arg_type = self.check_expr(arg, symbols)?;
-                            if &arg_type!= param_type {
-                                return Err(format!(
-                                    "Argument {} type mismatch: expected {:?}. found {:?}", i, param_type, arg_type
-                                ));
-                            }
-                        }
-                        
-                        Ok(*return_type)
-                    },
-                    _ => Err(format!("{} is not a function", callee))
+            _ => false
+        }
+    }
+
+    fn fresh_var(&mut self) -> Type {
+        self.type_vars.push(None);
+        Type::Var(self.type_vars.len() as u32 - 1)
+    }
+
+    // A típusváltozók helyére a kötésüket írja; a kötetlen változók megmaradnak
+    fn resolve(&self, ty: &Type) -> Type {
+        ty.map_vars(&mut |var| match &self.type_vars[var as usize] {
+            Some(bound) => self.resolve(bound),
+            None => Type::Var(var)
+        })
+    }
+
+    // A két típust egyezővé teszi a típusváltozók megkötésével. Sikertelen egyesítés nem
+    // hagy maga után részleges kötést.
+    fn unify(&mut self, a: &Type, b: &Type) -> bool {
+        // Sikertelen egyesítésnél csak az e hívásban kötött változókat oldjuk fel újra
+        let mut trail = Vec::new();
+        let unified = self.unify_resolved(&self.resolve(a), &self.resolve(b), &mut trail);
+        if !unified {
+            for var in trail {
+                self.type_vars[var as usize] = None;
+            }
+        }
+        unified
+    }
+
+    // A `trail` az itt kötött (korábban kötetlen) típusváltozók sorszámait gyűjti
+    fn unify_resolved(&mut self, a: &Type, b: &Type, trail: &mut Vec<u32>) -> bool {
+        let all = |a: &[Type], b: &[Type], checker: &mut Self, trail: &mut Vec<u32>| {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| checker.unify_resolved(&checker.resolve(a), &checker.resolve(b), trail))
+        };
+        match (a, b) {
+            (Type::Var(x), Type::Var(y)) if x == y => true,
+            // Az előfordulás-vizsgálat kizárja a végtelen típust (`_ = Tomb<_>`)
+            (Type::Var(var), other) | (other, Type::Var(var)) => {
+                if other.vars().contains(var) {
+                    return false;
                 }
+                self.type_vars[*var as usize] = Some(other.clone());
+                trail.push(*var);
+                true
             },
-            _ => unimplemented!()
+            (Type::Error, _) | (_, Type::Error) => true,
+            (Type::Function(a_params, a_ret), Type::Function(b_params, b_ret)) => {
+                all(a_params, b_params, self, trail) && self.unify_resolved(&self.resolve(a_ret), &self.resolve(b_ret), trail)
+            },
+            (Type::Tuple(a), Type::Tuple(b)) => all(a, b, self, trail),
+            (Type::Struct(a_name, a_args), Type::Struct(b_name, b_args))
+            | (Type::Enum(a_name, a_args), Type::Enum(b_name, b_args)) => a_name == b_name && all(a_args, b_args, self, trail),
+            (Type::Range(a), Type::Range(b)) | (Type::Slice(a), Type::Slice(b)) => self.unify_resolved(a, b, trail),
+            (Type::Array(a, a_len), Type::Array(b, b_len)) => a_len == b_len && self.unify_resolved(a, b, trail),
+            _ => a == b
+        }
+    }
+
+    // A korlát ellenőrzése; még ismeretlen típusnál a függvény (vagy a program) végére halasztva
+    fn require(&mut self, ty: &Type, bound: &str, span: Span) -> Result<(), Diagnostic> {
+        let ty = self.resolve(ty);
+        if !ty.vars().is_empty() {
+            self.pending.push((ty, Pending::Bound(bound.to_string()), span));
+            return Ok(());
+        }
+        if !self.satisfies(&ty, bound) {
+            return Err(self.unsatisfied_bound(&ty, bound, span));
+        }
+        Ok(())
+    }
+
+    // Jelölés nélküli paraméter típusa: típusváltozó, amelyet a törzs és a hívások kötnek meg.
+    // A paraméter tartományán tárolva, így a szignatúra és a törzs ellenőrzése ugyanazt látja.
+    fn param_type(&mut self, param: &Param) -> Type {
+        if let Some(annot) = &param.type_annot {
+            return self.parse_type(annot);
+        }
+        if let Some(ty) = self.expr_types.get(&param.span) {
+            return ty.clone();
+        }
+        let ty = self.fresh_var();
+        self.expr_types.insert(param.span, ty.clone());
+        self.inferred.push((param.name.clone(), ty.clone(), param.span));
+        ty
+    }
+
+    // A már ismertté vált típusokra halasztott megkötések ellenőrzése. A függvény végén a
+    // generikus paraméterek korlátai még láthatók; a program végén (`last`) a továbbra is
+    // ismeretlen típusok hibát adnak.
+    fn check_pending(&mut self, last: bool) {
+        for (ty, pending, span) in std::mem::take(&mut self.pending) {
+            let ty = self.resolve(&ty);
+            if !ty.vars().is_empty() {
+                if last {
+                    self.report_unknown(&ty, span, "cannot infer the type of this value", None);
+                } else {
+                    self.pending.push((ty, pending, span));
+                }
+                continue;
+            }
+            match pending {
+                _ if ty.has_error() => {},
+                Pending::Numeric if !ty.is_numeric() => {
+                    self.diagnostics.push(Diagnostic::error(format!("cannot apply arithmetic operator to `{}`", ty))
+                        .with_code(codes::INVALID_OPERANDS)
+                        .with_primary(span, "arithmetic operations require numbers")
+                        .with_note(format!("the operand was inferred to be `{}`", ty)));
+                },
+                Pending::Bound(bound) if !self.satisfies(&ty, &bound) => {
+                    let diag = self.unsatisfied_bound(&ty, &bound, span);
+                    self.diagnostics.push(diag.with_note(format!("the type was inferred to be `{}`", ty)));
+                },
+                _ => {}
+            }
+        }
+    }
+
+    // A program végén minden típusváltozó kötött kell legyen; a kötetlenek helyére a
+    // kódgenerálás felé `Type::Error` kerül
+    fn finish_inference(&mut self) {
+        for (name, ty, span) in std::mem::take(&mut self.inferred) {
+            let ty = self.resolve(&ty);
+            self.report_unknown(&ty, span, &format!("cannot infer type for `{}`", name), Some(&name));
+        }
+        self.check_pending(true);
+
+        let finalize = |checker: &Self, ty: &Type| checker.resolve(ty).map_vars(&mut |_| Type::Error);
+        let expr_types = std::mem::take(&mut self.expr_types);
+        self.expr_types = expr_types.iter().map(|(span, ty)| (*span, finalize(self, ty))).collect();
+        let coercions = std::mem::take(&mut self.coercions);
+        self.coercions = coercions.iter().map(|(span, ty)| (*span, finalize(self, ty))).collect();
+        let instantiations = std::mem::take(&mut self.instantiations);
+        self.instantiations = instantiations.iter()
+            .map(|(span, args)| (*span, args.iter().map(|ty| finalize(self, ty)).collect()))
+            .collect();
+    }
+
+    // Típusváltozónként egyszer jelzi, ha a (feloldott) típus nem következtethető ki
+    fn report_unknown(&mut self, ty: &Type, span: Span, label: &str, name: Option<&str>) {
+        let vars = ty.vars();
+        if vars.is_empty() {
+            return;
+        }
+        // A jelentett változót `Type::Error`-ra kötjük, így ugyanarra nem érkezik újabb hiba
+        for var in vars {
+            self.type_vars[var as usize] = Some(Type::Error);
         }
+        let mut diag = Diagnostic::error("type annotations needed")
+            .with_code(codes::CANNOT_INFER)
+            .with_primary(span, label);
+        if let Some(name) = name {
+            diag = diag.with_help(format!("consider giving `{}` an explicit type", name));
+        }
+        self.diagnostics.push(diag);
     }
-    
-    fn parse_type(&self, type_str: &Option<String>) -> Type {
-        match type_str.as_deref() {
-            Some("i32") => Type::Int,
-            Some("f64") => Type::Float,
-            Some("bool") => Type::Bool,
-            Some("str") => Type::String,
-            _ => Type::Error
+
+    // A generikus hívás típusargumentumainak kikövetkeztetése az argumentumokból (és a várt
+    // visszatérési típusból), majd a korlátok ellenőrzése. Az eredmény a helyettesített
+    // visszatérési típus; a típusargumentumok az `instantiations`-be kerülnek.
+    #[allow(clippy::too_many_arguments)]
+    fn check_generic_call(
+        &mut self,
+        callee: &str,
+        generics: &[GenericParam],
+        mut subst: HashMap<String, Type>,
+        param_types: &[Type],
+        return_type: &Type,
+        args: &[Expr],
+        expected: Option<&Type>,
+        span: Span
+    ) -> Result<Type, Diagnostic> {
+        if args.len() != param_types.len() {
+            return Err(Diagnostic::error(format!(
+                    "this function takes {} arguments but {} were supplied", param_types.len(), args.len()
+                ))
+                .with_code(codes::WRONG_ARG_COUNT)
+                .with_primary(span, format!("expected {} arguments", param_types.len())));
+        }
+
+        if let Some(expected) = expected {
+            let mut from_expected = subst.clone();
+            if bind_params(return_type, expected, &mut from_expected) {
+                subst = from_expected;
+            }
         }
+
+        for (i, (arg, param_type)) in args.iter().zip(param_types).enumerate() {
+            // A még ismeretlen típusargumentumok helyén típusváltozó, így a lezárás paraméterei is kikövetkeztethetők
+            let mut hint_subst = subst.clone();
+            for generic in generics {
+                if !hint_subst.contains_key(&generic.name) {
+                    let var = self.fresh_var();
+                    hint_subst.insert(generic.name.clone(), var);
+                }
+            }
+            let arg_expected = param_type.substitute(&hint_subst);
+            let hint = (!arg_expected.has_params()).then_some(&arg_expected);
+            let arg_type = self.check_expr_expecting(arg, hint)?;
+            if !bind_params(param_type, &arg_type, &mut subst) && !self.coerces(arg, &arg_type, &param_type.substitute(&subst)) {
+                return Err(Diagnostic::error("mismatched types")
+                    .with_code(codes::MISMATCHED_TYPES)
+                    .with_primary(arg.span, format!("expected `{}`, found `{}`", param_type.substitute(&subst), arg_type))
+                    .with_note(format!("argument {} of `{}` has type `{}`", i + 1, callee, param_type)));
+            }
+        }
+
+        let mut type_args = Vec::new();
+        for generic in generics {
+            let Some(ty) = subst.get(&generic.name).cloned() else {
+                return Err(Diagnostic::error(format!("type annotations needed: cannot infer type parameter `{}` of `{}`", generic.name, callee))
+                    .with_code(codes::CANNOT_INFER)
+                    .with_primary(span, format!("cannot infer type for `{}`", generic.name)));
+            };
+            for bound in &generic.bounds {
+                self.require(&ty, bound, span).map_err(|diag| {
+                    diag.with_note(format!("required by the bound `{}: {}` of `{}`", generic.name, bound, callee))
+                })?;
+            }
+            type_args.push(ty);
+        }
+
+        self.instantiations.insert(span, type_args);
+        Ok(return_type.substitute(&subst))
     }
-}
\ No newline at end of file
+
+    fn declare_methods(&mut self, block: &Impl) {
+        let Some(type_generics) = self.type_generics.get(&block.type_name).cloned() else {
+            self.diagnostics.push(Diagnostic::error(format!("cannot find type `{}` in this scope", block.type_name))
+                .with_code(codes::UNDEFINED_NAME)
+                .with_primary(block.span, "impl for an unknown type")
+                .with_note("methods can only be defined for structs and enums declared in this program"));
+            return;
+        };
+
+        // Csak a teljes generikus típusra írható impl: `impl<T> Lista<T>`
+        self.check_generics(&block.generics);
+        let names_generics = block.type_args.len() == block.generics.len()
+            && block.type_args.iter().zip(&block.generics).all(|(arg, generic)| arg.args.is_empty() && arg.name == generic.name);
+        if block.type_args.len() != type_generics.len() || !names_generics {
+            let expected: Vec<&str> = type_generics.iter().map(|g| g.name.as_str()).collect();
+            self.diagnostics.push(Diagnostic::error(format!("impl blocks must name the generic parameters of `{}`", block.type_name))
+                .with_code(codes::WRONG_TYPE_ARGS)
+                .with_primary(block.span, format!("expected {} generic parameters", type_generics.len()))
+                .with_help(format!("write `impl<{0}> {1}<{0}>`", expected.join(", "), block.type_name)));
+            return;
+        }
+
+        let Some(self_type) = self.impl_self_type(block) else { return };
+        let implemented = match &block.trait_name {
+            Some(trait_name) => {
+                let Some(info) = self.traits.get(trait_name).cloned() else {
+                    self.diagnostics.push(Diagnostic::error(format!("cannot find trait `{}` in this scope", trait_name))
+                        .with_code(codes::UNDEFINED_NAME)
+                        .with_primary(block.span, "not found in this scope"));
+                    return;
+                };
+                // Az ütköző második implementációt a `register_trait_impl` már jelentette
+                if self.trait_impls.get(&(block.type_name.clone(), trait_name.clone())).map(|(_, span)| *span) != Some(block.span) {
+                    return;
+                }
+                Some((trait_name.as_str(), info))
+            },
+            None => None
+        };
+
+        for fv in &block.methods {
+            let generics: Vec<GenericParam> = block.generics.iter().chain(&fv.generics).cloned().collect();
+            let method = Method {
+                signature: self.with_self_type(Some(self_type.clone()), |checker| checker.with_generics(&generics, |checker| checker.signature(fv))),
+                takes_self: fv.takes_self(),
+                generics,
+                impl_generics: block.generics.len(),
+                span: fv.span
+            };
+            if let Some((trait_name, info)) = &implemented {
+                if let Err(diag) = self.check_trait_method(trait_name, info, &fv.name, &method, &self_type) {
+                    self.diagnostics.push(diag);
+                    continue;
+                }
+            }
+            self.add_method(&block.type_name, &fv.name, method);
+        }
+
+        let Some((trait_name, info)) = implemented else { return };
+        let missing: Vec<String> = info.methods.iter()
+            .filter(|(name, _)| !info.provided.contains(name) && !block.methods.iter().any(|fv| fv.name == *name))
+            .map(|(name, _)| format!("`{}`", name))
+            .collect();
+        if !missing.is_empty() {
+            self.diagnostics.push(Diagnostic::error(format!("not all trait items implemented, missing: {}", missing.join(", ")))
+                .with_code(codes::MISSING_TRAIT_ITEMS)
+                .with_primary(block.span, format!("missing {} in implementation", missing.join(", ")))
+                .with_secondary(info.span, format!("`{}` is declared here", trait_name)));
+        }
+
+        // A felül nem írt alapértelmezett metódusok az implementáló típus metódusai lesznek
+        let self_subst = HashMap::from([(SELF_TYPE.to_string(), self_type)]);
+        for (name, method) in &info.methods {
+            if !info.provided.contains(name) || block.methods.iter().any(|fv| fv.name == *name) {
+                continue;
+            }
+            let method = Method {
+                signature: method.signature.substitute(&self_subst),
+                takes_self: method.takes_self,
+                generics: block.generics.iter().chain(&method.generics[method.impl_generics..]).cloned().collect(),
+                impl_generics: block.generics.len(),
+                span: method.span
+            };
+            self.add_method(&block.type_name, name, method);
+        }
+    }
+
+    fn add_method(&mut self, type_name: &str, name: &str, method: Method) {
+        let methods = self.methods.entry(type_name.to_string()).or_default();
+        if let Some(prev) = methods.get(name) {
+            let diag = Diagnostic::error(format!("duplicate definitions with name `{}`", name))
+                .with_code(codes::DUPLICATE_DEFINITION)
+                .with_primary(method.span, "duplicate definition")
+                .with_secondary(prev.span, format!("other definition for `{}`", name));
+            self.diagnostics.push(diag);
+            return;
+        }
+        methods.insert(name.to_string(), method);
+    }
+
+    // Az impl blokk metódusa a trait deklarációjával egyező aláírású-e (a `Self` helyén a blokk típusával)
+    fn check_trait_method(&self, trait_name: &str, info: &TraitInfo, name: &str, method: &Method, self_type: &Type) -> Result<(), Diagnostic> {
+        let Some((_, declared)) = info.methods.iter().find(|(declared, _)| declared == name) else {
+            return Err(Diagnostic::error(format!("method `{}` is not a member of trait `{}`", name, trait_name))
+                .with_code(codes::NOT_A_TRAIT_MEMBER)
+                .with_primary(method.span, format!("not a member of trait `{}`", trait_name))
+                .with_secondary(info.span, format!("`{}` is declared here", trait_name)));
+        };
+
+        // A metódus saját generikus paraméterei pozíció szerint felelnek meg egymásnak
+        let own = &method.generics[method.impl_generics..];
+        let declared_own = &declared.generics[declared.impl_generics..];
+        let mut subst: HashMap<String, Type> = declared_own.iter().zip(own)
+            .map(|(d, g)| (d.name.clone(), Type::Param(g.name.clone())))
+            .collect();
+        subst.insert(SELF_TYPE.to_string(), self_type.clone());
+        let expected = declared.signature.substitute(&subst);
+        let same_generics = own.len() == declared_own.len()
+            && own.iter().zip(declared_own).all(|(g, d)| g.bounds.len() == d.bounds.len() && g.bounds.iter().all(|b| d.bounds.contains(b)));
+        if expected != method.signature || method.takes_self != declared.takes_self || !same_generics {
+            return Err(Diagnostic::error(format!("method `{}` has an incompatible type for trait `{}`", name, trait_name))
+                .with_code(codes::INCOMPATIBLE_TRAIT_METHOD)
+                .with_primary(method.span, format!("expected `{}`, found `{}`", expected, method.signature))
+                .with_secondary(declared.span, "type in trait"));
+        }
+        Ok(())
+    }
+
+    // Két menetben: előbb a nevek, hogy az aláírások hivatkozhassanak más traitekre (`dyn Kiirhato`)
+    fn declare_traits(&mut self, traits: &[Trait]) {
+        let mut declared: Vec<&Trait> = Vec::new();
+        for def in traits {
+            if let Some(prev) = self.traits.get(&def.name) {
+                self.diagnostics.push(Diagnostic::error(format!("the name `{}` is defined multiple times", def.name))
+                    .with_code(codes::DUPLICATE_DEFINITION)
+                    .with_primary(def.span, format!("`{}` redefined here", def.name))
+                    .with_secondary(prev.span, format!("previous definition of `{}` here", def.name)));
+                continue;
+            }
+            self.traits.insert(def.name.clone(), TraitInfo { methods: Vec::new(), provided: Vec::new(), span: def.span });
+            declared.push(def);
+        }
+
+        for def in declared {
+            let self_param = GenericParam { name: SELF_TYPE.to_string(), bounds: vec![def.name.clone()], span: def.span };
+            let mut info = TraitInfo { methods: Vec::new(), provided: Vec::new(), span: def.span };
+            for item in &def.methods {
+                let fv = &item.function;
+                if let Some((_, prev)) = info.methods.iter().find(|(name, _)| *name == fv.name) {
+                    self.diagnostics.push(Diagnostic::error(format!("duplicate definitions with name `{}`", fv.name))
+                        .with_code(codes::DUPLICATE_DEFINITION)
+                        .with_primary(fv.span, "duplicate definition")
+                        .with_secondary(prev.span, format!("other definition for `{}`", fv.name)));
+                    continue;
+                }
+                let generics: Vec<GenericParam> = std::iter::once(self_param.clone()).chain(fv.generics.iter().cloned()).collect();
+                let signature = self.with_self_type(Some(Type::Param(SELF_TYPE.to_string())), |checker| {
+                    checker.with_generics(&generics, |checker| checker.signature(fv))
+                });
+                let method = Method { signature, takes_self: fv.takes_self(), generics, impl_generics: 1, span: fv.span };
+                info.methods.push((fv.name.clone(), method));
+                if item.has_default {
+                    info.provided.push(fv.name.clone());
+                }
+            }
+            self.traits.insert(def.name.clone(), info);
+        }
+    }
+
+    // `impl Trait for Tipus`: a korlátok ellenőrzéséhez a metódusok feldolgozása előtt kell
+    fn register_trait_impl(&mut self, block: &Impl) {
+        let Some(trait_name) = &block.trait_name else { return };
+        let key = (block.type_name.clone(), trait_name.clone());
+        if let Some((_, prev)) = self.trait_impls.get(&key) {
+            self.diagnostics.push(Diagnostic::error(format!("conflicting implementations of trait `{}` for type `{}`", trait_name, block.type_name))
+                .with_code(codes::DUPLICATE_DEFINITION)
+                .with_primary(block.span, "conflicting implementation")
+                .with_secondary(*prev, "first implementation here"));
+            return;
+        }
+        self.trait_impls.insert(key, (block.generics.clone(), block.span));
+    }
+
+    // Az impl blokk típusa a blokk generikus paramétereivel (`impl<T> Lista<T>` -> `Lista<T>`)
+    fn impl_self_type(&self, block: &Impl) -> Option<Type> {
+        let args = block.type_args.iter().map(|arg| Type::Param(arg.name.clone())).collect();
+        if self.structs.contains_key(&block.type_name) {
+            Some(Type::Struct(block.type_name.clone(), args))
+        } else if self.enums.contains_key(&block.type_name) {
+            Some(Type::Enum(block.type_name.clone(), args))
+        } else {
+            None
+        }
+    }
+
+    fn with_self_type<T>(&mut self, self_type: Option<Type>, check: impl FnOnce(&mut Self) -> T) -> T {
+        let outer = std::mem::replace(&mut self.self_type, self_type);
+        let result = check(self);
+        self.self_type = outer;
+        result
+    }
+
+    // Generikus paraméter, `dyn Trait` vagy primitív típus metódusa a traitjeiből; a `Self`
+    // helyére a fogadó típusa kerül
+    fn trait_method(&self, receiver: &Type, name: &str) -> Option<Method> {
+        let candidates: Vec<String> = match receiver {
+            Type::Param(param) => self.type_params.get(param).cloned().unwrap_or_default(),
+            Type::Dyn(trait_name) => vec![trait_name.clone()],
+            Type::Error => Vec::new(),
+            _ => {
+                let mut names: Vec<String> = self.traits.keys().filter(|t| self.satisfies(receiver, t)).cloned().collect();
+                names.sort();
+                names
+            }
+        };
+        let self_subst = HashMap::from([(SELF_TYPE.to_string(), receiver.clone())]);
+        candidates.iter()
+            .filter_map(|trait_name| self.traits.get(trait_name))
+            .find_map(|info| info.methods.iter().find(|(method, _)| method == name))
+            .map(|(_, method)| Method {
+                signature: method.signature.substitute(&self_subst),
+                takes_self: method.takes_self,
+                generics: method.generics[method.impl_generics..].to_vec(),
+                impl_generics: 0,
+                span: method.span
+            })
+    }
+
+    fn lookup_method(&self, type_name: &str, name: &str) -> Option<Method> {
+        self.methods.get(type_name).and_then(|methods| methods.get(name)).cloned()
+    }
+
+    // Az argumentumok számának és típusának ellenőrzése a paraméterlistával szemben
+    fn check_args(&mut self, callee: &str, args: &[Expr], param_types: &[Type], span: Span) -> Result<(), Diagnostic> {
+        if args.len() != param_types.len() {
+            return Err(Diagnostic::error(format!(
+                    "this function takes {} arguments but {} were supplied", param_types.len(), args.len()
+                ))
+                .with_code(codes::WRONG_ARG_COUNT)
+                .with_primary(span, format!("expected {} arguments", param_types.len())));
+        }
+
+        for (i, (arg, param_type)) in args.iter().zip(param_types.iter()).enumerate() {
+            let arg_type = self.check_expr_expecting(arg, Some(param_type))?;
+            if !self.coerces(arg, &arg_type, param_type) {
+                let diag = Diagnostic::error("mismatched types")
+                    .with_code(codes::MISMATCHED_TYPES)
+                    .with_primary(arg.span, format!("expected `{}`, found `{}`", param_type, arg_type))
+                    .with_note(format!("argument {} of `{}` has type `{}`", i + 1, callee, param_type));
+                return Err(cast_help(diag, arg, &arg_type, param_type));
+            }
+        }
+
+        Ok(())
+    }
+
+    // A traitben törzs nélkül deklarált metódusnál (`has_body` hamis) csak a szignatúra számít
+    fn check_function(&mut self, fv: &Function, impl_generics: &[GenericParam], has_body: bool) {
+        self.check_generics(&fv.generics);
+        let generics: Vec<GenericParam> = impl_generics.iter().chain(&fv.generics).cloned().collect();
+        self.with_generics(&generics, |checker| {
+            let param_types: Vec<Type> = fv.params.iter().map(|param| {
+                if let Some(annot) = &param.type_annot {
+                    checker.check_annotation(annot);
+                }
+                checker.param_type(param)
+            }).collect();
+            if let Some(annot) = &fv.return_type {
+                checker.check_annotation(annot);
+            }
+            let return_type = checker.parse_return_type(&fv.return_type);
+            checker.fn_return = Some((return_type.clone(), fv.return_type.as_ref().map(|annot| annot.span)));
+
+            checker.in_async = fv.is_async;
+            checker.with_scope(|checker| {
+                checker.bind_params(&fv.params, &param_types);
+                checker.check_block(&fv.body);
+            });
+            checker.in_async = false;
+            checker.fn_return = None;
+            if let Some(annot) = fv.return_type.as_ref().filter(|_| has_body) {
+                checker.check_missing_return(&fv.body, &return_type, annot.span);
+            }
+            checker.check_pending(false);
+        });
+    }
+
+    fn bind(&mut self, name: &str, ty: Type, kind: BindingKind, span: Span) {
+        self.bind_borrowing(name, ty, kind, span, None);
+    }
+
+    fn bind_borrowing(&mut self, name: &str, ty: Type, kind: BindingKind, span: Span, borrows: Option<String>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(Binding { name: name.to_string(), ty, kind, span, mutated: false, borrows });
+        }
+    }
+
+    // A paraméterek kötése a legbelső hatókörbe; egy név csak egyszer szerepelhet
+    fn bind_params(&mut self, params: &[Param], types: &[Type]) {
+        for (i, (param, ty)) in params.iter().zip(types).enumerate() {
+            if let Some(prev) = params[..i].iter().find(|prev| prev.name == param.name) {
+                self.diagnostics.push(Diagnostic::error(format!("identifier `{}` is bound more than once in this parameter list", param.name))
+                    .with_code(codes::DUPLICATE_DEFINITION)
+                    .with_primary(param.span, "used as parameter more than once")
+                    .with_secondary(prev.span, format!("first use of `{}`", param.name)));
+                continue;
+            }
+            self.bind(&param.name, ty.clone(), BindingKind::Param, param.span);
+        }
+    }
+
+    // A legbelső látható helyi kötés; a belső hatókör és a későbbi kötés elfedi a korábbit
+    fn local(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().flat_map(|scope| scope.iter().rev()).find(|binding| binding.name == name)
+    }
+
+    fn local_mut(&mut self, name: &str) -> Option<&mut Binding> {
+        self.scopes.iter_mut().rev().flat_map(|scope| scope.iter_mut().rev()).find(|binding| binding.name == name)
+    }
+
+    // Név típusa: a helyi kötés, ennek hiányában a modulszintű függvény vagy konstans
+    fn lookup(&self, name: &str) -> Option<Type> {
+        match self.local(name) {
+            Some(binding) => Some(binding.ty.clone()),
+            None => self.symbols.get(name).cloned()
+        }
+    }
+
+    // "Did you mean" javaslat a nem található értékhez: a látható helyi kötések, majd a
+    // modulszintű függvények és konstansok közül a leghasonlóbb név
+    fn suggest_name(&self, diag: Diagnostic, name: &str) -> Diagnostic {
+        let locals = self.scopes.iter().flatten().map(|binding| binding.name.as_str());
+        if let Some(local) = similar_name(name, locals) {
+            return diag.with_help(format!("a local variable with a similar name exists: `{}`", local));
+        }
+        match similar_name(name, self.symbols.keys().map(String::as_str)) {
+            Some(global) if matches!(self.symbols[global], Type::Function(..)) => {
+                diag.with_help(format!("a function with a similar name exists: `{}`", global))
+            },
+            Some(global) => diag.with_help(format!("a constant with a similar name exists: `{}`", global)),
+            None => diag
+        }
+    }
+
+    // Új lexikális hatókör a `visit` idejére; a végén a soha nem módosított `var` kötésekre figyelmeztet
+    fn with_scope<R>(&mut self, visit: impl FnOnce(&mut Self) -> R) -> R {
+        self.scopes.push(Vec::new());
+        let result = visit(self);
+        for binding in self.scopes.pop().unwrap_or_default() {
+            if binding.kind == BindingKind::Mutable && !binding.mutated {
+                self.diagnostics.push(Diagnostic::warning(format!("variable `{}` does not need to be mutable", binding.name))
+                    .with_code(codes::UNUSED_MUT)
+                    .with_primary(binding.span, "this variable is never reassigned")
+                    .with_help("declare it with `legyen` instead of `var`"));
+            }
+        }
+        result
+    }
+
+    // Utasításonként ellenőriz, így egy hibás utasítás után is folytatódik. A blokkban
+    // deklarált `var`, `legyen` és `konst` a blokk további részében látható, és elfedi a
+    // külső hatókörök azonos nevű kötéseit; az érték még a kötés előtt értékelődik ki.
+    fn check_block(&mut self, stmts: &[Stmt]) {
+        self.with_scope(|checker| {
+            // Az első utasítás, amely után a blokk folytatása már nem érhető el; blokkonként egy figyelmeztetés
+            let mut diverged: Option<Span> = None;
+            let mut reported = false;
+            for stmt in stmts {
+                if let (Some(span), false) = (diverged, reported) {
+                    checker.diagnostics.push(Diagnostic::warning("unreachable statement")
+                        .with_code(codes::UNREACHABLE_CODE)
+                        .with_primary(stmt.span, "unreachable statement")
+                        .with_secondary(span, "any code following this is unreachable"));
+                    reported = true;
+                }
+                match &stmt.kind {
+                    StmtKind::Const(def) => {
+                        let ty = checker.check_const(def);
+                        checker.bind(&def.name, ty, BindingKind::Const, def.span);
+                    },
+                    StmtKind::Let { name, mutable, type_annot, value } => {
+                        let ty = checker.check_let(stmt, type_annot, value).unwrap_or_else(|diag| {
+                            checker.diagnostics.push(diag);
+                            Type::Error
+                        });
+                        if !ty.vars().is_empty() {
+                            checker.inferred.push((name.clone(), ty.clone(), stmt.span));
+                        }
+                        let kind = if *mutable { BindingKind::Mutable } else { BindingKind::Immutable };
+                        let borrows = checker.borrowed(value);
+                        checker.bind_borrowing(name, ty, kind, stmt.span, borrows);
+                    },
+                    _ => if let Err(diag) = checker.check_stmt(stmt) {
+                        checker.diagnostics.push(diag);
+                    }
+                }
+                if diverged.is_none() && checker.stmt_diverges(stmt) {
+                    diverged = Some(stmt.span);
+                }
+            }
+        });
+    }
+
+    // A `vissza` értéke a függvény visszatérési típusához illeszkedik-e
+    fn check_return(&mut self, stmt: &Stmt, value: Option<&Expr>, value_type: &Type) -> Result<(), Diagnostic> {
+        let Some((return_type, annot)) = self.fn_return.clone() else { return Ok(()) };
+        let diag = match value {
+            _ if return_type == Type::Never => Diagnostic::error("`vissza` in a function that never returns")
+                .with_primary(stmt.span, "the function returns here"),
+            None if return_type == Type::Void || return_type.has_error() => return Ok(()),
+            None => Diagnostic::error(format!("`vissza;` in a function whose return type is `{}`", return_type))
+                .with_primary(stmt.span, format!("expected a `{}` value", return_type)),
+            Some(value) if self.coerces(value, value_type, &return_type) => return Ok(()),
+            Some(value) => Diagnostic::error("mismatched types")
+                .with_primary(value.span, format!("expected `{}`, found `{}`", return_type, self.resolve(value_type)))
+        };
+        let diag = diag.with_code(codes::MISMATCHED_TYPES);
+        Err(match annot {
+            Some(annot) if return_type == Type::Never => diag.with_secondary(annot, "the function is declared to never return"),
+            Some(annot) => diag.with_secondary(annot, format!("expected `{}` because of this return type", return_type)),
+            None => diag.with_help(format!("add a return type to the function: `-> {}`", self.resolve(value_type)))
+        })
+    }
+
+    // A nem `()` visszatérési típusú törzsnek minden úton `vissza`-val (vagy soha vissza nem
+    // térő hívással) kell végződnie; a `-> !` függvény törzsének vége sem lehet elérhető
+    fn check_missing_return(&mut self, body: &[Stmt], return_type: &Type, annot: Span) {
+        if matches!(return_type, Type::Void) || return_type.has_error() || self.block_diverges(body) {
+            return;
+        }
+        let diag = if *return_type == Type::Never {
+            Diagnostic::error("function declared to never return may return")
+                .with_primary(annot, "the return type `!` means the function must not return")
+                .with_note("the end of the function body is reachable")
+        } else {
+            Diagnostic::error(format!("not all paths return a value of type `{}`", return_type))
+                .with_primary(annot, format!("expected `{}` because of this return type", return_type))
+                .with_note("the end of the function body is reachable, for example through a `ha` without `vagy` or a `mialatt` loop")
+        };
+        self.diagnostics.push(diag.with_code(codes::MISSING_RETURN));
+    }
+
+    // Minden úton `vissza`-val vagy soha vissza nem térő hívással végződik-e a blokk
+    fn block_diverges(&self, stmts: &[Stmt]) -> bool {
+        stmts.iter().any(|stmt| self.stmt_diverges(stmt))
+    }
+
+    fn stmt_diverges(&self, stmt: &Stmt) -> bool {
+        match &stmt.kind {
+            StmtKind::Return(_) => true,
+            StmtKind::Expr(expr) | StmtKind::Let { value: expr, .. } | StmtKind::Assign { value: expr, .. } => self.expr_diverges(expr),
+            // Ciklusból kilépni csak `vissza`-val lehet, így a `mialatt igaz` sosem ér véget
+            StmtKind::While { condition, .. } => {
+                matches!(condition.kind, ExprKind::Literal(Literal::Bool(true))) || self.expr_diverges(condition)
+            },
+            StmtKind::For { iterable, .. } => self.expr_diverges(iterable),
+            StmtKind::Const(_) => false
+        }
+    }
+
+    fn expr_diverges(&self, expr: &Expr) -> bool {
+        if self.expr_types.get(&expr.span) == Some(&Type::Never) {
+            return true;
+        }
+        match &expr.kind {
+            ExprKind::If { condition, then_branch, else_branch } => {
+                self.expr_diverges(condition)
+                    || else_branch.as_ref().is_some_and(|else_branch| self.block_diverges(then_branch) && self.block_diverges(else_branch))
+            },
+            ExprKind::Match { scrutinee, arms } => {
+                self.expr_diverges(scrutinee) || (!arms.is_empty() && arms.iter().all(|arm| self.expr_diverges(&arm.body)))
+            },
+            ExprKind::Block(stmts) => self.block_diverges(stmts),
+            _ => false
+        }
+    }
+
+    // A kötés típusa: a jelölt típus, vagy annak hiányában az értéké
+    fn check_let(&mut self, stmt: &Stmt, type_annot: &Option<TypeAnnot>, value: &Expr) -> Result<Type, Diagnostic> {
+        let Some(type_annot) = type_annot else {
+            return self.check_expr(value);
+        };
+        self.check_annotation(type_annot);
+        let decl_type = self.parse_type(type_annot);
+        let value_type = self.check_expr_expecting(value, Some(&decl_type))?;
+        if !self.coerces(value, &value_type, &decl_type) {
+            let diag = Diagnostic::error("mismatched types")
+                .with_code(codes::MISMATCHED_TYPES)
+                .with_primary(value.span, format!("expected `{}`, found `{}`", decl_type, value_type))
+                .with_secondary(stmt.span, "expected due to this type annotation");
+            return Err(cast_help(diag, value, &value_type, &decl_type));
+        }
+        Ok(decl_type)
+    }
+
+    // Értékadás: a bal oldal hely (változó vagy annak mezője), amelynek gyökere `var` kötés
+    fn check_assign(&mut self, stmt: &Stmt, target: &Expr, op: Option<BinaryOp>, value: &Expr) -> Result<(), Diagnostic> {
+        let Some(root) = place_root(target) else {
+            return Err(Diagnostic::error("invalid left-hand side of assignment")
+                .with_code(codes::INVALID_ASSIGNMENT)
+                .with_primary(target.span, "cannot assign to this expression")
+                .with_help("only variables and their fields can be assigned to"));
+        };
+        let target_type = self.check_expr(target)?;
+        let message = || match &target.kind {
+            ExprKind::Ident(_) => format!("cannot assign twice to immutable variable `{}`", root),
+            _ => format!("cannot assign to `{}`, as `{}` is not declared as mutable", place_text(target), root)
+        };
+        self.check_mutable(root, message, stmt.span)?;
+
+        let value_type = self.check_expr_expecting(value, Some(&target_type))?;
+        if op.is_some() && !target_type.is_numeric() {
+            return Err(Diagnostic::error(format!("cannot apply arithmetic operator to `{}`", target_type))
+                .with_code(codes::INVALID_OPERANDS)
+                .with_primary(stmt.span, "compound assignment requires numbers"));
+        }
+        let matches = if op.is_some() { self.unify(&value_type, &target_type) } else { self.coerces(value, &value_type, &target_type) };
+        if !matches {
+            let diag = Diagnostic::error("mismatched types")
+                .with_code(codes::MISMATCHED_TYPES)
+                .with_primary(value.span, format!("expected `{}`, found `{}`", target_type, value_type))
+                .with_secondary(target.span, format!("this is of type `{}`", target_type));
+            return Err(cast_help(diag, value, &value_type, &target_type));
+        }
+        // A változóba (vagy mezőjébe) tett lezárással a változó is hivatkozást tart
+        if let Some(borrows) = self.borrowed(value) {
+            if let Some(binding) = self.local_mut(root) {
+                binding.borrows = Some(borrows);
+            }
+        }
+        Ok(())
+    }
+
+    // A hely gyökere módosítható-e; ha igen, a kötést módosítottnak jelöli
+    fn check_mutable(&mut self, root: &str, message: impl FnOnce() -> String, span: Span) -> Result<(), Diagnostic> {
+        let Some(binding) = self.local_mut(root) else {
+            // Modulszintű név: konstans vagy függvény
+            let diag = if self.consts.contains_key(root) || self.symbols.get(root).is_some_and(|ty| !matches!(ty, Type::Function(..))) {
+                Diagnostic::error(format!("cannot assign to constant `{}`", root))
+                    .with_primary(span, "cannot assign to a constant")
+            } else {
+                Diagnostic::error("invalid left-hand side of assignment")
+                    .with_primary(span, format!("`{}` is a function, not a variable", root))
+            };
+            return Err(diag.with_code(codes::INVALID_ASSIGNMENT));
+        };
+        if binding.kind == BindingKind::Mutable {
+            binding.mutated = true;
+            return Ok(());
+        }
+
+        let binding = binding.clone();
+        if binding.kind == BindingKind::Const {
+            return Err(Diagnostic::error(format!("cannot assign to constant `{}`", root))
+                .with_code(codes::INVALID_ASSIGNMENT)
+                .with_primary(span, "cannot assign to a constant")
+                .with_secondary(binding.span, format!("`{}` is declared here with `konst`", root)));
+        }
+        let diag = Diagnostic::error(message())
+            .with_code(codes::ASSIGN_TO_IMMUTABLE)
+            .with_primary(span, "cannot mutate an immutable binding");
+        Err(match binding.kind {
+            BindingKind::Param => diag
+                .with_secondary(binding.span, format!("`{}` is immutable", root))
+                .with_help(format!("copy it into a mutable variable first: `var {} = {};`", root, root)),
+            _ => diag
+                .with_secondary(binding.span, format!("`{}` is declared here with `legyen`", root))
+                .with_help(format!("declare it with `var` to make it mutable: `var {} = ...`", root))
+        })
+    }
+
+    fn check_stmt(&mut self, stmt: &Stmt) -> Result<(), Diagnostic> {
+        match &stmt.kind {
+            // A `check_block` veszi fel őket a blokk hatókörébe
+            StmtKind::Let { .. } | StmtKind::Const(_) => Ok(()),
+            StmtKind::Expr(expr) => {
+                let ty = self.check_expr(expr)?;
+                // Az `Eredmeny` hibája nem veszhet el észrevétlenül
+                if matches!(&ty, Type::Enum(name, _) if name == RESULT_TYPE && self.is_builtin_type(name)) {
+                    return Err(Diagnostic::error(format!("unused `{}` that must be used", ty))
+                        .with_code(codes::UNUSED_RESULT)
+                        .with_primary(expr.span, format!("this `{}` may be a `{}` variant, which should be handled", RESULT_TYPE, RESULT_ERR))
+                        .with_help("handle both variants with `eset`, propagate the error with `?`, or ignore it explicitly with `legyen _ = ...;`"));
+                }
+                Ok(())
+            },
+            StmtKind::Assign { target, op, value } => self.check_assign(stmt, target, *op, value),
+            StmtKind::Return(expr) => {
+                // Lezáráson kívül a függvény visszatérési típusa a várt típus
+                let expected = match &self.fn_return {
+                    Some((ty, _)) if self.closure_returns.is_empty() && *ty != Type::Never => Some(ty.clone()),
+                    _ => None
+                };
+                let return_type = match expr {
+                    Some(expr) => {
+                        let ty = self.check_expr_expecting(expr, expected.as_ref())?;
+                        self.check_escape(expr, "returning this value requires that the closure outlives the function")?;
+                        ty
+                    },
+                    None => Type::Void
+                };
+                if let Some(returns) = self.closure_returns.last_mut() {
+                    returns.push((return_type, stmt.span));
+                    return Ok(());
+                }
+
+                self.check_return(stmt, expr.as_ref(), &return_type)
+            },
+            StmtKind::While { condition, body } => {
+                self.check_condition(condition)?;
+                self.check_block(body);
+
+                Ok(())
+            },
+            StmtKind::For { pattern, iterable, body } => {
+                let iterable_type = self.check_known(iterable)?;
+                let Some(element_type) = self.element_type(&iterable_type) else {
+                    return Err(Diagnostic::error(format!("`{}` is not iterable", iterable_type))
+                        .with_code(codes::NOT_ITERABLE)
+                        .with_primary(iterable.span, format!("`{}` cannot be used in a `szor` loop", iterable_type))
+                        .with_help(format!(
+                            "ranges (`a..b`), arrays, slices and types with `{}(self) -> usize` and `{}(self, index: usize)` methods can be iterated",
+                            ITER_COUNT, ITER_GET
+                        )));
+                };
+
+                let mut bindings = HashMap::new();
+                self.check_pattern(pattern, &element_type, &mut bindings)?;
+                if let Some(missing) = Matcher::new(&self.structs, &self.enums, &self.type_generics).check(&element_type, &[(pattern, false)]).missing {
+                    return Err(Diagnostic::error(format!("refutable pattern in `szor` loop: `{}` not covered", missing))
+                        .with_code(codes::INVALID_PATTERN)
+                        .with_primary(pattern.span, format!("pattern `{}` not covered", missing))
+                        .with_note("the loop pattern must match every element"));
+                }
+
+                let borrows = self.borrowed(iterable);
+                self.with_scope(|checker| {
+                    for (name, ty) in bindings {
+                        checker.bind_borrowing(&name, ty, BindingKind::Param, pattern.span, borrows.clone());
+                    }
+                    checker.check_block(body);
+                });
+
+                Ok(())
+            }
+        }
+    }
+
+    // A `szor` ciklussal bejárható típus elemtípusa
+    fn element_type(&self, ty: &Type) -> Option<Type> {
+        if let Some(element) = array_element(ty) {
+            return Some(element);
+        }
+        match ty {
+            Type::Range(element) => Some((**element).clone()),
+            Type::Struct(name, args) | Type::Enum(name, args) => {
+                let count = self.lookup_method(name, ITER_COUNT)?;
+                let get = self.lookup_method(name, ITER_GET)?;
+                if count.generics.len() != count.impl_generics || get.generics.len() != get.impl_generics {
+                    return None;
+                }
+                let subst: HashMap<String, Type> = get.generics.iter().map(|g| g.name.clone()).zip(args.iter().cloned()).collect();
+                let usize_type = Type::Int(IntTy::Usize);
+                match (count.signature, get.signature.substitute(&subst)) {
+                    (Type::Function(count_params, count_return), Type::Function(get_params, get_return))
+                        if count.takes_self
+                            && get.takes_self
+                            && count_params.len() == 1
+                            && *count_return == usize_type
+                            && get_params.len() == 2
+                            && get_params[1] == usize_type => Some(*get_return),
+                    _ => None
+                }
+            },
+            _ => None
+        }
+    }
+
+    fn check_condition(&mut self, condition: &Expr) -> Result<(), Diagnostic> {
+        let cond_type = self.check_expr(condition)?;
+        if !self.unify(&cond_type, &Type::Bool) {
+            return Err(Diagnostic::error("condition is not a `bool`")
+                .with_code(codes::NON_BOOL_CONDITION)
+                .with_primary(condition.span, format!("expected `bool`, found `{}`", cond_type)));
+        }
+        Ok(())
+    }
+
+    // A literál értéke elfér-e a céltípusban (negált literálnál a negatív tartományt nézzük)
+    fn check_int_literal(&self, value: u128, negative: bool, ty: IntTy, span: Span) -> Result<Type, Diagnostic> {
+        // Előjel nélküli típusnál a negálást az unáris operátor ellenőrzése utasítja el
+        let negative = negative && ty.is_signed();
+        let fits = if negative { value <= ty.min_magnitude() } else { value <= ty.max_value() };
+        if !fits {
+            let literal = if negative { format!("-{}", value) } else { value.to_string() };
+            let min = if ty.is_signed() { format!("-{}", ty.min_magnitude()) } else { "0".to_string() };
+            return Err(Diagnostic::error(format!("literal out of range for `{}`", ty.name()))
+                .with_code(codes::LITERAL_OUT_OF_RANGE)
+                .with_primary(span, format!("does not fit into `{}`", ty.name()))
+                .with_note(format!(
+                    "the literal `{}` does not fit into the type `{}` whose range is `{}..={}`",
+                    literal, ty.name(), min, ty.max_value()
+                )));
+        }
+        Ok(Type::Int(ty))
+    }
+
+    // A `[...]` literál mindig rögzített méretű tömb; a várt elemtípus (`[T; N]`, `[T]` vagy
+    // `Tomb<T>` helyén) minden elemre vonatkozik, enélkül az első típusos elem határozza meg
+    fn check_array(&mut self, elements: &[Expr], expected: Option<&Type>) -> Result<Type, Diagnostic> {
+        let element_expected = expected.and_then(array_element).filter(|ty| *ty != Type::Error);
+        let leader = elements.iter().position(|element| !is_untyped_literal(element)).unwrap_or(0);
+        let (element_type, checked) = match (element_expected, elements.get(leader)) {
+            (Some(ty), _) => (ty, None),
+            (None, Some(first)) => (self.check_expr(first)?, Some(leader)),
+            // Üres literál: az elemtípust a későbbi használat köti meg
+            (None, None) => (self.fresh_var(), None)
+        };
+
+        for (i, element) in elements.iter().enumerate() {
+            if checked == Some(i) {
+                continue;
+            }
+            let ty = self.check_expr_expecting(element, Some(&element_type))?;
+            if !self.coerces(element, &ty, &element_type) {
+                return Err(Diagnostic::error("mismatched types")
+                    .with_code(codes::MISMATCHED_TYPES)
+                    .with_primary(element.span, format!("expected `{}`, found `{}`", element_type, ty))
+                    .with_note("all elements of an array must have the same type"));
+            }
+        }
+        Ok(Type::Array(Box::new(element_type), elements.len() as u64))
+    }
+
+    fn check_literal(&self, lit: &Literal, negative: bool, expected: Option<&Type>, span: Span) -> Result<Type, Diagnostic> {
+        match lit {
+            Literal::Int(value, suffix) => {
+                let ty = match (suffix, expected) {
+                    (Some(ty), _) => *ty,
+                    (None, Some(Type::Int(ty))) => *ty,
+                    (None, _) => IntTy::I32
+                };
+                self.check_int_literal(*value, negative, ty, span)
+            },
+            Literal::Float(_, suffix) => {
+                let ty = match (suffix, expected) {
+                    (Some(ty), _) => *ty,
+                    (None, Some(Type::Float(ty))) => *ty,
+                    (None, _) => FloatTy::F64
+                };
+                Ok(Type::Float(ty))
+            },
+            Literal::String(_) => Ok(Type::String),
+            Literal::ByteString(_) => Ok(Type::ByteString),
+            Literal::Char(_) => Ok(Type::Char),
+            Literal::Bool(_) => Ok(Type::Bool)
+        }
+    }
+
+    fn check_expr(&mut self, expr: &Expr) -> Result<Type, Diagnostic> {
+        self.check_expr_expecting(expr, None)
+    }
+
+    // Az `expected` típus csak az utótag nélküli szám literálok típusát befolyásolja
+    // Mező, metódus, indexelés és bejárás előtt a típusnak már ismertnek kell lennie
+    fn check_known(&mut self, expr: &Expr) -> Result<Type, Diagnostic> {
+        let ty = self.check_expr(expr)?;
+        if matches!(ty, Type::Var(_)) {
+            return Err(Diagnostic::error("type annotations needed")
+                .with_code(codes::CANNOT_INFER)
+                .with_primary(expr.span, "type must be known at this point")
+                .with_help("give the variable or parameter an explicit type"));
+        }
+        Ok(ty)
+    }
+
+    // A várt és az eredő típusban a már kikövetkeztetett típusváltozók helyén a kötésük áll
+    fn check_expr_expecting(&mut self, expr: &Expr, expected: Option<&Type>) -> Result<Type, Diagnostic> {
+        let expected = expected.map(|ty| self.resolve(ty));
+        let ty = self.check_expr_kind(expr, expected.as_ref())?;
+        let ty = self.resolve(&ty);
+        self.expr_types.insert(expr.span, ty.clone());
+        Ok(ty)
+    }
+
+    fn check_expr_kind(&mut self, expr: &Expr, expected: Option<&Type>) -> Result<Type, Diagnostic> {
+        match &expr.kind {
+            ExprKind::Literal(lit) => self.check_literal(lit, false, expected, expr.span),
+            ExprKind::Unary { op, operand } => {
+                let operand_type = match (&operand.kind, op) {
+                    (ExprKind::Literal(lit), UnaryOp::Neg) => {
+                        let ty = self.check_literal(lit, true, expected, expr.span)?;
+                        self.expr_types.insert(operand.span, ty.clone());
+                        ty
+                    },
+                    _ => self.check_expr_expecting(operand, expected)?
+                };
+
+                match (op, &operand_type) {
+                    (UnaryOp::Neg, Type::Int(ty)) if ty.is_signed() => Ok(operand_type),
+                    (UnaryOp::Neg, Type::Float(_)) => Ok(operand_type),
+                    (UnaryOp::Not, Type::Bool) => Ok(operand_type),
+                    (UnaryOp::Neg, _) => Err(Diagnostic::error(format!("cannot apply unary operator `-` to type `{}`", operand_type))
+                        .with_code(codes::INVALID_OPERANDS)
+                        .with_primary(expr.span, "cannot apply unary operator `-`")),
+                    (UnaryOp::Not, _) => Err(Diagnostic::error(format!("cannot apply unary operator `!` to type `{}`", operand_type))
+                        .with_code(codes::INVALID_OPERANDS)
+                        .with_primary(expr.span, "cannot apply unary operator `!`"))
+                }
+            },
+            ExprKind::Ident(name) => {
+                let ty = self.lookup(name).ok_or_else(|| {
+                    let diag = Diagnostic::error(format!("cannot find value `{}` in this scope", name))
+                        .with_code(codes::UNDEFINED_NAME)
+                        .with_primary(expr.span, "not found in this scope");
+                    self.suggest_name(diag, name)
+                })?;
+                // Függvényértékként csak nem generikus függvény adható át
+                if self.generic_fns.contains_key(name) && self.local(name).is_none() {
+                    return Err(Diagnostic::error(format!("cannot use generic function `{}` as a value", name))
+                        .with_code(codes::CANNOT_INFER)
+                        .with_primary(expr.span, "type parameters cannot be inferred here")
+                        .with_help(format!("wrap it in a closure: `|x: i32| {}(x)`", name)));
+                }
+                Ok(ty)
+            },
+            ExprKind::BinaryOp { left, op, right } => {
+                // Az utótag nélküli literál a másik oldal típusát veszi fel (`x + 1`, `1 + x`)
+                let is_arithmetic = matches!(op, BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div);
+                let operand_expected = if is_arithmetic { expected } else { None };
+                let (left_type, right_type) = if is_untyped_literal(left) && !is_untyped_literal(right) {
+                    let right_type = self.check_expr_expecting(right, operand_expected)?;
+                    (self.check_expr_expecting(left, Some(&right_type))?, right_type)
+                } else {
+                    let left_type = self.check_expr_expecting(left, operand_expected)?;
+                    (left_type.clone(), self.check_expr_expecting(right, Some(&left_type))?)
+                };
+
+                if !self.unify(&left_type, &right_type) {
+                    let diag = Diagnostic::error("mismatched types in binary operation")
+                        .with_code(codes::MISMATCHED_TYPES)
+                        .with_primary(right.span, format!("expected `{}`, found `{}`", left_type, right_type))
+                        .with_secondary(left.span, format!("this is of type `{}`", left_type));
+                    return Err(cast_help(diag, right, &right_type, &left_type));
+                }
+
+                let left_type = self.resolve(&left_type);
+                match op {
+                    // Ismeretlen operandustípusnál a megkötés a típus kikövetkeztetéséig vár
+                    BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div if matches!(left_type, Type::Var(_)) => {
+                        self.pending.push((left_type.clone(), Pending::Numeric, expr.span));
+                        Ok(left_type)
+                    },
+                    BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => {
+                        if left_type.is_numeric() {
+                            Ok(left_type)
+                        } else {
+                            Err(Diagnostic::error(format!("cannot apply arithmetic operator to `{}`", left_type))
+                                .with_code(codes::INVALID_OPERANDS)
+                                .with_primary(expr.span, "arithmetic operations require numbers"))
+                        }
+                    },
+                    BinaryOp::Eq | BinaryOp::Neq | BinaryOp::Lt | BinaryOp::Gt | BinaryOp::Le | BinaryOp::Ge => {
+                        // Generikus és felhasználói típusú értékek csak `Osszehasonlithato` implementációval hasonlíthatók össze
+                        let user_type = matches!(left_type, Type::Param(_) | Type::Struct(..) | Type::Enum(..) | Type::Dyn(_) | Type::Var(_));
+                        if user_type {
+                            self.require(&left_type, BOUND_ORD, expr.span)?;
+                        }
+                        Ok(Type::Bool)
+                    },
+                    BinaryOp::And | BinaryOp::Or => {
+                        if self.unify(&left_type, &Type::Bool) {
+                            Ok(Type::Bool)
+                        } else {
+                            Err(Diagnostic::error(format!("cannot apply logical operator to `{}`", left_type))
+                                .with_code(codes::INVALID_OPERANDS)
+                                .with_primary(expr.span, "logical operations require booleans"))
+                        }
+                    }
+                }
+            },
+            ExprKind::Call { callee, args } => {
+                let fv_type = self.lookup(callee).ok_or_else(|| {
+                    let diag = Diagnostic::error(format!("cannot find function `{}` in this scope", callee))
+                        .with_code(codes::UNDEFINED_NAME)
+                        .with_primary(expr.span, "not found in this scope");
+                    self.suggest_name(diag, callee)
+                })?;
+                match self.resolve(&fv_type) {
+                    Type::Function(param_types, return_type) => {
+                        if let Some(generics) = self.generic_fns.get(callee).cloned().filter(|_| self.local(callee).is_none()) {
+                            return self.check_generic_call(
+                                callee, &generics, HashMap::new(), &param_types, &return_type, args, expected, expr.span
+                            );
+                        }
+                        self.check_args(callee, args, &param_types, expr.span)?;
+                        if callee == BUILTIN_SPAWN {
+                            self.check_escape(&args[0], "the closure is run on another thread")?;
+                        }
+                        Ok(*return_type)
+                    },
+                    // Ismeretlen típusú (paraméterként kapott) függvény: a hívás alakja határozza meg
+                    Type::Var(_) => {
+                        let param_types: Vec<Type> = args.iter().map(|_| self.fresh_var()).collect();
+                        let return_type = self.fresh_var();
+                        self.unify(&fv_type, &Type::Function(param_types.clone(), Box::new(return_type.clone())));
+                        self.check_args(callee, args, &param_types, expr.span)?;
+                        Ok(return_type)
+                    },
+                    other => Err(Diagnostic::error(format!("`{}` is not a function", callee))
+                        .with_code(codes::NOT_A_FUNCTION)
+                        .with_primary(expr.span, format!("`{}` has type `{}`", callee, other)))
+                }
+            },
+            ExprKind::If { condition, then_branch, else_branch } => {
+                self.check_condition(condition)?;
+                self.check_block(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.check_block(else_branch);
+                }
+
+                Ok(Type::Void)
+            },
+            ExprKind::Interpolated(parts) => {
+                for part in parts {
+                    let InterpPart::Expr(part) = part else { continue };
+                    let part_type = self.check_expr(part)?;
+                    if matches!(part_type, Type::Var(_)) {
+                        self.require(&part_type, BOUND_DISPLAY, part.span)?;
+                    } else if !self.satisfies(&part_type, BOUND_DISPLAY) {
+                        return Err(Diagnostic::error(format!("`{}` cannot be formatted into a string", part_type))
+                            .with_code(codes::NOT_PRINTABLE)
+                            .with_primary(part.span, format!("this is of type `{}`", part_type))
+                            .with_note(format!("only values implementing `{}` can be interpolated", BOUND_DISPLAY)));
+                    }
+                }
+
+                Ok(Type::String)
+            },
+            ExprKind::StructLit { name, fields } => {
+                let Some(def) = self.structs.get(name).cloned() else {
+                    return Err(Diagnostic::error(format!("cannot find struct `{}` in this scope", name))
+                        .with_code(codes::UNDEFINED_NAME)
+                        .with_primary(expr.span, "not found in this scope"));
+                };
+
+                // Generikus structnál a típusargumentumok a mezők értékéből (és a várt típusból) adódnak
+                let mut subst: HashMap<String, Type> = HashMap::new();
+                let self_type = Type::Struct(name.clone(), self.generic_self_type(name));
+                if let Some(expected) = expected {
+                    let mut from_expected = HashMap::new();
+                    if bind_params(&self_type, expected, &mut from_expected) {
+                        subst = from_expected;
+                    }
+                }
+
+                let mut initialized: HashMap<&str, Span> = HashMap::new();
+                for init in fields {
+                    let Some((_, field_type)) = def.iter().find(|(field, _)| *field == init.name) else {
+                        return Err(Diagnostic::error(format!("struct `{}` has no field named `{}`", name, init.name))
+                            .with_code(codes::UNKNOWN_FIELD)
+                            .with_primary(init.span, "unknown field"));
+                    };
+                    if let Some(prev) = initialized.insert(&init.name, init.span) {
+                        return Err(Diagnostic::error(format!("field `{}` specified more than once", init.name))
+                            .with_code(codes::DUPLICATE_DEFINITION)
+                            .with_primary(init.span, "used more than once")
+                            .with_secondary(prev, "first use of this field"));
+                    }
+
+                    let field_expected = field_type.substitute(&subst);
+                    let hint = (!field_expected.has_params()).then_some(&field_expected);
+                    let value_type = self.check_expr_expecting(&init.value, hint)?;
+                    if !bind_params(field_type, &value_type, &mut subst) && !self.coerces(&init.value, &value_type, &field_type.substitute(&subst)) {
+                        return Err(Diagnostic::error("mismatched types")
+                            .with_code(codes::MISMATCHED_TYPES)
+                            .with_primary(init.value.span, format!("expected `{}`, found `{}`", field_type.substitute(&subst), value_type))
+                            .with_note(format!("field `{}` of `{}` has type `{}`", init.name, name, field_type)));
+                    }
+                }
+
+                let missing: Vec<String> = def.iter()
+                    .filter(|(field, _)| !initialized.contains_key(field.as_str()))
+                    .map(|(field, _)| format!("`{}`", field))
+                    .collect();
+                if !missing.is_empty() {
+                    return Err(Diagnostic::error(format!("missing fields {} in initializer of `{}`", missing.join(", "), name))
+                        .with_code(codes::MISSING_FIELDS)
+                        .with_primary(expr.span, "missing fields"));
+                }
+
+                let mut type_args = Vec::new();
+                for generic in self.type_generics.get(name).cloned().unwrap_or_default() {
+                    let Some(ty) = subst.get(&generic.name).cloned() else {
+                        return Err(Diagnostic::error(format!("type annotations needed: cannot infer type parameter `{}` of `{}`", generic.name, name))
+                            .with_code(codes::CANNOT_INFER)
+                            .with_primary(expr.span, format!("cannot infer type for `{}`", generic.name)));
+                    };
+                    if let Some(bound) = generic.bounds.iter().find(|bound| !self.satisfies(&ty, bound)) {
+                        return Err(self.unsatisfied_bound(&ty, bound, expr.span));
+                    }
+                    type_args.push(ty);
+                }
+                Ok(Type::Struct(name.clone(), type_args))
+            },
+            ExprKind::Field { object, field } => {
+                let object_type = self.check_known(object)?;
+                let field_type = match &object_type {
+                    Type::Struct(name, args) => self.struct_fields(name, args)
+                        .and_then(|fields| fields.into_iter().find(|(f, _)| f == field))
+                        .map(|(_, ty)| ty),
+                    _ => None
+                };
+                field_type.ok_or_else(|| {
+                    Diagnostic::error(format!("no field `{}` on type `{}`", field, object_type))
+                        .with_code(codes::UNKNOWN_FIELD)
+                        .with_primary(expr.span, "unknown field")
+                })
+            },
+            ExprKind::MethodCall { receiver, method, args } => {
+                let receiver_type = self.check_known(receiver)?;
+                if let Some(element) = array_element(&receiver_type) {
+                    match method.as_str() {
+                        ARRAY_LEN => {
+                            self.check_args(ARRAY_LEN, args, &[], expr.span)?;
+                            return Ok(Type::Int(IntTy::Usize));
+                        },
+                        ARRAY_PUSH if receiver_type.is_dyn_array() => {
+                            if let Some(root) = place_root(receiver) {
+                                self.check_mutable(root, || format!("cannot push to `{}`, as `{}` is not declared as mutable", place_text(receiver), root), expr.span)?;
+                            }
+                            self.check_args(ARRAY_PUSH, args, &[element], expr.span)?;
+                            return Ok(Type::Void);
+                        },
+                        _ => {}
+                    }
+                }
+                if let (Type::Range(element), RANGE_STEP) = (&receiver_type, method.as_str()) {
+                    self.check_args(RANGE_STEP, args, &[(**element).clone()], expr.span)?;
+                    if matches!(args[0].kind, ExprKind::Literal(Literal::Int(0, _)) | ExprKind::Unary { op: UnaryOp::Neg, .. }) {
+                        return Err(Diagnostic::error("range step must be positive")
+                            .with_code(codes::INVALID_OPERANDS)
+                            .with_primary(args[0].span, "step must be greater than zero"));
+                    }
+                    return Ok(receiver_type);
+                }
+
+                // Más típusoknál (generikus paraméter, `dyn Trait`, primitív) a traitjeik metódusai hívhatók
+                let (found, type_name, type_args) = match &receiver_type {
+                    Type::Struct(name, args) | Type::Enum(name, args) => (self.lookup_method(name, method), name.clone(), args.clone()),
+                    _ => (self.trait_method(&receiver_type, method), receiver_type.to_string(), Vec::new())
+                };
+                let Some(found) = found else {
+                    return Err(Diagnostic::error(format!("no method named `{}` found for type `{}`", method, receiver_type))
+                        .with_code(codes::UNKNOWN_METHOD)
+                        .with_primary(expr.span, "method not found"));
+                };
+                if !found.takes_self {
+                    return Err(Diagnostic::error(format!("`{}` is an associated function, not a method", method))
+                        .with_code(codes::UNKNOWN_METHOD)
+                        .with_primary(expr.span, "this is an associated function, not a method")
+                        .with_secondary(found.span, "defined here without a `self` parameter")
+                        .with_help(format!("use associated function syntax instead: `{}::{}(...)`", type_name, method)));
+                }
+
+                let Type::Function(param_types, return_type) = found.signature else { unreachable!() };
+                let callee = format!("{}::{}", type_name, method);
+                if !found.generics.is_empty() {
+                    // Az impl blokk paraméterei a fogadó típusargumentumai
+                    let subst = found.generics[..found.impl_generics].iter()
+                        .map(|g| g.name.clone())
+                        .zip(type_args.iter().cloned())
+                        .collect();
+                    return self.check_generic_call(
+                        &callee, &found.generics, subst, &param_types[1..], &return_type, args, expected, expr.span
+                    );
+                }
+                self.check_args(&callee, args, &param_types[1..], expr.span)?;
+                Ok(*return_type)
+            },
+            ExprKind::Tuple(elements) if elements.is_empty() => Ok(Type::Void),
+            ExprKind::Tuple(elements) => {
+                let expected_elements = match expected {
+                    Some(Type::Tuple(tys)) if tys.len() == elements.len() => Some(tys),
+                    _ => None
+                };
+                let mut types = Vec::new();
+                for (i, element) in elements.iter().enumerate() {
+                    let element_expected = expected_elements.map(|tys| &tys[i]);
+                    types.push(self.check_expr_expecting(element, element_expected)?);
+                }
+                Ok(Type::Tuple(types))
+            },
+            ExprKind::Array(elements) => self.check_array(elements, expected),
+            ExprKind::Index { object, index } => {
+                let object_type = self.check_known(object)?;
+                if object_type == Type::Error {
+                    return Ok(Type::Error);
+                }
+                let Some(element) = array_element(&object_type) else {
+                    return Err(Diagnostic::error(format!("cannot index into a value of type `{}`", object_type))
+                        .with_code(codes::NOT_INDEXABLE)
+                        .with_primary(object.span, "this value cannot be indexed")
+                        .with_note(format!("arrays, slices and `{}<T>` can be indexed", DYN_ARRAY_TYPE)));
+                };
+
+                let index_type = self.check_expr_expecting(index, Some(&Type::Int(IntTy::Usize)))?;
+                match &index_type {
+                    Type::Int(_) | Type::Error => {},
+                    Type::Range(bound) if matches!(**bound, Type::Int(_)) => return Ok(Type::Slice(Box::new(element))),
+                    _ => return Err(Diagnostic::error(format!("the type `{}` cannot be indexed by `{}`", object_type, index_type))
+                        .with_code(codes::MISMATCHED_TYPES)
+                        .with_primary(index.span, "indices must be integers or integer ranges"))
+                }
+                // A konstans index a rögzített hosszal már most összevethető
+                if let (Type::Array(_, len), ExprKind::Literal(Literal::Int(value, _))) = (&object_type, &index.kind) {
+                    if *value >= *len as u128 {
+                        return Err(Diagnostic::error("this operation will panic at run time")
+                            .with_code(codes::INDEX_OUT_OF_BOUNDS)
+                            .with_primary(expr.span, format!("index out of bounds: the length is {} but the index is {}", len, value)));
+                    }
+                }
+                Ok(element)
+            },
+            ExprKind::Block(stmts) => {
+                self.check_block(stmts);
+                Ok(Type::Void)
+            },
+            ExprKind::Range { start, end, .. } => {
+                let element_expected = match expected {
+                    Some(Type::Range(element)) => Some(&**element),
+                    _ => None
+                };
+                // Mint a bináris operátoroknál: az utótag nélküli literál a másik határ típusát veszi fel
+                let (start_type, end_type) = if is_untyped_literal(start) && !is_untyped_literal(end) {
+                    let end_type = self.check_expr_expecting(end, element_expected)?;
+                    (self.check_expr_expecting(start, Some(&end_type))?, end_type)
+                } else {
+                    let start_type = self.check_expr_expecting(start, element_expected)?;
+                    (start_type.clone(), self.check_expr_expecting(end, Some(&start_type))?)
+                };
+
+                if !self.unify(&start_type, &end_type) {
+                    return Err(Diagnostic::error("mismatched types in range")
+                        .with_code(codes::MISMATCHED_TYPES)
+                        .with_primary(end.span, format!("expected `{}`, found `{}`", start_type, end_type))
+                        .with_secondary(start.span, format!("this is of type `{}`", start_type)));
+                }
+                let start_type = self.resolve(&start_type);
+                if !matches!(start_type, Type::Int(_)) {
+                    return Err(Diagnostic::error(format!("range bounds must be integers, found `{}`", start_type))
+                        .with_code(codes::INVALID_OPERANDS)
+                        .with_primary(expr.span, "not an integer range"));
+                }
+
+                Ok(Type::Range(Box::new(start_type)))
+            },
+            ExprKind::Match { scrutinee, arms } => {
+                let scrutinee_type = self.check_expr(scrutinee)?;
+                let borrows = self.borrowed(scrutinee);
+
+                // Az első nem divergáló ág típusa a teljes kifejezés típusa (ha minden ág divergál, `!`)
+                let mut result: Option<(Type, Span)> = None;
+                for arm in arms {
+                    let mut bindings = HashMap::new();
+                    self.check_pattern(&arm.pattern, &scrutinee_type, &mut bindings)?;
+                    let arm_expected = result.as_ref().map(|(ty, _)| ty).or(expected);
+                    let body_type = self.with_scope(|checker| {
+                        for (name, ty) in bindings {
+                            checker.bind_borrowing(&name, ty, BindingKind::Param, arm.pattern.span, borrows.clone());
+                        }
+                        if let Some(guard) = &arm.guard {
+                            checker.check_condition(guard)?;
+                        }
+                        checker.check_expr_expecting(&arm.body, arm_expected)
+                    })?;
+                    match &result {
+                        _ if body_type == Type::Never => {},
+                        None => result = Some((body_type, arm.body.span)),
+                        Some((first_type, first_span)) if !self.unify(first_type, &body_type) => {
+                            return Err(Diagnostic::error("`eset` arms have incompatible types")
+                                .with_code(codes::MISMATCHED_TYPES)
+                                .with_primary(arm.body.span, format!("expected `{}`, found `{}`", first_type, body_type))
+                                .with_secondary(*first_span, format!("this is found to be of type `{}`", first_type)));
+                        },
+                        Some(_) => {}
+                    }
+                }
+
+                if scrutinee_type != Type::Error {
+                    let patterns: Vec<(&Pattern, bool)> = arms.iter().map(|arm| (&arm.pattern, arm.guard.is_some())).collect();
+                    let report = Matcher::new(&self.structs, &self.enums, &self.type_generics).check(&scrutinee_type, &patterns);
+                    for i in report.unreachable {
+                        self.diagnostics.push(Diagnostic::warning("unreachable pattern")
+                            .with_code(codes::UNREACHABLE_PATTERN)
+                            .with_primary(arms[i].pattern.span, "no value can reach this arm"));
+                    }
+                    if let Some(missing) = report.missing {
+                        return Err(Diagnostic::error(format!("non-exhaustive patterns: `{}` not covered", missing))
+                            .with_code(codes::NON_EXHAUSTIVE_PATTERNS)
+                            .with_primary(scrutinee.span, format!("pattern `{}` not covered", missing))
+                            .with_note(format!("the matched value is of type `{}`", scrutinee_type))
+                            .with_help("add an arm for the missing case, or a wildcard `_` arm"));
+                    }
+                }
+
+                let fallback = if arms.is_empty() { Type::Void } else { Type::Never };
+                Ok(result.map_or(fallback, |(ty, _)| ty))
+            },
+            ExprKind::AssocCall { type_name, name, args } => {
+                // `Alak::Kor(1.0)`: a változat konstruktora
+                let self_args = self.generic_self_type(type_name);
+                if let Some(field_types) = self.variant(type_name, name, &self_args) {
+                    return self.check_variant_constructor(type_name, name, &field_types, args, expected, expr.span);
+                }
+                if !self.structs.contains_key(type_name) && !self.enums.contains_key(type_name) {
+                    return Err(Diagnostic::error(format!("cannot find type `{}` in this scope", type_name))
+                        .with_code(codes::UNDEFINED_NAME)
+                        .with_primary(expr.span, "not found in this scope"));
+                }
+                let Some(found) = self.lookup_method(type_name, name) else {
+                    return Err(Diagnostic::error(format!("no function or associated item named `{}` found for `{}`", name, type_name))
+                        .with_code(codes::UNKNOWN_METHOD)
+                        .with_primary(expr.span, "associated item not found"));
+                };
+
+                // `Pont::hossz(p)` is megengedett: ilyenkor a `self` az első argumentum
+                let Type::Function(param_types, return_type) = found.signature else { unreachable!() };
+                let callee = format!("{}::{}", type_name, name);
+                if !found.generics.is_empty() {
+                    return self.check_generic_call(
+                        &callee, &found.generics, HashMap::new(), &param_types, &return_type, args, expected, expr.span
+                    );
+                }
+                self.check_args(&callee, args, &param_types, expr.span)?;
+                Ok(*return_type)
+            },
+            ExprKind::Closure { params, return_type, body, .. } => {
+                let in_async = std::mem::replace(&mut self.in_async, false);
+                let result = self.check_closure(expr, params, return_type, body, expected);
+                self.in_async = in_async;
+                result
+            },
+            ExprKind::Await(future) => self.check_await(expr, future, expected),
+            ExprKind::Cast { expr: operand, target } => self.check_cast(expr, operand, target),
+            ExprKind::Try(operand) => self.check_try(expr, operand),
+            ExprKind::Path { type_name, name } => match self.variant(type_name, name, &self.generic_self_type(type_name)) {
+                Some(field_types) if field_types.is_empty() => {
+                    self.check_variant_constructor(type_name, name, &field_types, &[], expected, expr.span)
+                },
+                Some(field_types) => {
+                    let placeholders = vec!["_"; field_types.len()].join(", ");
+                    Err(Diagnostic::error(format!("expected value, found tuple variant `{}::{}`", type_name, name))
+                        .with_code(codes::WRONG_ARG_COUNT)
+                        .with_primary(expr.span, format!("this variant has {} fields", field_types.len()))
+                        .with_help(format!("use the constructor: `{}::{}({})`", type_name, name, placeholders)))
+                },
+                None if self.enums.contains_key(type_name) || self.structs.contains_key(type_name) => {
+                    Err(Diagnostic::error(format!("no variant or associated item named `{}` found for `{}`", name, type_name))
+                        .with_code(codes::UNKNOWN_METHOD)
+                        .with_primary(expr.span, "variant or associated item not found"))
+                },
+                None => Err(Diagnostic::error(format!("cannot find type `{}` in this scope", type_name))
+                    .with_code(codes::UNDEFINED_NAME)
+                    .with_primary(expr.span, "not found in this scope"))
+            }
+        }
+    }
+
+    // `x?`: `Opcio<T>` csak `Opcio`-t, `Eredmeny<T, E>` csak azonos hibatípusú `Eredmeny`-t
+    // visszaadó függvényben használható; az értéke a `T`
+    fn check_try(&mut self, expr: &Expr, operand: &Expr) -> Result<Type, Diagnostic> {
+        let operand_type = self.check_known(operand)?;
+        let (value_type, error_type) = match &operand_type {
+            Type::Enum(name, args) if name == OPTION_TYPE && self.is_builtin_type(name) => (args[0].clone(), None),
+            Type::Enum(name, args) if name == RESULT_TYPE && self.is_builtin_type(name) => (args[0].clone(), Some(args[1].clone())),
+            Type::Error => return Ok(Type::Error),
+            other => return Err(Diagnostic::error(format!("the `?` operator can only be applied to values of type `{}` or `{}`", OPTION_TYPE, RESULT_TYPE))
+                .with_code(codes::INVALID_TRY)
+                .with_primary(operand.span, format!("the `?` operator cannot be applied to type `{}`", other)))
+        };
+        if !self.closure_returns.is_empty() {
+            return Err(Diagnostic::error("the `?` operator cannot be used inside a closure")
+                .with_code(codes::INVALID_TRY)
+                .with_primary(expr.span, "this would return from the closure, not from the enclosing function")
+                .with_help("use `eset` to handle both variants"));
+        }
+        let Some((return_type, annot)) = self.fn_return.clone() else {
+            return Err(Diagnostic::error("the `?` operator can only be used inside a function")
+                .with_code(codes::INVALID_TRY)
+                .with_primary(expr.span, "cannot use the `?` operator here"));
+        };
+
+        let kind = if error_type.is_some() { RESULT_TYPE } else { OPTION_TYPE };
+        let return_type = self.resolve(&return_type);
+        let diag = match (&return_type, &error_type) {
+            (Type::Enum(name, _), None) if name == OPTION_TYPE => return Ok(value_type),
+            (Type::Enum(name, args), Some(error_type)) if name == RESULT_TYPE => {
+                if self.unify(&args[1], error_type) {
+                    return Ok(value_type);
+                }
+                Diagnostic::error(format!("`?` couldn't convert the error to `{}`", args[1]))
+                    .with_primary(expr.span, format!("the error is of type `{}`, but the function returns errors of type `{}`", error_type, args[1]))
+            },
+            (Type::Enum(name, _), _) if name == OPTION_TYPE || name == RESULT_TYPE => {
+                Diagnostic::error(format!("the `?` operator can only be used on `{}` values in a function that returns `{}`", name, name))
+                    .with_primary(expr.span, format!("this is of type `{}`", operand_type))
+            },
+            _ => Diagnostic::error(format!("the `?` operator can only be used in a function that returns `{}` or `{}`", OPTION_TYPE, RESULT_TYPE))
+                .with_primary(expr.span, format!("cannot use the `?` operator in a function that returns `{}`", return_type))
+        };
+        let diag = diag.with_code(codes::INVALID_TRY);
+        Err(match annot {
+            Some(annot) => diag.with_secondary(annot, format!("expected `{}` because of this return type", return_type)),
+            None => diag.with_help(format!("add a return type to the function, for example `-> {}<...>`", kind))
+        })
+    }
+
+    // `x as T`: számtípusok egymás közt, `bool`/`char` egésszé, `u8` karakterré alakítható
+    fn check_cast(&mut self, expr: &Expr, operand: &Expr, target: &TypeAnnot) -> Result<Type, Diagnostic> {
+        self.check_annotation(target);
+        let target_type = self.parse_type(target);
+        // Az utótag nélküli literál a cél típusát veszi fel, így a tartománya is ellenőrzött (`300 as u8`)
+        let operand_type = if is_untyped_literal(operand) {
+            self.check_expr_expecting(operand, Some(&target_type))?
+        } else {
+            self.check_known(operand)?
+        };
+
+        let valid = match (&operand_type, &target_type) {
+            (Type::Error, _) | (_, Type::Error) => true,
+            (Type::Int(_) | Type::Float(_), Type::Int(_) | Type::Float(_)) => true,
+            (Type::Bool | Type::Char, Type::Int(_)) => true,
+            (Type::Int(IntTy::U8), Type::Char) => true,
+            (from, to) => from == to
+        };
+        if valid {
+            return Ok(target_type);
+        }
+
+        let primitive = |ty: &Type| matches!(ty, Type::Int(_) | Type::Float(_) | Type::Bool | Type::Char);
+        let message = if primitive(&operand_type) && primitive(&target_type) {
+            format!("invalid cast from `{}` to `{}`", operand_type, target_type)
+        } else {
+            format!("non-primitive cast: `{}` as `{}`", operand_type, target_type)
+        };
+        let diag = Diagnostic::error(message)
+            .with_code(codes::INVALID_CAST)
+            .with_primary(expr.span, "an `as` expression can only convert between numbers, `bool` and `char`");
+        Err(match (&operand_type, &target_type) {
+            (Type::Int(_), Type::Char) => diag.with_help("only `u8` can be cast to `char`"),
+            (Type::Int(_) | Type::Float(_), Type::Bool) => diag.with_help(format!("compare with zero instead: `{} != 0`", place_text(operand))),
+            _ => diag
+        })
+    }
+
+    // A paraméterek típusa a jelölésből vagy a várt függvénytípusból, a visszatérési típus a
+    // jelölésből, a törzs kifejezéséből, illetve blokktörzsnél a `vissza` utasításokból adódik
+    fn check_closure(
+        &mut self,
+        expr: &Expr,
+        params: &[Param],
+        return_type: &Option<TypeAnnot>,
+        body: &Expr,
+        expected: Option<&Type>
+    ) -> Result<Type, Diagnostic> {
+        let (expected_params, expected_return) = match expected {
+            Some(Type::Function(param_types, ret)) if param_types.len() == params.len() => (Some(param_types.as_slice()), Some(&**ret)),
+            _ => (None, None)
+        };
+
+        let mut param_types = Vec::new();
+        for (i, param) in params.iter().enumerate() {
+            let param_type = match (&param.type_annot, expected_params) {
+                (Some(annot), _) => {
+                    self.check_annotation(annot);
+                    self.parse_type(annot)
+                },
+                (None, Some(types)) if !types[i].has_params() => types[i].clone(),
+                // A törzs és a lezárás hívásai következtetik ki
+                (None, _) => self.param_type(param)
+            };
+            param_types.push(param_type);
+        }
+
+        let declared = return_type.as_ref().map(|annot| {
+            self.check_annotation(annot);
+            self.parse_type(annot)
+        });
+        let hint = declared.clone().or_else(|| expected_return.filter(|ty| !ty.has_params()).cloned());
+        self.closure_returns.push(Vec::new());
+        let body_type = self.with_scope(|checker| {
+            checker.bind_params(params, &param_types);
+            checker.check_expr_expecting(body, hint.as_ref())
+        });
+        let mut results = self.closure_returns.pop().unwrap_or_default();
+        let body_type = body_type?;
+
+        if !matches!(body.kind, ExprKind::Block(_)) {
+            results.insert(0, (body_type, body.span));
+        }
+        // A soha vissza nem térő törzs (`|| panik("...")`) nem határozza meg a visszatérési típust
+        results.retain(|(ty, _)| *ty != Type::Never);
+        if let (Some(declared), Some(annot), ExprKind::Block(stmts)) = (&declared, return_type, &body.kind) {
+            self.check_missing_return(stmts, declared, annot.span);
+        }
+        let result_type = declared.or_else(|| results.first().map(|(ty, _)| ty.clone())).unwrap_or(Type::Void);
+        for (ty, span) in &results {
+            if !self.unify(ty, &result_type) {
+                let result_type = self.resolve(&result_type);
+                return Err(Diagnostic::error("mismatched types")
+                    .with_code(codes::MISMATCHED_TYPES)
+                    .with_primary(*span, format!("expected `{}`, found `{}`", result_type, self.resolve(ty)))
+                    .with_note(format!("the closure returns `{}`", result_type)));
+            }
+        }
+
+        // Csak a körülvevő hatókörök helyi kötéseit kell elkapni, a globális függvényeket nem
+        let captured = captures::free_variables(params, body).into_iter()
+            .filter(|name| self.local(name).is_some())
+            .collect();
+        self.captures.insert(expr.span, captured);
+
+        Ok(Type::Function(param_types, Box::new(result_type)))
+    }
+
+    // Az `akkor` bármely részkifejezésben állhat: a kódgenerátor a felfüggesztés előtt kiszámolt
+    // értékeket a keretbe menti
+    fn check_await(&mut self, expr: &Expr, future: &Expr, expected: Option<&Type>) -> Result<Type, Diagnostic> {
+        if !self.in_async {
+            return Err(Diagnostic::error("`akkor` is only allowed inside `aszink` functions")
+                .with_code(codes::INVALID_AWAIT)
+                .with_primary(expr.span, "only allowed inside `aszink` functions")
+                .with_help(format!("mark the function `aszink fv`, or run the future to completion with `{}(...)`", BUILTIN_BLOCK_ON)));
+        }
+
+        let expected = expected.map(|output| future_type(output.clone()));
+        match self.check_expr_expecting(future, expected.as_ref())? {
+            Type::Struct(name, args) if name == FUTURE_TYPE && args.len() == 1 => Ok(args[0].clone()),
+            Type::Error => Ok(Type::Error),
+            other => Err(Diagnostic::error("mismatched types")
+                .with_code(codes::MISMATCHED_TYPES)
+                .with_primary(future.span, format!("expected `{}<_>`, found `{}`", FUTURE_TYPE, other))
+                .with_note("only futures returned by `aszink` functions can be awaited"))
+        }
+    }
+
+    // A környezetét hivatkozással elkapó lezárás nem élheti túl az elkapott változókat:
+    // sem közvetlenül, sem változón, struct-on, tömbön vagy függvényhíváson keresztül
+    fn check_escape(&self, expr: &Expr, reason: &str) -> Result<(), Diagnostic> {
+        let Some(name) = self.borrowed(expr) else { return Ok(()) };
+        Err(Diagnostic::error(format!("closure may outlive the current function, but it borrows `{}`", name))
+            .with_code(codes::ESCAPING_CLOSURE)
+            .with_primary(expr.span, format!("may outlive borrowed value `{}`", name))
+            .with_note(reason)
+            .with_help(format!("to force the closure to take ownership of `{}`, use `mozgat`", name)))
+    }
+
+    // Ha a kifejezés értéke (vagy egy része) hivatkozással elkapó lezárás, az elkapott változó neve
+    fn borrowed(&self, expr: &Expr) -> Option<String> {
+        match &expr.kind {
+            // A `mozgat` lezárás is hivatkozást tart, ha egy ilyen lezárást kap el
+            ExprKind::Closure { is_move, .. } => {
+                let captured = self.captures.get(&expr.span)?;
+                match captured.first() {
+                    Some(name) if !is_move => Some(name.clone()),
+                    _ => captured.iter().find_map(|name| self.local(name).and_then(|binding| binding.borrows.clone()))
+                }
+            },
+            ExprKind::Ident(name) => self.local(name).and_then(|binding| binding.borrows.clone()),
+            ExprKind::StructLit { fields, .. } => fields.iter().find_map(|field| self.borrowed(&field.value)),
+            ExprKind::Array(elements) | ExprKind::Tuple(elements) => elements.iter().find_map(|element| self.borrowed(element)),
+            ExprKind::Field { object, .. } | ExprKind::Index { object, .. } => self.borrowed(object),
+            ExprKind::Try(operand) => self.borrowed(operand),
+            ExprKind::Match { scrutinee, arms } => self.borrowed(scrutinee).or_else(|| arms.iter().find_map(|arm| self.borrowed(&arm.body))),
+            // A hívás visszaadhatja a kapott lezárást, kivéve ha az eredmény egyszerű érték
+            ExprKind::Call { args, .. } | ExprKind::AssocCall { args, .. } | ExprKind::MethodCall { args, .. } => {
+                let result = self.expr_types.get(&expr.span).map(|ty| self.resolve(ty));
+                if matches!(result, Some(Type::Int(_) | Type::Float(_) | Type::Bool | Type::Char | Type::String | Type::ByteString | Type::Void | Type::Never)) {
+                    return None;
+                }
+                let receiver = match &expr.kind {
+                    ExprKind::MethodCall { receiver, .. } => self.borrowed(receiver),
+                    _ => None
+                };
+                receiver.or_else(|| args.iter().find_map(|arg| self.borrowed(arg)))
+            },
+            _ => None
+        }
+    }
+
+    // Generikus enumnál a típusargumentumok az adatmezőkből (vagy a várt típusból) adódnak
+    #[allow(clippy::too_many_arguments)]
+    fn check_variant_constructor(
+        &mut self,
+        enum_name: &str,
+        variant: &str,
+        field_types: &[Type],
+        args: &[Expr],
+        expected: Option<&Type>,
+        span: Span
+    ) -> Result<Type, Diagnostic> {
+        let callee = format!("{}::{}", enum_name, variant);
+        let generics = self.type_generics.get(enum_name).cloned().unwrap_or_default();
+        if generics.is_empty() {
+            self.check_args(&callee, args, field_types, span)?;
+            return Ok(Type::Enum(enum_name.to_string(), Vec::new()));
+        }
+        let self_type = Type::Enum(enum_name.to_string(), self.generic_self_type(enum_name));
+        self.check_generic_call(&callee, &generics, HashMap::new(), field_types, &self_type, args, expected, span)
+    }
+
+    // A minta illeszkedik-e az `expected` típusra; a kötött neveket a `bindings`-be gyűjti
+    fn check_pattern(&mut self, pattern: &Pattern, expected: &Type, bindings: &mut HashMap<String, Type>) -> Result<(), Diagnostic> {
+        self.expr_types.insert(pattern.span, expected.clone());
+
+        match &pattern.kind {
+            PatternKind::Wildcard => Ok(()),
+            PatternKind::Binding(name) => {
+                if bindings.insert(name.clone(), expected.clone()).is_some() {
+                    return Err(Diagnostic::error(format!("identifier `{}` is bound more than once in the same pattern", name))
+                        .with_code(codes::DUPLICATE_DEFINITION)
+                        .with_primary(pattern.span, "used in a pattern more than once"));
+                }
+                Ok(())
+            },
+            PatternKind::Literal(expr) => self.check_pattern_literal(expr, expected),
+            PatternKind::Range { start, end, inclusive } => {
+                if !matches!(expected, Type::Int(_) | Type::Char) {
+                    return Err(Diagnostic::error("only `char` and integer types are allowed in range patterns")
+                        .with_code(codes::INVALID_PATTERN)
+                        .with_primary(pattern.span, format!("the matched value is of type `{}`", expected)));
+                }
+                self.check_pattern_literal(start, expected)?;
+                self.check_pattern_literal(end, expected)?;
+                if exhaustiveness::is_empty_range(start, end, *inclusive, expected) {
+                    return Err(Diagnostic::error("range pattern is empty")
+                        .with_code(codes::INVALID_PATTERN)
+                        .with_primary(pattern.span, "lower bound must be less than the upper bound"));
+                }
+                Ok(())
+            },
+            PatternKind::Tuple(elements) => {
+                let Type::Tuple(element_types) = expected else {
+                    return Err(self.pattern_mismatch(pattern, expected, "tuple"));
+                };
+                if element_types.len() != elements.len() {
+                    return Err(Diagnostic::error("mismatched types")
+                        .with_code(codes::MISMATCHED_TYPES)
+                        .with_primary(pattern.span, format!(
+                            "expected a tuple with {} elements, found one with {} elements", element_types.len(), elements.len()
+                        )));
+                }
+                for (element, ty) in elements.iter().zip(element_types) {
+                    self.check_pattern(element, ty, bindings)?;
+                }
+                Ok(())
+            },
+            PatternKind::Struct { name, fields, rest } => {
+                let Type::Struct(expected_name, args) = expected else {
+                    return Err(self.pattern_mismatch(pattern, expected, name));
+                };
+                if expected_name != name {
+                    return Err(self.pattern_mismatch(pattern, expected, name));
+                }
+                let defs = self.struct_fields(name, args).unwrap_or_default();
+
+                let mut mentioned: HashMap<&str, Span> = HashMap::new();
+                for field in fields {
+                    let Some((_, field_type)) = defs.iter().find(|(def, _)| *def == field.name) else {
+                        return Err(Diagnostic::error(format!("struct `{}` does not have a field named `{}`", name, field.name))
+                            .with_code(codes::UNKNOWN_FIELD)
+                            .with_primary(field.span, "unknown field"));
+                    };
+                    if let Some(prev) = mentioned.insert(&field.name, field.span) {
+                        return Err(Diagnostic::error(format!("field `{}` bound multiple times in the pattern", field.name))
+                            .with_code(codes::DUPLICATE_DEFINITION)
+                            .with_primary(field.span, "multiple uses of field")
+                            .with_secondary(prev, "first use of field"));
+                    }
+                    self.check_pattern(&field.pattern, field_type, bindings)?;
+                }
+
+                let missing: Vec<String> = defs.iter()
+                    .filter(|(def, _)| !mentioned.contains_key(def.as_str()))
+                    .map(|(def, _)| format!("`{}`", def))
+                    .collect();
+                if !rest && !missing.is_empty() {
+                    return Err(Diagnostic::error(format!("pattern does not mention fields {}", missing.join(", ")))
+                        .with_code(codes::MISSING_FIELDS)
+                        .with_primary(pattern.span, "missing fields")
+                        .with_help("include the missing fields in the pattern, or ignore them with `..`"));
+                }
+                Ok(())
+            },
+            PatternKind::Variant { enum_name, variant, fields } => {
+                let Type::Enum(expected_name, args) = expected else {
+                    return Err(self.pattern_mismatch(pattern, expected, enum_name));
+                };
+                if expected_name != enum_name {
+                    return Err(self.pattern_mismatch(pattern, expected, enum_name));
+                }
+                let Some(field_types) = self.variant(enum_name, variant, args) else {
+                    return Err(Diagnostic::error(format!("no variant named `{}` in enum `{}`", variant, enum_name))
+                        .with_code(codes::UNDEFINED_NAME)
+                        .with_primary(pattern.span, "variant not found"));
+                };
+                if field_types.len() != fields.len() {
+                    return Err(Diagnostic::error(format!(
+                            "this pattern has {} fields, but the corresponding variant has {} fields", fields.len(), field_types.len()
+                        ))
+                        .with_code(codes::MISMATCHED_TYPES)
+                        .with_primary(pattern.span, format!("expected {} fields, found {}", field_types.len(), fields.len())));
+                }
+                for (field, ty) in fields.iter().zip(&field_types) {
+                    self.check_pattern(field, ty, bindings)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn check_pattern_literal(&mut self, expr: &Expr, expected: &Type) -> Result<(), Diagnostic> {
+        let lit_type = self.check_expr_expecting(expr, Some(expected))?;
+        if lit_type != *expected {
+            return Err(Diagnostic::error("mismatched types")
+                .with_code(codes::MISMATCHED_TYPES)
+                .with_primary(expr.span, format!("expected `{}`, found `{}`", expected, lit_type))
+                .with_note(format!("the matched value is of type `{}`", expected)));
+        }
+        Ok(())
+    }
+
+    fn pattern_mismatch(&self, pattern: &Pattern, expected: &Type, found: &str) -> Diagnostic {
+        Diagnostic::error("mismatched types")
+            .with_code(codes::MISMATCHED_TYPES)
+            .with_primary(pattern.span, format!("expected `{}`, found `{}`", expected, found))
+            .with_note(format!("the matched value is of type `{}`", expected))
+    }
+
+    // A típusjelölés hibáinak jelentése; a `parse_type` ilyenkor `Type::Error`-t ad
+    fn check_annotation(&mut self, annot: &TypeAnnot) {
+        if annot.is_dyn {
+            self.check_trait_object(annot);
+            return;
+        }
+        if annot.is_fn() || annot.is_array() {
+            for param in annot.args.iter().chain(annot.return_type.as_deref()) {
+                self.check_annotation(param);
+            }
+            return;
+        }
+        let is_self = annot.name == SELF_TYPE && self.self_type.is_some();
+        let generics = if primitive_type(&annot.name).is_some() || self.type_params.contains_key(&annot.name) || is_self {
+            Vec::new()
+        } else if let Some(generics) = self.type_generics.get(&annot.name) {
+            generics.clone()
+        } else {
+            let mut diag = Diagnostic::error(format!("cannot find type `{}` in this scope", annot.name))
+                .with_code(codes::UNDEFINED_NAME)
+                .with_primary(annot.span, "not found in this scope");
+            let types = self.type_generics.keys().chain(self.type_params.keys()).map(String::as_str);
+            if let Some(similar) = similar_name(&annot.name, types.chain(PRIMITIVE_NAMES.iter().copied())) {
+                diag = diag.with_help(format!("a type with a similar name exists: `{}`", similar));
+            }
+            self.diagnostics.push(diag);
+            return;
+        };
+
+        if annot.args.len() != generics.len() {
+            self.diagnostics.push(Diagnostic::error(format!(
+                    "type `{}` takes {} generic arguments but {} were supplied", annot.name, generics.len(), annot.args.len()
+                ))
+                .with_code(codes::WRONG_TYPE_ARGS)
+                .with_primary(annot.span, format!("expected {} generic arguments", generics.len())));
+            return;
+        }
+        for (arg, generic) in annot.args.iter().zip(&generics) {
+            self.check_annotation(arg);
+            let arg_type = self.parse_type(arg);
+            if let Some(bound) = generic.bounds.iter().find(|bound| !self.satisfies(&arg_type, bound)) {
+                let diag = self.unsatisfied_bound(&arg_type, bound, arg.span)
+                    .with_note(format!("required by the bound `{}: {}` of `{}`", generic.name, bound, annot.name));
+                self.diagnostics.push(diag);
+            }
+        }
+    }
+
+    // `dyn Trait` csak objektumbiztos traitre írható: minden metódusa `self`-en hívható,
+    // nem generikus, és a `Self` típust nem használja
+    fn check_trait_object(&mut self, annot: &TypeAnnot) {
+        let Some(info) = self.traits.get(&annot.name) else {
+            self.diagnostics.push(Diagnostic::error(format!("cannot find trait `{}` in this scope", annot.name))
+                .with_code(codes::UNDEFINED_NAME)
+                .with_primary(annot.span, "not found in this scope"));
+            return;
+        };
+        let violation = info.methods.iter().find_map(|(name, method)| object_safety_violation(method).map(|reason| (name, method.span, reason)));
+        if let Some((name, span, reason)) = violation {
+            let diag = Diagnostic::error(format!("the trait `{}` cannot be made into an object", annot.name))
+                .with_code(codes::NOT_OBJECT_SAFE)
+                .with_primary(annot.span, format!("`{}` cannot be made into an object", annot.name))
+                .with_secondary(span, format!("...because method `{}` {}", name, reason));
+            self.diagnostics.push(diag);
+        }
+    }
+
+    fn parse_type(&self, annot: &TypeAnnot) -> Type {
+        if annot.is_dyn {
+            return if self.traits.contains_key(&annot.name) { Type::Dyn(annot.name.clone()) } else { Type::Error };
+        }
+        if annot.is_fn() {
+            let params = annot.args.iter().map(|param| self.parse_type(param)).collect();
+            return Type::Function(params, Box::new(self.parse_return_type(&annot.return_type.as_deref().cloned())));
+        }
+        if annot.is_array() {
+            let element = Box::new(self.parse_type(&annot.args[0]));
+            return match annot.array_len {
+                Some(len) => Type::Array(element, len),
+                None => Type::Slice(element)
+            };
+        }
+        if let (SELF_TYPE, Some(self_type)) = (annot.name.as_str(), &self.self_type) {
+            return if annot.args.is_empty() { self_type.clone() } else { Type::Error };
+        }
+        if let Some(ty) = primitive_type(&annot.name) {
+            return if annot.args.is_empty() { ty } else { Type::Error };
+        }
+        if self.type_params.contains_key(&annot.name) {
+            return if annot.args.is_empty() { Type::Param(annot.name.clone()) } else { Type::Error };
+        }
+
+        let args: Vec<Type> = annot.args.iter().map(|arg| self.parse_type(arg)).collect();
+        match self.type_generics.get(&annot.name) {
+            Some(generics) if generics.len() != args.len() => Type::Error,
+            Some(_) if self.structs.contains_key(&annot.name) => Type::Struct(annot.name.clone(), args),
+            Some(_) => Type::Enum(annot.name.clone(), args),
+            None => Type::Error
+        }
+    }
+
+    fn parse_return_type(&self, annot: &Option<TypeAnnot>) -> Type {
+        match annot {
+            Some(annot) => self.parse_type(annot),
+            None => Type::Void
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::modules::Module;
+    use crate::parser::Parser;
+    use crate::source_map::SourceMap;
+    use crate::{prelude, resolver};
+
+    // A `main` lépései a típusellenőrzésig; az eredmény a típusellenőrző diagnosztikái
+    fn check(src: &str) -> Vec<Diagnostic> {
+        let mut source_map = SourceMap::new();
+        let file = source_map.add_file("teszt.dlang".to_string(), src.to_string());
+        let (program, diagnostics) = Parser::new(Lexer::new(&source_map.get(file).src, file)).parse_program();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        let (mut program, diagnostics) = resolver::resolve(vec![Module { path: Vec::new(), file, program }]);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+        let prelude_id = source_map.add_file("<prelude>".to_string(), prelude::SOURCE.to_string());
+        let (prelude, _) = Parser::new(Lexer::new(&source_map.get(prelude_id).src, prelude_id)).parse_program();
+        program.traits.splice(0..0, prelude.traits);
+        program.structs.splice(0..0, prelude.structs);
+        program.enums.splice(0..0, prelude.enums);
+        TypeChecker::new().with_prelude(prelude_id).check_program(&program)
+    }
+
+    // A diagnosztikák kódjai a jelentés sorrendjében
+    fn error_codes(src: &str) -> Vec<&'static str> {
+        check(src).iter().map(|diag| diag.code.unwrap_or_default()).collect()
+    }
+
+    #[test]
+    fn struct_literals_and_field_access() {
+        let src = "
+strukt Pont {
+    x: f64,
+    y: f64,
+}
+
+fv main() {
+    legyen a = Pont { x: 1.0 };
+    legyen b = Pont { x: 1.0, y: 2.0, z: 3.0 };
+    legyen c = Pont { x: 1.0, y: igaz };
+    legyen d = Pont { x: 1.0, y: 2.0 };
+    legyen e = d.z;
+    legyen f: f64 = d.x;
+}
+";
+        assert_eq!(error_codes(src), [codes::MISSING_FIELDS, codes::UNKNOWN_FIELD, codes::MISMATCHED_TYPES, codes::UNKNOWN_FIELD]);
+    }
+
+    #[test]
+    fn methods_and_associated_functions_resolve() {
+        let src = "
+strukt Pont {
+    x: f64,
+    y: f64,
+}
+
+impl Pont {
+    fv uj(x: f64, y: f64) -> Pont {
+        vissza Pont { x: x, y: y };
+    }
+
+    fv hossz(self) -> f64 {
+        vissza self.x * self.x + self.y * self.y;
+    }
+}
+
+fv main() {
+    legyen p = Pont::uj(3.0, 4.0);
+    legyen h = p.hossz();
+    legyen q = Pont::hossz(p);
+    legyen r = p.terulet();
+    legyen s = Pont::nincs();
+    legyen t = p.hossz(1.0);
+}
+";
+        assert_eq!(error_codes(src), [codes::UNKNOWN_METHOD, codes::UNKNOWN_METHOD, codes::WRONG_ARG_COUNT]);
+    }
+
+    #[test]
+    fn enum_constructors_and_matching() {
+        let src = "
+enum Alak {
+    Kor(f64),
+    Teglalap(f64, f64),
+}
+
+fv main() {
+    legyen a = Alak::Kor(1.0);
+    legyen k = Alak::Kor(1.0, 2.0);
+    legyen b = Alak::Haromszog;
+    legyen c: Alak = Alak::Teglalap(1.0, igaz);
+    legyen d = eset a {
+        Alak::Kor(r) => r,
+    };
+}
+";
+        assert_eq!(error_codes(src), [codes::WRONG_ARG_COUNT, codes::UNKNOWN_METHOD, codes::MISMATCHED_TYPES, codes::NON_EXHAUSTIVE_PATTERNS]);
+    }
+
+    #[test]
+    fn trait_impls_and_bounds_are_checked() {
+        let src = "
+vonas Alakzat {
+    fv terulet(self) -> f64;
+    fv leiras(self) -> str {
+        vissza \"alakzat\";
+    }
+}
+
+strukt Kor {
+    r: f64,
+}
+
+strukt Negyzet {
+    a: f64,
+}
+
+strukt Pont {
+    x: i32,
+}
+
+impl Alakzat for Kor {
+    fv terulet(self) -> f64 {
+        vissza self.r * self.r * 3.14;
+    }
+    fv kerulet(self) -> f64 {
+        vissza self.r * 6.28;
+    }
+}
+
+impl Alakzat for Negyzet {
+    fv leiras(self) -> str {
+        vissza \"negyzet\";
+    }
+}
+
+fv nagyobb<T: Alakzat>(a: T, b: T) -> f64 {
+    vissza a.terulet() + b.terulet();
+}
+
+fv main() {
+    legyen x = nagyobb(Pont { x: 1 }, Pont { x: 2 });
+}
+";
+        assert_eq!(error_codes(src), [codes::NOT_A_TRAIT_MEMBER, codes::MISSING_TRAIT_ITEMS, codes::UNSATISFIED_BOUND]);
+    }
+
+    #[test]
+    fn constant_evaluation_errors() {
+        let src = "
+konst T: u8 = 200 + 100;
+konst O: i32 = 10 / (5 - 5);
+konst C1: i32 = C2 + 1;
+konst C2: i32 = C1 + 1;
+konst R: i32 = f();
+konst M: i8 = -128 - 1;
+konst T: i32 = 1;
+
+fv f() -> i32 {
+    vissza 1;
+}
+
+fv main(x: i32) {
+    konst Y: i32 = x + 1;
+    konst Z: str = 5;
+}
+";
+        assert_eq!(error_codes(src), [
+            codes::DUPLICATE_DEFINITION,
+            codes::CONST_OVERFLOW,
+            codes::DIVISION_BY_ZERO,
+            codes::CONST_CYCLE,
+            codes::NOT_CONST,
+            codes::CONST_OVERFLOW,
+            codes::NOT_CONST,
+            codes::MISMATCHED_TYPES
+        ]);
+    }
+
+    #[test]
+    fn assignments_need_mutable_places() {
+        let src = "
+strukt Pont { x: i32, y: i32 }
+konst MAX: i32 = 10;
+
+fv f(a: i32) {
+    a = 2;
+}
+
+fv main() {
+    legyen x = 1;
+    x = 2;
+    legyen p = Pont { x: 1, y: 2 };
+    p.x = 3;
+    var felesleges = 4;
+    MAX = 3;
+    konst BELSO: i32 = 1;
+    BELSO = 2;
+    f(1) = 3;
+    var s = \"a\";
+    s += \"b\";
+    var t = 1;
+    t = \"szoveg\";
+    f = 2;
+}
+";
+        assert_eq!(error_codes(src), [
+            codes::ASSIGN_TO_IMMUTABLE,
+            codes::ASSIGN_TO_IMMUTABLE,
+            codes::ASSIGN_TO_IMMUTABLE,
+            codes::INVALID_ASSIGNMENT,
+            codes::INVALID_ASSIGNMENT,
+            codes::INVALID_ASSIGNMENT,
+            codes::INVALID_OPERANDS,
+            codes::MISMATCHED_TYPES,
+            codes::INVALID_ASSIGNMENT,
+            codes::UNUSED_MUT
+        ]);
+        let src = "
+strukt Pont { x: i32, y: i32 }
+
+fv main() {
+    var n = 0;
+    n = n + 1;
+    n += 2;
+    n *= 3;
+    var p = Pont { x: 1, y: 2 };
+    p.x = 5;
+    p.y -= 1;
+    legyen k = n + p.x;
+    szor i in 0..3 {
+        var s = i;
+        s /= 2;
+    }
+}
+";
+        assert!(check(src).is_empty(), "{:?}", check(src));
+    }
+
+    #[test]
+    fn scopes_shadowing_and_suggestions() {
+        let src = "
+fv main() {
+    legyen x = 1;
+    legyen x = \"szoveg\";
+    ha igaz {
+        legyen x = 2.5;
+        print(\"{x}\");
+    }
+    print(\"{x}\");
+}
+";
+        assert!(check(src).is_empty(), "{:?}", check(src));
+
+        let src = "
+fv osszeg(a: i32, a: i32) -> i32 {
+    vissza a;
+}
+
+fv main() {
+    legyen szamlalo = 1;
+    print(\"{szamlalu}\");
+    ha igaz {
+        legyen belso = 2;
+    }
+    print(\"{belso}\");
+    legyen g = |p: i32, p: i32| p;
+    legyen v: i33 = 0;
+    legyen s: Strng = 0;
+    osszek(1, 2);
+    szor i in 0..3 {}
+    print(\"{i}\");
+    konst M: i32 = szamlalo;
+}
+";
+        let diagnostics = check(src);
+        let found: Vec<_> = diagnostics.iter().map(|diag| diag.code.unwrap_or_default()).collect();
+        assert_eq!(found, [
+            codes::DUPLICATE_DEFINITION,
+            codes::UNDEFINED_NAME,
+            codes::UNDEFINED_NAME,
+            codes::DUPLICATE_DEFINITION,
+            codes::UNDEFINED_NAME,
+            codes::UNDEFINED_NAME,
+            codes::UNDEFINED_NAME,
+            codes::UNDEFINED_NAME,
+            codes::NOT_CONST
+        ]);
+        // Elgépelésnél a hasonló nevű elemet javasoljuk, a blokkból kilépett névnél nem
+        let help: Vec<_> = diagnostics.iter().map(|diag| diag.help.as_deref().unwrap_or_default()).collect();
+        assert!(help[1].contains("`szamlalo`"), "{:?}", help);
+        assert!(help[2].is_empty() && help[7].is_empty(), "{:?}", help);
+        assert!(help[4].contains("`i32`") && help[6].contains("`osszeg`"), "{:?}", help);
+    }
+
+    #[test]
+    fn numeric_widths_and_casts_are_checked() {
+        let src = "
+konst ROSSZ: u8 = 300 as u8;
+konst NEG: u32 = -1 as u32;
+konst X: i32 = 1000;
+konst Y: i8 = X as i8;
+
+strukt Pont { x: i32 }
+
+fv f(a: i64) -> i64 { vissza a; }
+
+fv fo() {
+    legyen a: i32 = 1;
+    legyen b: i64 = 2;
+    legyen c = a + b;
+    legyen d: i8 = a;
+    legyen e: u32 = 1.5;
+    legyen g = f(a);
+    legyen h = \"x\" as i32;
+    legyen i = a as char;
+    legyen j = a as bool;
+    legyen p = Pont { x: 1 } as i32;
+    legyen q = a as Szam;
+}
+";
+        assert_eq!(error_codes(src), [
+            codes::LITERAL_OUT_OF_RANGE,
+            codes::INVALID_OPERANDS,
+            codes::CONST_OVERFLOW,
+            codes::MISMATCHED_TYPES,
+            codes::MISMATCHED_TYPES,
+            codes::MISMATCHED_TYPES,
+            codes::MISMATCHED_TYPES,
+            codes::INVALID_CAST,
+            codes::INVALID_CAST,
+            codes::INVALID_CAST,
+            codes::INVALID_CAST,
+            codes::UNDEFINED_NAME
+        ]);
+    }
+
+    #[test]
+    fn try_operator_and_unused_results() {
+        let src = "
+fv oszt(a: i32, b: i32) -> Eredmeny<i32, str> {
+    ha b == 0 {
+        vissza Eredmeny::Hiba(\"division by zero\");
+    }
+    vissza Eredmeny::Rendben(a / b);
+}
+
+fv elso(t: [i32]) -> Opcio<i32> {
+    vissza Opcio::Nincs;
+}
+
+fv egesz(a: i32) -> i32 {
+    vissza oszt(a, 2)?;
+}
+
+fv opcioban(t: [i32]) -> Opcio<i32> {
+    vissza Opcio::Van(oszt(1, 2)?);
+}
+
+fv mas_hiba() -> Eredmeny<i32, i32> {
+    vissza Eredmeny::Rendben(oszt(1, 2)?);
+}
+
+fv nem_enum(x: i32) -> Opcio<i32> {
+    vissza Opcio::Van(x?);
+}
+
+fv lezarasban() -> Eredmeny<i32, str> {
+    legyen f = |x: i32| oszt(x, 1)?;
+    vissza Eredmeny::Rendben(1);
+}
+
+fv nincs_tipus() {
+    legyen x = oszt(1, 2)?;
+}
+
+fv fo() {
+    oszt(1, 0);
+    fajl_ir(\"a.txt\", \"b\");
+    legyen _ = oszt(1, 0);
+    elso([1][0..1]);
+}
+";
+        assert_eq!(error_codes(src), [
+            codes::INVALID_TRY,
+            codes::INVALID_TRY,
+            codes::INVALID_TRY,
+            codes::INVALID_TRY,
+            codes::INVALID_TRY,
+            codes::INVALID_TRY,
+            codes::UNUSED_RESULT,
+            codes::UNUSED_RESULT
+        ]);
+    }
+}